@@ -1,35 +1,199 @@
 use clap::Parser;
-use color_eyre::eyre::{Context, Result};
-use futures_util::SinkExt;
+use color_eyre::eyre::{eyre, Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use mbus::control::{FunctionCode, SND_NKE};
 use mbus::Frame;
 use mbus_codec::MbusCodec;
+use multiplexer::{heater_routes, slave_addresses, Bus, SlaveRoute};
+use std::fmt;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
 use tokio::signal;
 use tokio_serial::SerialPortBuilderExt;
-use tokio_util::codec::Decoder;
+use tokio_util::codec::{Decoder, Framed};
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, info, Level};
+use tracing::{debug, info, info_span, warn, Instrument, Level};
 use tracing_subscriber::FmtSubscriber;
 
 mod mbus_codec;
 mod multiplexer;
 
+trait Transport: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Transport for T {}
+
+type Port = Framed<Box<dyn Transport>, MbusCodec>;
+
+#[derive(Clone, Debug)]
+enum Endpoint {
+    Serial(String),
+    Tcp(String),
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endpoint::Serial(path) => write!(f, "{}", path),
+            Endpoint::Tcp(address) => write!(f, "tcp:{}", address),
+        }
+    }
+}
+
+impl From<&str> for Endpoint {
+    fn from(s: &str) -> Self {
+        match s.strip_prefix("tcp:") {
+            Some(address) => Endpoint::Tcp(address.to_string()),
+            None => Endpoint::Serial(s.to_string()),
+        }
+    }
+}
+
+fn parse_bus_endpoints(s: &str) -> Result<Vec<Endpoint>> {
+    let endpoints = s.split(',').map(Endpoint::from).collect::<Vec<_>>();
+    if endpoints.len() < 2 {
+        return Err(eyre!(
+            "expected at least two comma-separated endpoints (a master and a slave)"
+        ));
+    }
+    Ok(endpoints)
+}
+
+#[derive(Clone, Debug)]
+struct BusRoute {
+    bus: usize,
+    route: SlaveRoute,
+}
+
+fn parse_hex_u8(s: &str) -> Result<u8> {
+    u8::from_str_radix(s.trim_start_matches("0x"), 16)
+        .with_context(|| format!("invalid hex byte {:?}", s))
+}
+
+fn parse_index(s: &str) -> Result<usize> {
+    s.parse().with_context(|| format!("invalid index {:?}", s))
+}
+
+fn parse_function(s: &str) -> Result<FunctionCode> {
+    match s {
+        "nke" => Ok(FunctionCode::SndNke),
+        "snd_ud" => Ok(FunctionCode::SndUd),
+        "req_ud1" => Ok(FunctionCode::ReqUd1),
+        "req_ud2" => Ok(FunctionCode::ReqUd2),
+        _ => Err(eyre!(
+            "unknown function {:?}, expected one of nke, snd_ud, req_ud1 or req_ud2",
+            s
+        )),
+    }
+}
+
+fn parse_data(s: &str) -> Result<Vec<u8>> {
+    if !s.is_ascii() || !s.len().is_multiple_of(2) {
+        return Err(eyre!("invalid hex data {:?}", s));
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| parse_hex_u8(&s[i..i + 2]))
+        .collect()
+}
+
+fn parse_route(s: &str) -> Result<BusRoute> {
+    let (from, to) = s
+        .split_once('=')
+        .ok_or_else(|| eyre!("expected BUS:PORT:ADDRESS[:FUNCTION]=PORT:ADDRESS"))?;
+
+    let (bus, port, address, function) = match from.split(':').collect::<Vec<_>>()[..] {
+        [bus, port, address] => (bus, port, address, None),
+        [bus, port, address, function] => (bus, port, address, Some(function)),
+        _ => return Err(eyre!("expected BUS:PORT:ADDRESS[:FUNCTION] before '='")),
+    };
+    let (to_port, to_address) = to
+        .split_once(':')
+        .ok_or_else(|| eyre!("expected PORT:ADDRESS after '='"))?;
+
+    let mut route = SlaveRoute::forward(
+        parse_index(port)?,
+        parse_hex_u8(address)?,
+        parse_index(to_port)?,
+        parse_hex_u8(to_address)?,
+    );
+    if let Some(function) = function {
+        route = route.with_function(parse_function(function)?);
+    }
+
+    Ok(BusRoute {
+        bus: parse_index(bus)?,
+        route,
+    })
+}
+
+fn parse_ack(s: &str) -> Result<BusRoute> {
+    let (bus, port, address, function, data) = match s.split(':').collect::<Vec<_>>()[..] {
+        [bus, port, address, function] => (bus, port, address, function, None),
+        [bus, port, address, function, data] => (bus, port, address, function, Some(data)),
+        _ => return Err(eyre!("expected BUS:PORT:ADDRESS:FUNCTION[:DATA]")),
+    };
+
+    let mut route = SlaveRoute::acknowledge(
+        parse_index(port)?,
+        parse_hex_u8(address)?,
+        parse_function(function)?,
+    );
+    if let Some(data) = data {
+        route = route.with_data(parse_data(data)?);
+    }
+
+    Ok(BusRoute {
+        bus: parse_index(bus)?,
+        route,
+    })
+}
+
 #[derive(Parser, Debug)]
 #[command()]
 struct Args {
     #[arg(long, default_value = "info")]
     log_level: Level,
 
-    #[arg(long, value_name = "TTY", value_hint = clap::ValueHint::FilePath)]
-    tty_path_external_master: String,
+    /// Endpoints of a bus, each either a TTY path or tcp:HOST:PORT. Without any --route or
+    /// --ack for a bus, three endpoints are taken as MASTER,HEATER,WMBUSMETERS
+    #[arg(
+        long = "bus",
+        value_name = "PORT,PORT,...",
+        value_parser = parse_bus_endpoints,
+        required = true
+    )]
+    buses: Vec<Vec<Endpoint>>,
+
+    /// Forward frames for ADDRESS received on PORT of BUS to the slave on the other side
+    #[arg(
+        long = "route",
+        value_name = "BUS:PORT:ADDRESS[:FUNCTION]=PORT:ADDRESS",
+        value_parser = parse_route
+    )]
+    routes: Vec<BusRoute>,
+
+    /// Acknowledge frames for ADDRESS received on PORT of BUS without forwarding them
+    #[arg(
+        long = "ack",
+        value_name = "BUS:PORT:ADDRESS:FUNCTION[:DATA]",
+        value_parser = parse_ack
+    )]
+    acks: Vec<BusRoute>,
 
-    #[arg(long, value_name = "TTY", value_hint = clap::ValueHint::FilePath)]
-    tty_path_heater: String,
+    /// Baudrate of the serial ports [default: 2400]
+    #[arg(short, long)]
+    serial_baudrate: Option<u32>,
 
-    #[arg(long, value_name = "TTY", value_hint = clap::ValueHint::FilePath)]
-    tty_path_wmbusmeters: String,
+    /// Detect the baudrate of every port instead of using --serial-baudrate
+    #[arg(long)]
+    auto_baud: bool,
 
-    #[arg(short, long, default_value_t = 2400)]
-    serial_baudrate: u32,
+    #[arg(long, value_name = "MS", default_value_t = 3000)]
+    auto_baud_window_ms: u64,
+
+    #[arg(long, value_name = "MS", default_value_t = 2000)]
+    response_timeout_ms: u64,
 }
 
 fn open_serial(path: String, baudrate: u32) -> Result<tokio_serial::SerialStream> {
@@ -44,6 +208,166 @@ fn open_serial(path: String, baudrate: u32) -> Result<tokio_serial::SerialStream
     Ok(serial)
 }
 
+const DEFAULT_BAUDRATE: u32 = 2400;
+const AUTO_BAUD_RATES: [u32; 3] = [2400, 9600, 300];
+
+#[derive(Clone, Copy, Debug)]
+struct SerialSettings {
+    baudrate: Option<u32>,
+    auto_baud: Option<Duration>,
+}
+
+#[derive(Clone, Debug)]
+enum BaudProbe {
+    // wait for the master on the other side to talk to us
+    Listen,
+    // we are the master, so poke the slaves at these addresses one by one
+    SendNke(Vec<u8>),
+}
+
+fn framed<T: Transport + 'static>(transport: T) -> Port {
+    MbusCodec::default().framed(Box::new(transport))
+}
+
+/// Waits up to `window` for a valid frame on `port`, which is open at `baudrate`, skipping
+/// garbage in between.
+async fn listen_at(
+    mut port: Port,
+    path: &str,
+    baudrate: u32,
+    window: Duration,
+) -> Result<Option<(Port, Frame)>> {
+    let deadline = tokio::time::Instant::now() + window;
+    loop {
+        match tokio::time::timeout_at(deadline, port.next()).await {
+            Ok(Some(Ok(frame))) => return Ok(Some((port, frame.into()))),
+            Ok(Some(Err(err))) => {
+                // the codec gives up after an error, so start over with a fresh one
+                debug!("Invalid data on {} at baudrate {}: {}", path, baudrate, err);
+                port = framed(open_serial(path.to_string(), baudrate)?);
+            }
+            Ok(None) | Err(_) => return Ok(None),
+        }
+    }
+}
+
+/// Opens `endpoint`, detecting the baudrate of serial ports when auto baud is enabled. When
+/// listening, the frame the baudrate was detected with is returned so it can still be routed.
+async fn open_port(
+    endpoint: Endpoint,
+    serial: SerialSettings,
+    probe: &BaudProbe,
+) -> Result<(Port, Option<Frame>)> {
+    let path = match endpoint {
+        Endpoint::Tcp(address) => {
+            if serial.baudrate.is_some() || serial.auto_baud.is_some() {
+                warn!("Ignoring serial settings for TCP endpoint {}", address);
+            }
+
+            debug!("Connecting to {}", address);
+            return Ok((framed(TcpStream::connect(address).await?), None));
+        }
+        Endpoint::Serial(path) => path,
+    };
+
+    let window = match serial.auto_baud {
+        Some(window) => window,
+        None => {
+            let baudrate = serial.baudrate.unwrap_or(DEFAULT_BAUDRATE);
+            return Ok((framed(open_serial(path, baudrate)?), None));
+        }
+    };
+
+    for baudrate in AUTO_BAUD_RATES {
+        match probe {
+            BaudProbe::Listen => {
+                let port = framed(open_serial(path.clone(), baudrate)?);
+                if let Some((port, frame)) = listen_at(port, &path, baudrate, window).await? {
+                    info!(
+                        "Detected baudrate {} on {} (received {})",
+                        baudrate, path, frame
+                    );
+                    return Ok((port, Some(frame)));
+                }
+            }
+            BaudProbe::SendNke(addresses) => {
+                // a broadcast would make all slaves answer at once, so ask them one at a time
+                for &address in addresses {
+                    let mut port = framed(open_serial(path.clone(), baudrate)?);
+                    port.send(
+                        Frame::Short {
+                            control: SND_NKE,
+                            address,
+                        }
+                        .into(),
+                    )
+                    .await?;
+
+                    if let Some((port, frame)) = listen_at(port, &path, baudrate, window).await? {
+                        info!(
+                            "Detected baudrate {} on {} (slave {:#04X} answered {})",
+                            baudrate, path, address, frame
+                        );
+                        return Ok((port, None));
+                    }
+                }
+            }
+        }
+        debug!("No frame received on {} at baudrate {}", path, baudrate);
+    }
+
+    warn!("Could not detect the baudrate of {}", path);
+    Err(eyre!(
+        "no valid frame received on {} at any of the baudrates {:?}",
+        path,
+        AUTO_BAUD_RATES
+    ))
+}
+
+async fn open_bus(
+    endpoints: Vec<Endpoint>,
+    routes: Vec<SlaveRoute>,
+    serial: SerialSettings,
+) -> Result<Bus<Port>> {
+    let mut ports = Vec::with_capacity(endpoints.len());
+    let mut received = Vec::new();
+    for (index, endpoint) in endpoints.into_iter().enumerate() {
+        // only the ports we forward to have slaves behind them
+        let addresses = slave_addresses(&routes, index);
+        let probe = if addresses.is_empty() {
+            BaudProbe::Listen
+        } else {
+            BaudProbe::SendNke(addresses)
+        };
+
+        let (port, frame) = open_port(endpoint.clone(), serial, &probe)
+            .await
+            .with_context(|| format!("Failed to open port {} ({})", index, endpoint))?;
+        ports.push(port);
+        received.extend(frame.map(|frame| (index, frame)));
+    }
+
+    let mut bus = Bus::new(ports, routes)?;
+    for (port, frame) in received {
+        bus.replay(port, frame);
+    }
+    Ok(bus)
+}
+
+fn bus_routes(index: usize, ports: usize, configured: &[BusRoute]) -> Result<Vec<SlaveRoute>> {
+    let routes = configured
+        .iter()
+        .filter(|route| route.bus == index)
+        .map(|route| route.route.clone())
+        .collect::<Vec<_>>();
+
+    match (routes.is_empty(), ports) {
+        (false, _) => Ok(routes),
+        (true, 3) => Ok(heater_routes()),
+        (true, _) => Err(eyre!("no --route or --ack given for bus {}", index)),
+    }
+}
+
 fn spawn_sigint_watcher(token: CancellationToken) {
     debug!("Spawning SIGINT watcher");
     tokio::spawn(async move {
@@ -65,38 +389,86 @@ async fn main() -> Result<()> {
             .finish(),
     )?;
 
-    let external_master = open_serial(args.tty_path_external_master, args.serial_baudrate)
-        .with_context(|| "Failed to open external master port")?;
-    let heater = open_serial(args.tty_path_heater, args.serial_baudrate)
-        .with_context(|| "Failed to open heater port")?;
-    let wmbusmeters = open_serial(args.tty_path_wmbusmeters, args.serial_baudrate)
-        .with_context(|| "Failed to open wmbusmeters port")?;
-
-    let mut external_master = MbusCodec::default().framed(external_master);
-    let mut heater = MbusCodec::default().framed(heater);
-    let mut wmbusmeters = MbusCodec::default().framed(wmbusmeters);
+    let response_timeout = Duration::from_millis(args.response_timeout_ms);
+    let serial = SerialSettings {
+        baudrate: args.serial_baudrate,
+        auto_baud: args
+            .auto_baud
+            .then(|| Duration::from_millis(args.auto_baud_window_ms)),
+    };
     let token = CancellationToken::new();
 
     spawn_sigint_watcher(token.clone());
 
-    info!("Initializing all slaves");
-    heater
-        .send(Frame::Short {
-            control: 0x40,
-            address: 0x0,
-        })
-        .await?;
-
-    info!("Starting main loop");
-    while !token.is_cancelled() {
-        multiplexer::multiplex_single_op(
-            token.clone(),
-            &mut external_master,
-            &mut heater,
-            &mut wmbusmeters,
-        )
-        .await?;
+    let configured = args.routes.into_iter().chain(args.acks).collect::<Vec<_>>();
+    if let Some(route) = configured
+        .iter()
+        .find(|route| route.bus >= args.buses.len())
+    {
+        return Err(eyre!(
+            "route {:?} refers to unknown bus {}",
+            route.route,
+            route.bus
+        ));
+    }
+
+    let mut tasks = Vec::new();
+    for (index, endpoints) in args.buses.into_iter().enumerate() {
+        let routes = bus_routes(index, endpoints.len(), &configured)?;
+        let mut bus = open_bus(endpoints, routes, serial)
+            .await
+            .with_context(|| format!("Failed to open bus {}", index))?;
+
+        info!("Initializing all slaves on bus {}", index);
+        bus.initialize().await?;
+
+        let token = token.clone();
+        tasks.push(tokio::spawn(
+            async move {
+                info!("Starting main loop");
+                let result = bus.run(token.clone(), response_timeout).await;
+                if result.is_err() {
+                    // take the other buses down with us
+                    token.cancel();
+                }
+                result
+            }
+            .instrument(info_span!("bus", index)),
+        ));
+    }
+
+    for task in tasks {
+        task.await??;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_route() -> Result<()> {
+        let route = parse_route("1:2:fd:req_ud2=1:5a")?;
+        assert_eq!(route.bus, 1);
+        assert_eq!(
+            route.route,
+            SlaveRoute::forward(2, 0xFD, 1, 0x5A).with_function(FunctionCode::ReqUd2)
+        );
+
+        let route = parse_ack("0:2:0xfd:snd_ud:87932768ffffffff")?;
+        assert_eq!(route.bus, 0);
+        assert_eq!(
+            route.route,
+            SlaveRoute::acknowledge(2, 0xFD, FunctionCode::SndUd)
+                .with_data(vec![0x87, 0x93, 0x27, 0x68, 0xFF, 0xFF, 0xFF, 0xFF])
+        );
+
+        assert!(parse_route("0:0:5a").is_err());
+        assert!(parse_ack("0:0:5a:foo").is_err());
+        assert!(parse_ack("0:0:5a:snd_ud:123").is_err());
+
+        Ok(())
+    }
+}
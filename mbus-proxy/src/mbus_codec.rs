@@ -1,16 +1,18 @@
-use bytes::{Buf, BufMut, BytesMut};
-use mbus::{Frame, ParseError, ParseSizeNeeded};
+use bytes::BytesMut;
+use mbus::{BytesFrame, ParseError};
 use std::io::{Error, ErrorKind};
 use tokio_util::codec::{Decoder, Encoder};
-use tracing::trace;
+use tracing::{trace, warn};
 
 #[derive(Default)]
 pub struct MbusCodec {
     needed_bytes: usize,
+    #[cfg(test)]
+    parse_attempts: usize,
 }
 
 impl Decoder for MbusCodec {
-    type Item = Frame;
+    type Item = BytesFrame;
     type Error = Error;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
@@ -18,34 +20,132 @@ impl Decoder for MbusCodec {
             return Ok(None);
         }
 
-        match Frame::try_parse(src.chunk()) {
-            Ok((bytes_read, frame)) => {
-                trace!("Decoded frame {:?}", frame);
+        #[cfg(test)]
+        {
+            self.parse_attempts += 1;
+        }
+        // the user data of long frames is split off the buffer rather than copied
+        let (skipped, result) = BytesFrame::scan_from(src);
+        if skipped > 0 {
+            warn!("Skipping {} bytes of line noise", skipped);
+        }
+
+        match result {
+            Ok(frame) => {
+                trace!("Decoded frame {}", frame);
 
-                src.advance(bytes_read);
                 self.needed_bytes = 0;
                 Ok(Some(frame))
             }
-            Err(ParseError::Incomplete(ParseSizeNeeded::Size(min))) => {
-                self.needed_bytes = min.into();
+            Err(ParseError::Incomplete { needed }) => {
+                self.needed_bytes = src.len() + needed;
                 Ok(None)
             }
-            Err(ParseError::Incomplete(_)) => Ok(None),
             Err(err) => Err(Error::new(ErrorKind::InvalidData, err)),
         }
     }
 }
 
-impl Encoder<Frame> for MbusCodec {
+impl Encoder<BytesFrame> for MbusCodec {
     type Error = Error;
 
-    fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        trace!("Encoding frame {:?}", item);
+    fn encode(&mut self, item: BytesFrame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        trace!("Encoding frame {}", item);
+        item.validate()
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        dst.reserve(item.encoded_len());
+        item.write_to_buf(dst);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BufMut;
+    use mbus::Frame;
+
+    #[test]
+    fn test_decode_byte_by_byte() -> Result<(), Error> {
+        let bytes = b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16";
+        let mut codec = MbusCodec::default();
+        let mut src = BytesMut::new();
+        let mut frames = Vec::new();
+
+        for byte in bytes {
+            src.put_u8(*byte);
+            if let Some(frame) = codec.decode(&mut src)? {
+                frames.push(frame);
+            }
+        }
+
+        assert_eq!(frames, vec![Frame::from_bytes(bytes).unwrap().into()]);
+        // each byte of the header, then the complete frame
+        assert_eq!(codec.parse_attempts, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_without_copy() -> Result<(), Error> {
+        let mut codec = MbusCodec::default();
+        let mut src = BytesMut::from(&b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16"[..]);
+        let payload = src[7..10].as_ptr();
 
-        for byte in item.iter_bytes() {
-            dst.put_u8(byte);
+        match codec.decode(&mut src)? {
+            Some(BytesFrame::Long { data, .. }) => assert_eq!(data.as_ptr(), payload),
+            frame => panic!("expected a long frame, got {:?}", frame),
         }
 
         Ok(())
     }
+
+    #[test]
+    fn test_decode_after_stray_start_byte() -> Result<(), Error> {
+        let mut codec = MbusCodec::default();
+        let mut src = BytesMut::from(&b"\x68"[..]);
+        assert_eq!(codec.decode(&mut src)?, None);
+
+        src.put_u8(0xE5);
+        assert_eq!(codec.decode(&mut src)?, Some(BytesFrame::Single));
+        assert!(src.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_in_chunks() -> Result<(), Error> {
+        // the payload holds an ACK and a short frame, which must not be decoded on their own
+        let frame = Frame::Long {
+            control: 0x08,
+            address: 0x01,
+            control_information: 0x72,
+            data: vec![0xE5, 0x10, 0x7B, 0x49, 0xC4, 0x16],
+        };
+        let mut codec = MbusCodec::default();
+        let mut src = BytesMut::new();
+        let mut frames = Vec::new();
+        for chunk in frame.to_bytes().chunks(3) {
+            src.extend_from_slice(chunk);
+            frames.extend(codec.decode(&mut src)?);
+        }
+
+        assert_eq!(frames, vec![frame.into()]);
+        assert!(src.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_after_noise() -> Result<(), Error> {
+        let mut codec = MbusCodec::default();
+        let mut src = BytesMut::from(&b"\x00\x10\x7b\x49\xc5\x16\xe5\x68"[..]);
+
+        assert_eq!(codec.decode(&mut src)?, Some(BytesFrame::Single));
+        assert_eq!(codec.decode(&mut src)?, None);
+        assert_eq!(src.as_ref(), b"\x68");
+
+        Ok(())
+    }
 }
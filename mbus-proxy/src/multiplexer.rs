@@ -1,38 +1,282 @@
-use color_eyre::eyre::{Context, Result};
+use color_eyre::eyre::{eyre, Context, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::task::Poll;
 use std::time::Duration;
 
+use futures_util::future::poll_fn;
 use futures_util::stream::StreamExt;
-use futures_util::{FutureExt, Sink, SinkExt, Stream};
-use mbus::Frame;
+use futures_util::{Sink, SinkExt, Stream};
+use mbus::address::{is_valid_primary, BROADCAST_NO_REPLY, NETWORK_LAYER, UNCONFIGURED};
+use mbus::control::{ControlField, FunctionCode, Role};
+use mbus::telegram::Telegram;
+use mbus::{BytesFrame, Frame};
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 
-const SND_NKE: u8 = 0x40;
-const SND_UD: u8 = 0x73;
-const REQ_UD2: u8 = 0x7B;
+#[derive(Debug)]
+pub struct ResponseTimeout(pub Duration);
 
-async fn forward_frame<S>(frame: Frame, origin: &mut S, destination: &mut S) -> Result<()>
+impl fmt::Display for ResponseTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "destination did not respond within {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ResponseTimeout {}
+
+/// Keeps track of the frame count bit to use for the next request to each slave. The bit only
+/// toggles once a slave answered, so a request that is retried after a timeout keeps its FCB.
+#[derive(Debug, Default)]
+pub struct FcbTracker(HashMap<(usize, u8), bool>);
+
+impl FcbTracker {
+    fn next_fcb(&self, port: usize, address: u8) -> bool {
+        // the first request after a reset (SND_NKE) carries FCB=1
+        self.0.get(&(port, address)).copied().unwrap_or(true)
+    }
+
+    fn prepare(&self, port: usize, mut frame: Frame) -> Frame {
+        match &mut frame {
+            Frame::Short { control, address }
+            | Frame::Control {
+                control, address, ..
+            }
+            | Frame::Long {
+                control, address, ..
+            } => {
+                let field = ControlField::from(*control);
+                if field.fcv() {
+                    *control = field.with_fcb(self.next_fcb(port, *address)).bits();
+                }
+            }
+            Frame::Single => {}
+        }
+        frame
+    }
+
+    fn acknowledge(&mut self, port: usize, address: u8, control: ControlField) {
+        if control.function() == FunctionCode::SndNke {
+            self.0.remove(&(port, address));
+        } else if control.fcv() {
+            self.0
+                .insert((port, address), !self.next_fcb(port, address));
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RouteAction {
+    /// Answer with a single character acknowledgement, without involving any slave.
+    Acknowledge,
+    /// Forward the frame to the slave with `address` on `port` and relay its response.
+    Forward { port: usize, address: u8 },
+}
+
+/// Describes what to do with frames a master sends to `address` on `port`. Routes can be
+/// narrowed down to a function code and to the exact user data of the frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlaveRoute {
+    pub port: usize,
+    pub address: u8,
+    pub function: Option<FunctionCode>,
+    pub data: Option<Vec<u8>>,
+    pub action: RouteAction,
+}
+
+impl SlaveRoute {
+    pub fn acknowledge(port: usize, address: u8, function: FunctionCode) -> Self {
+        Self {
+            port,
+            address,
+            function: Some(function),
+            data: None,
+            action: RouteAction::Acknowledge,
+        }
+    }
+
+    pub fn forward(port: usize, address: u8, to_port: usize, to_address: u8) -> Self {
+        Self {
+            port,
+            address,
+            function: None,
+            data: None,
+            action: RouteAction::Forward {
+                port: to_port,
+                address: to_address,
+            },
+        }
+    }
+
+    pub fn with_function(mut self, function: FunctionCode) -> Self {
+        self.function = Some(function);
+        self
+    }
+
+    pub fn with_data(mut self, data: Vec<u8>) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    fn matches(&self, port: usize, frame: &Frame) -> bool {
+        let addressed = match frame.addressed() {
+            Some(addressed) => addressed,
+            None => return false,
+        };
+
+        self.port == port
+            && self.address == addressed.address()
+            && self.function.is_none_or(|function| {
+                ControlField::from(addressed.control()).function() == function
+            })
+            && self
+                .data
+                .as_ref()
+                .is_none_or(|data| data == addressed.data())
+    }
+}
+
+pub const EXTERNAL_MASTER_PORT: usize = 0;
+pub const HEATER_PORT: usize = 1;
+pub const WMBUSMETERS_PORT: usize = 2;
+
+/// Routes for a bus made of an external master, a heater and wmbusmeters (in that order). The
+/// heater is exposed as 0x5A to the external master and as 0xFD to wmbusmeters, which selects
+/// it by its secondary address first.
+pub fn heater_routes() -> Vec<SlaveRoute> {
+    vec![
+        SlaveRoute::acknowledge(
+            EXTERNAL_MASTER_PORT,
+            BROADCAST_NO_REPLY,
+            FunctionCode::SndNke,
+        ),
+        SlaveRoute::acknowledge(EXTERNAL_MASTER_PORT, 0x5A, FunctionCode::SndNke),
+        SlaveRoute::forward(EXTERNAL_MASTER_PORT, 0x5A, HEATER_PORT, 0x5A),
+        SlaveRoute::acknowledge(WMBUSMETERS_PORT, UNCONFIGURED, FunctionCode::SndNke),
+        SlaveRoute::acknowledge(WMBUSMETERS_PORT, NETWORK_LAYER, FunctionCode::SndNke),
+        SlaveRoute::acknowledge(WMBUSMETERS_PORT, NETWORK_LAYER, FunctionCode::SndUd)
+            .with_data(b"\x87\x93\x27\x68\xff\xff\xff\xff".to_vec()),
+        // only the short frames requesting data, like before routes existed
+        SlaveRoute::forward(WMBUSMETERS_PORT, NETWORK_LAYER, HEATER_PORT, 0x5A)
+            .with_function(FunctionCode::ReqUd1),
+        SlaveRoute::forward(WMBUSMETERS_PORT, NETWORK_LAYER, HEATER_PORT, 0x5A)
+            .with_function(FunctionCode::ReqUd2),
+    ]
+}
+
+/// Primary addresses of the slaves that frames get forwarded to on `port`.
+pub fn slave_addresses(routes: &[SlaveRoute], port: usize) -> Vec<u8> {
+    let mut addresses = routes
+        .iter()
+        .filter_map(|route| match route.action {
+            RouteAction::Forward {
+                port: destination,
+                address,
+            } if destination == port => Some(address),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    addresses.sort_unstable();
+    addresses.dedup();
+    addresses
+}
+
+fn set_address(frame: &mut Frame, new_address: u8) {
+    match frame {
+        Frame::Short { address, .. }
+        | Frame::Control { address, .. }
+        | Frame::Long { address, .. } => *address = new_address,
+        Frame::Single => {}
+    }
+}
+
+/// Returns mutable references to two distinct ports.
+fn port_pair<S>(ports: &mut [S], a: usize, b: usize) -> (&mut S, &mut S) {
+    assert_ne!(a, b, "a port cannot be routed to itself");
+    if a < b {
+        let (left, right) = ports.split_at_mut(b);
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = ports.split_at_mut(a);
+        (&mut right[0], &mut left[b])
+    }
+}
+
+async fn next_frame<S>(ports: &mut [S]) -> Option<(usize, std::io::Result<BytesFrame>)>
 where
-    S: Stream<Item = std::result::Result<Frame, std::io::Error>>
-        + Sink<Frame, Error = std::io::Error>
+    S: Stream<Item = std::result::Result<BytesFrame, std::io::Error>> + Unpin,
+{
+    poll_fn(|cx| {
+        let mut all_closed = true;
+        for (index, port) in ports.iter_mut().enumerate() {
+            match port.poll_next_unpin(cx) {
+                Poll::Ready(Some(result)) => return Poll::Ready(Some((index, result))),
+                Poll::Ready(None) => {}
+                Poll::Pending => all_closed = false,
+            }
+        }
+
+        if all_closed {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+async fn forward_frame<S>(
+    frame: Frame,
+    origin: &mut S,
+    destination: &mut S,
+    destination_port: usize,
+    response_timeout: Duration,
+    fcb: &mut FcbTracker,
+) -> Result<()>
+where
+    S: Stream<Item = std::result::Result<BytesFrame, std::io::Error>>
+        + Sink<BytesFrame, Error = std::io::Error>
         + Unpin,
 {
-    // forward to heater
-    debug!("Forwarding frame {:?} to destination", frame);
-    destination.send(frame).await?;
+    let addressed = frame.address().zip(frame.control_field());
+    let frame = fcb.prepare(destination_port, frame);
 
-    // read response or timeout after 50ms
-    let resp = tokio::time::timeout(
-        Duration::from_secs(2),
-        destination.next().map(|r| r.unwrap()),
-    )
-    .await??;
+    debug!("Forwarding frame {} to port {}", frame, destination_port);
+    destination.send(frame.into()).await?;
+
+    // read response or give up after the configured timeout
+    let resp = match tokio::time::timeout(response_timeout, destination.next()).await {
+        Ok(Some(resp)) => resp.with_context(|| "Failed reading response from destination")?,
+        Ok(None) => return Err(eyre!("destination closed before responding")),
+        Err(_) => {
+            warn!(
+                "No response received from port {} within {:?}",
+                destination_port, response_timeout
+            );
+            return Err(ResponseTimeout(response_timeout).into());
+        }
+    };
 
     debug!(
-        "Received response {:?} from destination, forwarding it to the origin",
-        resp
+        "Received response {} from port {}, forwarding it to the origin",
+        resp, destination_port
     );
 
+    // the response is relayed as received, only the request gets rewritten
+    if let Ok(telegram) = Telegram::from_frame_ref(resp.as_frame_ref()) {
+        debug!("Decoded response:\n{}", telegram);
+        if telegram.header.status.has_error() {
+            warn!(
+                "Meter {:08X} on port {} reports: {}",
+                telegram.header.identification, destination_port, telegram.header.status
+            );
+        }
+    }
+
+    if let Some((address, control)) = addressed {
+        fcb.acknowledge(destination_port, address, control);
+    }
+
     // reply
     origin.send(resp).await?;
 
@@ -41,80 +285,204 @@ where
 
 pub async fn multiplex_single_op<S>(
     token: CancellationToken,
-    external_master: &mut S,
-    heater: &mut S,
-    wmbusmeters: &mut S,
+    response_timeout: Duration,
+    fcb: &mut FcbTracker,
+    routes: &[SlaveRoute],
+    ports: &mut [S],
 ) -> Result<()>
 where
-    S: Stream<Item = std::result::Result<Frame, std::io::Error>>
-        + Sink<Frame, Error = std::io::Error>
+    S: Stream<Item = std::result::Result<BytesFrame, std::io::Error>>
+        + Sink<BytesFrame, Error = std::io::Error>
         + Unpin,
 {
-    tokio::select! {
+    let (port, result) = tokio::select! {
         biased;
 
-        Some(result) = external_master.next() => {
-            let frame = result.with_context(|| "Failed reading frame from external master")?;
-            debug!("Received frame {:?} from external master", frame);
+        Some(next) = next_frame(ports) => next,
+        _ = token.cancelled() => {
+            debug!("Cancellation token received, shutting down");
+            return Ok(());
+        }
+    };
+    let frame = result.with_context(|| format!("Failed reading frame from port {}", port))?;
+    debug!("Received frame {} from port {}", frame, port);
 
-            match frame {
-                Frame::Short { control, address } if control == SND_NKE && (address == 0xFF || address == 0x5A) => {
-                    external_master.send(Frame::Single).await?;
-                }
-                Frame::Short { address, .. } | Frame::Long { address, .. } | Frame::Control { address, .. } if address == 0x5A => {
-                    forward_frame(frame, external_master, heater).await?;
-                }
-                Frame::Short { .. } | Frame::Long { .. } | Frame::Control { .. } => {
-                    // ignore, this is not for us
-                    info!("Received frame from external master for a slave that we are not familiar with: {:?}", frame)
+    handle_frame(port, frame.into(), response_timeout, fcb, routes, ports).await
+}
+
+/// Acknowledges or forwards a frame received from `port` according to the first matching route.
+async fn handle_frame<S>(
+    port: usize,
+    mut frame: Frame,
+    response_timeout: Duration,
+    fcb: &mut FcbTracker,
+    routes: &[SlaveRoute],
+    ports: &mut [S],
+) -> Result<()>
+where
+    S: Stream<Item = std::result::Result<BytesFrame, std::io::Error>>
+        + Sink<BytesFrame, Error = std::io::Error>
+        + Unpin,
+{
+    match routes.iter().find(|route| route.matches(port, &frame)) {
+        Some(SlaveRoute {
+            action: RouteAction::Acknowledge,
+            ..
+        }) => {
+            ports[port].send(Frame::ack().into()).await?;
+        }
+        Some(SlaveRoute {
+            action:
+                RouteAction::Forward {
+                    port: destination_port,
+                    address,
                 },
-                _ => {
-                    error!("Received unexpected frame from external master: {:?}", frame);
-                }
-            }
+            ..
+        }) => {
+            set_address(&mut frame, *address);
+            let (origin, destination) = port_pair(ports, port, *destination_port);
+            forward_frame(
+                frame,
+                origin,
+                destination,
+                *destination_port,
+                response_timeout,
+                fcb,
+            )
+            .await?;
+        }
+        None if matches!(frame.role(), Role::MasterToSlave(_)) => {
+            // ignore, this is not for us
+            info!(
+                "Received frame from port {} for a slave that we are not familiar with: {}",
+                port, frame
+            )
         }
-        Some(result) = wmbusmeters.next() => {
-            let frame = result.with_context(|| "Failed reading frame from wmbusmeters")?;
-            debug!("Received frame {:?} from wmbusmeters", frame);
+        None => {
+            error!("Received unexpected frame from port {}: {}", port, frame);
+        }
+    }
 
-            match frame {
-                Frame::Short { control, address } if control == SND_NKE && (address == 0x0 || address == 0xFD) => {
-                    wmbusmeters.send(Frame::Single).await?;
-                }
-                Frame::Long { control, address, data, .. } if control == SND_UD && address == 0xFD && data == b"\x87\x93\x27\x68\xff\xff\xff\xff" => {
-                    wmbusmeters.send(Frame::Single).await?;
-                }
-                Frame::Short { control, address } if address == 0xFD => {
-                    forward_frame(Frame::Short {
-                        control,
-                        address: 0x5A,
-                    }, wmbusmeters, heater).await?;
-                },
-                _ => {
-                    error!("Received unexpected frame from wmbusmeters: {:?}", frame);
+    Ok(())
+}
+
+pub struct Bus<S> {
+    ports: Vec<S>,
+    routes: Vec<SlaveRoute>,
+    fcb: FcbTracker,
+    pending: Vec<(usize, Frame)>,
+}
+
+impl<S> Bus<S>
+where
+    S: Stream<Item = std::result::Result<BytesFrame, std::io::Error>>
+        + Sink<BytesFrame, Error = std::io::Error>
+        + Unpin,
+{
+    pub fn new(ports: Vec<S>, routes: Vec<SlaveRoute>) -> Result<Self> {
+        for route in &routes {
+            let destination = match route.action {
+                RouteAction::Forward { port, .. } => Some(port),
+                RouteAction::Acknowledge => None,
+            };
+            if let Some(port) = Some(route.port)
+                .into_iter()
+                .chain(destination)
+                .find(|port| *port >= ports.len())
+            {
+                return Err(eyre!("route {:?} refers to unknown port {}", route, port));
+            }
+            if destination == Some(route.port) {
+                return Err(eyre!("route {:?} forwards to its own port", route));
+            }
+            if let RouteAction::Forward { address, .. } = route.action {
+                if !is_valid_primary(address) {
+                    return Err(eyre!(
+                        "route {:?} forwards to {:#04X}, which is not a primary address",
+                        route,
+                        address
+                    ));
                 }
             }
         }
-        Some(result) = heater.next() => {
-            let frame = result.with_context(|| "Failed reading frame from heater")?;
 
-            error!("Received unexpected frame from heater: {:?}", frame);
-        }
-        _ = token.cancelled() => {
-            debug!("Cancellation token received, shutting down");
-            return Ok(());
+        Ok(Self {
+            ports,
+            routes,
+            fcb: FcbTracker::default(),
+            pending: Vec::new(),
+        })
+    }
+
+    /// Handles `frame` as if it was received from `port`, before anything read from the ports,
+    /// e.g. the frame the baudrate was detected with.
+    pub fn replay(&mut self, port: usize, frame: Frame) {
+        self.pending.push((port, frame));
+    }
+
+    /// Resets the link of every port that frames get forwarded to.
+    pub async fn initialize(&mut self) -> Result<()> {
+        let mut destinations = self
+            .routes
+            .iter()
+            .filter_map(|route| match route.action {
+                RouteAction::Forward { port, .. } => Some(port),
+                RouteAction::Acknowledge => None,
+            })
+            .collect::<Vec<_>>();
+        destinations.sort_unstable();
+        destinations.dedup();
+
+        for port in destinations {
+            self.ports[port]
+                .send(Frame::snd_nke(UNCONFIGURED).into())
+                .await?;
         }
+
+        Ok(())
     }
 
-    Ok(())
+    pub async fn run(mut self, token: CancellationToken, response_timeout: Duration) -> Result<()> {
+        for (port, frame) in std::mem::take(&mut self.pending) {
+            debug!("Replaying frame {} from port {}", frame, port);
+            handle_frame(
+                port,
+                frame,
+                response_timeout,
+                &mut self.fcb,
+                &self.routes,
+                &mut self.ports,
+            )
+            .await?;
+        }
+
+        while !token.is_cancelled() {
+            multiplex_single_op(
+                token.clone(),
+                response_timeout,
+                &mut self.fcb,
+                &self.routes,
+                &mut self.ports,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::mbus_codec::MbusCodec;
+    use mbus::control::SND_NKE;
     use tokio_util::codec::{Decoder, Framed};
 
+    const REQ_UD2: u8 = ControlField::new(FunctionCode::ReqUd2, true).bits();
+    const REQ_UD1: u8 = ControlField::new(FunctionCode::ReqUd1, true).bits();
+    const REQ_UD2_FCB_CLEARED: u8 = ControlField::new(FunctionCode::ReqUd2, false).bits();
+    const RESPONSE_TIMEOUT: Duration = Duration::from_millis(100);
+
     #[derive(Clone, Debug)]
     struct MockBuilder(tokio_test::io::Builder);
 
@@ -133,6 +501,11 @@ mod tests {
             self
         }
 
+        pub fn wait(&mut self, duration: Duration) -> &mut Self {
+            self.0.wait(duration);
+            self
+        }
+
         pub fn build(&mut self) -> Framed<tokio_test::io::Mock, MbusCodec> {
             MbusCodec::default().framed(self.0.build())
         }
@@ -140,33 +513,36 @@ mod tests {
 
     #[tokio::test]
     async fn test_master_send_nke() -> Result<()> {
-        let mut external_master = MockBuilder::new()
+        let external_master = MockBuilder::new()
             .read(Frame::Short {
                 control: SND_NKE,
-                address: 0x0,
+                address: 0x5A,
             })
             .write(Frame::Single)
             .build();
-        let mut heater = MockBuilder::new().build();
-        let mut wmbusmeter = MockBuilder::new().build();
+        let heater = MockBuilder::new().build();
+        let wmbusmeter = MockBuilder::new().build();
 
+        let mut ports = vec![external_master, heater, wmbusmeter];
+        let mut fcb = FcbTracker::default();
         multiplex_single_op(
             CancellationToken::new(),
-            &mut external_master,
-            &mut heater,
-            &mut wmbusmeter,
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
         )
         .await?;
-        assert!(external_master.next().await.is_none());
+        assert!(ports[EXTERNAL_MASTER_PORT].next().await.is_none());
 
         Ok(())
     }
 
     #[tokio::test]
     async fn test_wmbusmeter_send_nke() -> Result<()> {
-        let mut external_master = MockBuilder::new().build();
-        let mut heater = MockBuilder::new().build();
-        let mut wmbusmeter = MockBuilder::new()
+        let external_master = MockBuilder::new().build();
+        let heater = MockBuilder::new().build();
+        let wmbusmeter = MockBuilder::new()
             .read(Frame::Short {
                 control: SND_NKE,
                 address: 0x0,
@@ -174,21 +550,24 @@ mod tests {
             .write(Frame::Single)
             .build();
 
+        let mut ports = vec![external_master, heater, wmbusmeter];
+        let mut fcb = FcbTracker::default();
         multiplex_single_op(
             CancellationToken::new(),
-            &mut external_master,
-            &mut heater,
-            &mut wmbusmeter,
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
         )
         .await?;
-        assert!(wmbusmeter.next().await.is_none());
+        assert!(ports[WMBUSMETERS_PORT].next().await.is_none());
 
         Ok(())
     }
 
     #[tokio::test]
     async fn test_master_forward_req_ud2() -> Result<()> {
-        let mut external_master = MockBuilder::new()
+        let external_master = MockBuilder::new()
             .read(Frame::Short {
                 control: REQ_UD2,
                 address: 0x5A,
@@ -200,7 +579,7 @@ mod tests {
                 control_information: 0x00,
             })
             .build();
-        let mut heater = MockBuilder::new()
+        let heater = MockBuilder::new()
             .write(Frame::Short {
                 control: REQ_UD2,
                 address: 0x5A,
@@ -212,28 +591,150 @@ mod tests {
                 control_information: 0x00,
             })
             .build();
-        let mut wmbusmeter = MockBuilder::new().build();
+        let wmbusmeter = MockBuilder::new().build();
 
+        let mut ports = vec![external_master, heater, wmbusmeter];
+        let mut fcb = FcbTracker::default();
         multiplex_single_op(
             CancellationToken::new(),
-            &mut external_master,
-            &mut heater,
-            &mut wmbusmeter,
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
         )
         .await?;
-        assert!(heater.next().await.is_none());
-        assert!(external_master.next().await.is_none());
+        assert!(ports[HEATER_PORT].next().await.is_none());
+        assert!(ports[EXTERNAL_MASTER_PORT].next().await.is_none());
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_mux_two_req_ud2s() -> Result<()> {
-        let mut external_master = MockBuilder::new()
+    async fn test_wmbusmeter_forward_req_ud1() -> Result<()> {
+        let external_master = MockBuilder::new().build();
+        let heater = MockBuilder::new()
+            .write(Frame::Short {
+                control: REQ_UD1,
+                address: 0x5A,
+            })
+            .read(Frame::Single)
+            .build();
+        let wmbusmeter = MockBuilder::new()
             .read(Frame::Short {
+                control: REQ_UD1,
+                address: 0xFD,
+            })
+            .write(Frame::Single)
+            .read(Frame::Long {
+                control: 0x53,
+                address: 0xFD,
+                data: vec![0x01],
+                control_information: 0x51,
+            })
+            .build();
+
+        let mut ports = vec![external_master, heater, wmbusmeter];
+        let mut fcb = FcbTracker::default();
+        multiplex_single_op(
+            CancellationToken::new(),
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
+        )
+        .await?;
+        // long frames to the heater other than the selection are still not forwarded
+        multiplex_single_op(
+            CancellationToken::new(),
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
+        )
+        .await?;
+        assert!(ports[HEATER_PORT].next().await.is_none());
+        assert!(ports[WMBUSMETERS_PORT].next().await.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_master_forward_timeout() -> Result<()> {
+        let external_master = MockBuilder::new()
+            .read(Frame::Short {
+                control: REQ_UD2,
+                address: 0x5A,
+            })
+            .build();
+        let heater = MockBuilder::new()
+            .write(Frame::Short {
                 control: REQ_UD2,
                 address: 0x5A,
             })
+            .wait(Duration::from_secs(1))
+            .build();
+        let wmbusmeter = MockBuilder::new().build();
+
+        let mut ports = vec![external_master, heater, wmbusmeter];
+        let mut fcb = FcbTracker::default();
+        let result = multiplex_single_op(
+            CancellationToken::new(),
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
+        )
+        .await;
+        assert!(result.unwrap_err().is::<ResponseTimeout>());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_master_forward_destination_closed() -> Result<()> {
+        let external_master = MockBuilder::new()
+            .read(Frame::Short {
+                control: REQ_UD2,
+                address: 0x5A,
+            })
+            .build();
+        let heater = MockBuilder::new()
+            .write(Frame::Short {
+                control: REQ_UD2,
+                address: 0x5A,
+            })
+            .build();
+        let wmbusmeter = MockBuilder::new().build();
+
+        let mut ports = vec![external_master, heater, wmbusmeter];
+        let mut fcb = FcbTracker::default();
+        let result = multiplex_single_op(
+            CancellationToken::new(),
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
+        )
+        .await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "destination closed before responding"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fcb_kept_on_retry() -> Result<()> {
+        let external_master = MockBuilder::new()
+            .read(Frame::Short {
+                control: REQ_UD2,
+                address: 0x5A,
+            })
+            .read(Frame::Short {
+                control: REQ_UD2_FCB_CLEARED,
+                address: 0x5A,
+            })
             .write(Frame::Long {
                 control: 0x00,
                 address: 0x5A,
@@ -241,7 +742,12 @@ mod tests {
                 control_information: 0x00,
             })
             .build();
-        let mut heater = MockBuilder::new()
+        let heater = MockBuilder::new()
+            .write(Frame::Short {
+                control: REQ_UD2,
+                address: 0x5A,
+            })
+            .wait(RESPONSE_TIMEOUT * 2)
             .write(Frame::Short {
                 control: REQ_UD2,
                 address: 0x5A,
@@ -252,10 +758,63 @@ mod tests {
                 data: vec![0xCA, 0xFE, 0xBA, 0xBE],
                 control_information: 0x00,
             })
+            .build();
+        let wmbusmeter = MockBuilder::new().build();
+
+        let mut ports = vec![external_master, heater, wmbusmeter];
+        let mut fcb = FcbTracker::default();
+        let result = multiplex_single_op(
+            CancellationToken::new(),
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
+        )
+        .await;
+        assert!(result.unwrap_err().is::<ResponseTimeout>());
+        multiplex_single_op(
+            CancellationToken::new(),
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
+        )
+        .await?;
+        assert!(ports[HEATER_PORT].next().await.is_none());
+        assert!(ports[EXTERNAL_MASTER_PORT].next().await.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mux_two_req_ud2s() -> Result<()> {
+        let external_master = MockBuilder::new()
+            .read(Frame::Short {
+                control: REQ_UD2,
+                address: 0x5A,
+            })
+            .write(Frame::Long {
+                control: 0x00,
+                address: 0x5A,
+                data: vec![0xCA, 0xFE, 0xBA, 0xBE],
+                control_information: 0x00,
+            })
+            .build();
+        let heater = MockBuilder::new()
             .write(Frame::Short {
                 control: REQ_UD2,
                 address: 0x5A,
             })
+            .read(Frame::Long {
+                control: 0x00,
+                address: 0x5A,
+                data: vec![0xCA, 0xFE, 0xBA, 0xBE],
+                control_information: 0x00,
+            })
+            .write(Frame::Short {
+                control: REQ_UD2_FCB_CLEARED,
+                address: 0x5A,
+            })
             .read(Frame::Long {
                 control: 0x00,
                 address: 0x5A,
@@ -263,7 +822,7 @@ mod tests {
                 control_information: 0x01,
             })
             .build();
-        let mut wmbusmeter = MockBuilder::new()
+        let wmbusmeter = MockBuilder::new()
             .read(Frame::Short {
                 control: REQ_UD2,
                 address: 0xFD,
@@ -276,40 +835,213 @@ mod tests {
             })
             .build();
 
+        let mut ports = vec![external_master, heater, wmbusmeter];
+        let mut fcb = FcbTracker::default();
         multiplex_single_op(
             CancellationToken::new(),
-            &mut external_master,
-            &mut heater,
-            &mut wmbusmeter,
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
         )
         .await?;
         multiplex_single_op(
             CancellationToken::new(),
-            &mut external_master,
-            &mut heater,
-            &mut wmbusmeter,
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
         )
         .await?;
-        assert!(heater.next().await.is_none());
-        assert!(external_master.next().await.is_none());
-        assert!(wmbusmeter.next().await.is_none());
+        assert!(ports[HEATER_PORT].next().await.is_none());
+        assert!(ports[EXTERNAL_MASTER_PORT].next().await.is_none());
+        assert!(ports[WMBUSMETERS_PORT].next().await.is_none());
 
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_independent_buses() -> Result<()> {
+        let first = Bus::new(
+            vec![
+                MockBuilder::new()
+                    .read(Frame::Short {
+                        control: REQ_UD2,
+                        address: 0x5A,
+                    })
+                    .write(Frame::Long {
+                        control: 0x00,
+                        address: 0x5A,
+                        data: vec![0xCA, 0xFE, 0xBA, 0xBE],
+                        control_information: 0x00,
+                    })
+                    .build(),
+                MockBuilder::new()
+                    .write(Frame::Short {
+                        control: REQ_UD2,
+                        address: 0x5A,
+                    })
+                    .read(Frame::Long {
+                        control: 0x00,
+                        address: 0x5A,
+                        data: vec![0xCA, 0xFE, 0xBA, 0xBE],
+                        control_information: 0x00,
+                    })
+                    .build(),
+                MockBuilder::new().build(),
+            ],
+            heater_routes(),
+        )?;
+        let second = Bus::new(
+            vec![
+                MockBuilder::new().build(),
+                MockBuilder::new()
+                    .write(Frame::Short {
+                        control: REQ_UD2,
+                        address: 0x5A,
+                    })
+                    .read(Frame::Long {
+                        control: 0x00,
+                        address: 0x5A,
+                        data: vec![0x01, 0x02],
+                        control_information: 0x00,
+                    })
+                    .build(),
+                MockBuilder::new()
+                    .read(Frame::Short {
+                        control: REQ_UD2,
+                        address: 0xFD,
+                    })
+                    .write(Frame::Long {
+                        control: 0x00,
+                        address: 0x5A,
+                        data: vec![0x01, 0x02],
+                        control_information: 0x00,
+                    })
+                    .build(),
+            ],
+            heater_routes(),
+        )?;
+        let token = CancellationToken::new();
+
+        // the mocks assert on drop that every expected read and write happened
+        let (first, second, _) = tokio::join!(
+            first.run(token.clone(), RESPONSE_TIMEOUT),
+            second.run(token.clone(), RESPONSE_TIMEOUT),
+            async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                token.cancel();
+            }
+        );
+        first?;
+        second?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_custom_routes() -> Result<()> {
+        let master = MockBuilder::new()
+            .read(Frame::Short {
+                control: REQ_UD2,
+                address: 0x10,
+            })
+            .write(Frame::Long {
+                control: 0x00,
+                address: 0x10,
+                data: vec![0x01, 0x02],
+                control_information: 0x00,
+            })
+            .build();
+        let heater = MockBuilder::new().build();
+        let water_meter = MockBuilder::new()
+            .write(Frame::Short {
+                control: REQ_UD2,
+                address: 0x01,
+            })
+            .read(Frame::Long {
+                control: 0x00,
+                address: 0x10,
+                data: vec![0x01, 0x02],
+                control_information: 0x00,
+            })
+            .build();
+        let routes = vec![
+            SlaveRoute::forward(0, 0x5A, 1, 0x5A),
+            SlaveRoute::forward(0, 0x10, 2, 0x01),
+        ];
+
+        let mut ports = vec![master, heater, water_meter];
+        let mut fcb = FcbTracker::default();
+        multiplex_single_op(
+            CancellationToken::new(),
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &routes,
+            &mut ports,
+        )
+        .await?;
+        assert!(ports[2].next().await.is_none());
+        assert!(ports[0].next().await.is_none());
+
+        assert!(Bus::new(ports, vec![SlaveRoute::forward(0, 0x10, 3, 0x01)]).is_err());
+
+        let ports = vec![MockBuilder::new().build(), MockBuilder::new().build()];
+        assert!(Bus::new(ports, vec![SlaveRoute::forward(0, 0x10, 1, NETWORK_LAYER)]).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replay() -> Result<()> {
+        let external_master = MockBuilder::new().write(Frame::Single).build();
+        let heater = MockBuilder::new().build();
+        let wmbusmeter = MockBuilder::new().build();
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let mut bus = Bus::new(vec![external_master, heater, wmbusmeter], heater_routes())?;
+        bus.replay(
+            EXTERNAL_MASTER_PORT,
+            Frame::Short {
+                control: SND_NKE,
+                address: 0x5A,
+            },
+        );
+        // the replayed frame is handled even though the bus is stopped right away
+        bus.run(token, RESPONSE_TIMEOUT).await
+    }
+
+    #[test]
+    fn test_slave_addresses() {
+        let routes = vec![
+            SlaveRoute::forward(0, 0x10, 2, 0x01),
+            SlaveRoute::forward(0, 0x5A, 1, 0x5A),
+            SlaveRoute::forward(3, 0x5A, 1, 0x5A),
+            SlaveRoute::acknowledge(1, 0x20, FunctionCode::SndNke),
+        ];
+        assert_eq!(slave_addresses(&routes, 1), vec![0x5A]);
+        assert_eq!(slave_addresses(&routes, 2), vec![0x01]);
+        assert!(slave_addresses(&routes, 0).is_empty());
+        assert_eq!(slave_addresses(&heater_routes(), HEATER_PORT), vec![0x5A]);
+    }
+
     #[tokio::test]
     async fn test_cancel() -> Result<()> {
-        let mut external_master = MockBuilder::new().build();
-        let mut heater = MockBuilder::new().build();
-        let mut wmbusmeter = MockBuilder::new().build();
+        let external_master = MockBuilder::new().build();
+        let heater = MockBuilder::new().build();
+        let wmbusmeter = MockBuilder::new().build();
         let token = CancellationToken::new();
         token.cancel();
 
+        let mut ports = vec![external_master, heater, wmbusmeter];
+        let mut fcb = FcbTracker::default();
         multiplex_single_op(
             token.clone(),
-            &mut external_master,
-            &mut heater,
-            &mut wmbusmeter,
+            RESPONSE_TIMEOUT,
+            &mut fcb,
+            &heater_routes(),
+            &mut ports,
         )
         .await?;
 
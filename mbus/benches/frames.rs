@@ -0,0 +1,76 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mbus::{Frame, FrameRef};
+
+const LONG_FRAME_SIZES: [usize; 3] = [16, 64, 252];
+
+fn long_frame(length: usize) -> Frame {
+    Frame::rsp_ud(0x01, 0x72, (0..length).map(|i| i as u8).collect::<Vec<_>>())
+}
+
+/// What a master polling a few meters sees: requests, acknowledgements and responses.
+fn mixed_traffic() -> (Vec<u8>, u64) {
+    let frames = [
+        Frame::snd_nke(0x01),
+        Frame::ack(),
+        Frame::req_ud2(0x01, true),
+        long_frame(64),
+        Frame::req_ud2(0x02, true),
+        long_frame(16),
+        Frame::req_ud2(0x03, false),
+        long_frame(252),
+    ];
+    let mut bytes = Vec::new();
+    for _ in 0..32 {
+        for frame in &frames {
+            bytes.extend_from_slice(&frame.to_bytes());
+        }
+    }
+    (bytes, 32 * frames.len() as u64)
+}
+
+fn parse(c: &mut Criterion) {
+    let (bytes, count) = mixed_traffic();
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Elements(count));
+    group.bench_function("parse_all", |b| {
+        b.iter(|| Frame::parse_all(black_box(&bytes)))
+    });
+    group.bench_function("frame_ref", |b| {
+        b.iter(|| {
+            let mut rest = black_box(&bytes[..]);
+            while let Ok((bytes_read, frame)) = FrameRef::try_parse(rest) {
+                black_box(frame);
+                rest = &rest[bytes_read..];
+            }
+        })
+    });
+    group.finish();
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    group.throughput(Throughput::Elements(1));
+    for length in LONG_FRAME_SIZES {
+        let frame = long_frame(length);
+        group.bench_with_input(BenchmarkId::new("to_bytes", length), &frame, |b, frame| {
+            b.iter(|| black_box(frame).to_bytes())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("iter_bytes", length),
+            &frame,
+            |b, frame| b.iter(|| black_box(frame).iter_bytes().collect::<Vec<_>>()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("encode_into", length),
+            &frame,
+            |b, frame| {
+                let mut buf = [0u8; 512];
+                b.iter(|| black_box(frame).encode_into(&mut buf))
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse, encode);
+criterion_main!(benches);
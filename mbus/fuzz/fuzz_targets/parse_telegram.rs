@@ -0,0 +1,26 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mbus::telegram::{Records, Telegram};
+
+fuzz_target!(|data: &[u8]| {
+    // the lazy parser has to agree with the eager one, record by record
+    let eager = Telegram::parse(data);
+    if let Ok((_, records)) = Telegram::parse_lazy(data) {
+        let lazy = records.collect::<Result<Vec<_>, _>>();
+        match &eager {
+            Ok(telegram) => assert_eq!(lazy.as_ref(), Ok(&telegram.records)),
+            Err(err) => assert_eq!(lazy.as_ref().err(), Some(err)),
+        }
+    }
+    for record in Records::new(data).flatten() {
+        let _ = record.value();
+        let _ = record.normalized();
+    }
+
+    if let Ok(telegram) = eager {
+        let _ = telegram.to_string();
+        let bytes = telegram.to_bytes();
+        assert_eq!(Telegram::parse(&bytes), Ok(telegram));
+    }
+});
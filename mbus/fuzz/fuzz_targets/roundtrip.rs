@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mbus::Frame;
+
+fuzz_target!(|frame: Frame| {
+    // the encoder and the byte iterator have to produce the same bytes
+    let bytes = frame.to_bytes();
+    assert_eq!(frame.iter_bytes().collect::<Vec<_>>(), bytes);
+    assert_eq!(frame.encoded_len(), bytes.len());
+
+    assert_eq!(Frame::try_parse(&bytes), Ok((bytes.len(), frame)));
+});
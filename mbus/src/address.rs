@@ -0,0 +1,49 @@
+//! Primary addresses with a special meaning on the bus.
+
+/// Address of slaves that have not been configured yet.
+pub const UNCONFIGURED: u8 = 0x00;
+/// Highest address that can be assigned to a slave.
+pub const MAX_PRIMARY: u8 = 250;
+/// Addresses the slave selected by its secondary address.
+pub const NETWORK_LAYER: u8 = 0xFD;
+/// Broadcast to which all slaves reply, only usable with a single slave on the bus.
+pub const BROADCAST_WITH_REPLY: u8 = 0xFE;
+/// Broadcast to which no slave replies.
+pub const BROADCAST_NO_REPLY: u8 = 0xFF;
+
+pub const fn is_broadcast(address: u8) -> bool {
+    address == BROADCAST_WITH_REPLY || address == BROADCAST_NO_REPLY
+}
+
+pub const fn is_network_layer(address: u8) -> bool {
+    address == NETWORK_LAYER
+}
+
+/// Whether the address can be assigned to a slave, including the unconfigured address.
+pub const fn is_valid_primary(address: u8) -> bool {
+    address <= MAX_PRIMARY
+}
+
+/// Whether the slave at the address is expected to answer.
+pub const fn expects_reply(address: u8) -> bool {
+    address != BROADCAST_NO_REPLY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert!(is_valid_primary(UNCONFIGURED));
+        assert!(is_valid_primary(0x5A));
+        assert!(!is_valid_primary(251));
+        assert!(!is_valid_primary(NETWORK_LAYER));
+        assert!(is_network_layer(0xFD));
+        assert!(is_broadcast(0xFE));
+        assert!(is_broadcast(0xFF));
+        assert!(!is_broadcast(0xFD));
+        assert!(expects_reply(BROADCAST_WITH_REPLY));
+        assert!(!expects_reply(BROADCAST_NO_REPLY));
+    }
+}
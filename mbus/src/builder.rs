@@ -0,0 +1,183 @@
+use crate::control::{ControlField, FunctionCode};
+use crate::{Frame, FrameError, MAX_DATA_LENGTH};
+use alloc::vec::Vec;
+
+/// Builds addressed frames, picking the frame type from the fields that were set: a short frame
+/// without control information, a control frame without data and a long frame otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct FrameBuilder {
+    control: Option<ControlField>,
+    fcb: Option<bool>,
+    address: Option<u8>,
+    control_information: Option<u8>,
+    data: Vec<u8>,
+}
+
+impl FrameBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the control field, either as raw bits or as a function code, in which case the FCV
+    /// bit is derived from the function.
+    pub fn control(mut self, control: impl Into<ControlField>) -> Self {
+        self.control = Some(control.into());
+        self
+    }
+
+    /// Overrides the frame count bit of the control field.
+    pub fn fcb(mut self, fcb: bool) -> Self {
+        self.fcb = Some(fcb);
+        self
+    }
+
+    pub fn address(mut self, address: u8) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    pub fn control_information(mut self, control_information: u8) -> Self {
+        self.control_information = Some(control_information);
+        self
+    }
+
+    pub fn data(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.data = data.into();
+        self
+    }
+
+    pub fn build(self) -> Result<Frame, FrameError> {
+        let mut control = self.control.ok_or(FrameError::MissingControl)?;
+        let address = self.address.ok_or(FrameError::MissingAddress)?;
+        if let Some(fcb) = self.fcb {
+            if !control.fcv() {
+                return Err(FrameError::UnexpectedFcb);
+            }
+            control = control.with_fcb(fcb);
+        }
+
+        let function = control.function();
+        let carries_user_data = self.control_information.is_some() || !self.data.is_empty();
+        if carries_user_data
+            && matches!(
+                function,
+                FunctionCode::SndNke | FunctionCode::ReqUd1 | FunctionCode::ReqUd2
+            )
+        {
+            return Err(FrameError::UnexpectedUserData(function));
+        }
+
+        let control = control.bits();
+        match self.control_information {
+            None if self.data.is_empty() => Ok(Frame::Short { control, address }),
+            None => Err(FrameError::MissingControlInformation),
+            Some(control_information) if self.data.is_empty() => Ok(Frame::Control {
+                control,
+                address,
+                control_information,
+            }),
+            Some(_) if self.data.len() > MAX_DATA_LENGTH => {
+                Err(FrameError::DataTooLong(self.data.len()))
+            }
+            Some(control_information) => Ok(Frame::Long {
+                control,
+                address,
+                control_information,
+                data: self.data,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::{REQ_UD2, RSP_UD, SND_UD};
+    use alloc::vec;
+
+    #[test]
+    fn test_build() {
+        assert_eq!(
+            Frame::builder()
+                .control(FunctionCode::ReqUd2)
+                .fcb(true)
+                .address(0x5A)
+                .build(),
+            Ok(Frame::Short {
+                control: 0x7B,
+                address: 0x5A
+            })
+        );
+        assert_eq!(
+            Frame::builder()
+                .control(FunctionCode::SndUd)
+                .address(0x01)
+                .control_information(0x51)
+                .build(),
+            Ok(Frame::Control {
+                control: SND_UD,
+                address: 0x01,
+                control_information: 0x51
+            })
+        );
+        assert_eq!(
+            Frame::builder()
+                .control(RSP_UD)
+                .address(0x01)
+                .control_information(0x72)
+                .data([0x01, 0x02])
+                .build(),
+            Ok(Frame::Long {
+                control: RSP_UD,
+                address: 0x01,
+                control_information: 0x72,
+                data: vec![0x01, 0x02]
+            })
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(
+            Frame::builder().address(0x01).build(),
+            Err(FrameError::MissingControl)
+        );
+        assert_eq!(
+            Frame::builder().control(REQ_UD2).build(),
+            Err(FrameError::MissingAddress)
+        );
+        assert_eq!(
+            Frame::builder()
+                .control(FunctionCode::SndNke)
+                .fcb(true)
+                .address(0x01)
+                .build(),
+            Err(FrameError::UnexpectedFcb)
+        );
+        assert_eq!(
+            Frame::builder()
+                .control(REQ_UD2)
+                .address(0x01)
+                .control_information(0x72)
+                .build(),
+            Err(FrameError::UnexpectedUserData(FunctionCode::ReqUd2))
+        );
+        assert_eq!(
+            Frame::builder()
+                .control(SND_UD)
+                .address(0x01)
+                .data([0x00])
+                .build(),
+            Err(FrameError::MissingControlInformation)
+        );
+        assert_eq!(
+            Frame::builder()
+                .control(SND_UD)
+                .address(0x01)
+                .control_information(0x51)
+                .data(vec![0x00; MAX_DATA_LENGTH + 1])
+                .build(),
+            Err(FrameError::DataTooLong(MAX_DATA_LENGTH + 1))
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{encoder, Frame, FrameError, FrameRef, ParseError, MAX_DATA_LENGTH};
+use bytes::{BufMut, Bytes, BytesMut};
+use core::fmt;
+use core::ops::Range;
+
+/// Frame whose user data shares the buffer it was parsed from, for codecs that read into a
+/// [`BytesMut`] and would otherwise copy every payload into a fresh `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BytesFrame {
+    Single,
+    Short {
+        control: u8,
+        address: u8,
+    },
+    Control {
+        control: u8,
+        address: u8,
+        control_information: u8,
+    },
+    Long {
+        control: u8,
+        address: u8,
+        control_information: u8,
+        data: Bytes,
+    },
+}
+
+impl BytesFrame {
+    /// Parses the frame at the start of `src` and splits it off, without copying its data. On
+    /// error `src` is left untouched.
+    pub fn split_from(src: &mut BytesMut) -> Result<Self, ParseError> {
+        let (bytes_read, frame) = FrameRef::try_parse(src)?;
+        let (frame, data) = Self::detach(frame, src);
+        Ok(Self::split(src, 0, bytes_read, frame, data))
+    }
+
+    /// Like [`BytesFrame::split_from`], but skips line noise like [`Frame::scan`]. The skipped
+    /// bytes are dropped from `src` and counted in the returned number, also on error.
+    pub fn scan_from(src: &mut BytesMut) -> (usize, Result<Self, ParseError>) {
+        let (skipped, result) = FrameRef::scan(src);
+        match result {
+            Ok((bytes_read, frame)) => {
+                let (frame, data) = Self::detach(frame, src);
+                (
+                    skipped,
+                    Ok(Self::split(src, skipped, bytes_read, frame, data)),
+                )
+            }
+            Err(err) => {
+                let _ = src.split_to(skipped);
+                (skipped, Err(err))
+            }
+        }
+    }
+
+    /// The frame without its data, and where the data of a long frame is in `src`.
+    fn detach(frame: FrameRef<'_>, src: &[u8]) -> (Self, Option<Range<usize>>) {
+        match frame {
+            FrameRef::Single => (BytesFrame::Single, None),
+            FrameRef::Short { control, address } => (BytesFrame::Short { control, address }, None),
+            FrameRef::Control {
+                control,
+                address,
+                control_information,
+            } => (
+                BytesFrame::Control {
+                    control,
+                    address,
+                    control_information,
+                },
+                None,
+            ),
+            FrameRef::Long {
+                control,
+                address,
+                control_information,
+                data,
+            } => {
+                let start = data.as_ptr() as usize - src.as_ptr() as usize;
+                let frame = BytesFrame::Long {
+                    control,
+                    address,
+                    control_information,
+                    data: Bytes::new(),
+                };
+                (frame, Some(start..start + data.len()))
+            }
+        }
+    }
+
+    /// Drops `skipped` bytes from `src`, then splits off the `bytes_read` of the frame and
+    /// points the frame at its data in there.
+    fn split(
+        src: &mut BytesMut,
+        skipped: usize,
+        bytes_read: usize,
+        mut frame: Self,
+        data: Option<Range<usize>>,
+    ) -> Self {
+        let _ = src.split_to(skipped);
+        let bytes = src.split_to(bytes_read).freeze();
+        if let (BytesFrame::Long { data: payload, .. }, Some(range)) = (&mut frame, data) {
+            *payload = bytes.slice(range.start - skipped..range.end - skipped);
+        }
+        frame
+    }
+
+    /// Checks that the frame can be encoded, see [`Frame::validate`].
+    pub fn validate(&self) -> Result<(), FrameError> {
+        match self {
+            BytesFrame::Long { data, .. } if data.len() > MAX_DATA_LENGTH => {
+                Err(FrameError::DataTooLong(data.len()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Number of bytes the encoded frame takes on the wire.
+    pub fn encoded_len(&self) -> usize {
+        encoder::encoded_len(self.as_frame_ref())
+    }
+
+    /// Appends the encoded frame to `buf` without validating it and returns the number of bytes
+    /// written.
+    pub fn write_to_buf<B: BufMut>(&self, buf: &mut B) -> usize {
+        let _ = encoder::encode(self.as_frame_ref(), |chunk| {
+            buf.put_slice(chunk);
+            Ok::<_, core::convert::Infallible>(())
+        });
+        self.encoded_len()
+    }
+
+    pub fn as_frame_ref(&self) -> FrameRef<'_> {
+        match self {
+            BytesFrame::Single => FrameRef::Single,
+            BytesFrame::Short { control, address } => FrameRef::Short {
+                control: *control,
+                address: *address,
+            },
+            BytesFrame::Control {
+                control,
+                address,
+                control_information,
+            } => FrameRef::Control {
+                control: *control,
+                address: *address,
+                control_information: *control_information,
+            },
+            BytesFrame::Long {
+                control,
+                address,
+                control_information,
+                data,
+            } => FrameRef::Long {
+                control: *control,
+                address: *address,
+                control_information: *control_information,
+                data,
+            },
+        }
+    }
+}
+
+impl fmt::Display for BytesFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_frame_ref().fmt(f)
+    }
+}
+
+/// Takes over the user data of a long frame without copying it.
+impl From<Frame> for BytesFrame {
+    fn from(frame: Frame) -> Self {
+        match frame {
+            Frame::Single => BytesFrame::Single,
+            Frame::Short { control, address } => BytesFrame::Short { control, address },
+            Frame::Control {
+                control,
+                address,
+                control_information,
+            } => BytesFrame::Control {
+                control,
+                address,
+                control_information,
+            },
+            Frame::Long {
+                control,
+                address,
+                control_information,
+                data,
+            } => BytesFrame::Long {
+                control,
+                address,
+                control_information,
+                data: data.into(),
+            },
+        }
+    }
+}
+
+impl From<BytesFrame> for Frame {
+    fn from(frame: BytesFrame) -> Self {
+        frame.as_frame_ref().to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_from() {
+        let mut src =
+            BytesMut::from(&b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16\xE5\x10"[..]);
+        let payload = src[7..10].as_ptr();
+
+        let frame = BytesFrame::split_from(&mut src).unwrap();
+        match &frame {
+            BytesFrame::Long { data, .. } => {
+                assert_eq!(data.as_ref(), [0x01, 0x7A, 0x08]);
+                assert_eq!(data.as_ptr(), payload);
+            }
+            _ => panic!("expected a long frame, got {:?}", frame),
+        }
+        assert_eq!(
+            Frame::from(frame),
+            Frame::from_bytes(b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16").unwrap()
+        );
+
+        assert_eq!(BytesFrame::split_from(&mut src), Ok(BytesFrame::Single));
+        assert!(BytesFrame::split_from(&mut src).is_err());
+        assert_eq!(src.as_ref(), b"\x10");
+    }
+
+    #[test]
+    fn test_scan_from() {
+        let mut src = BytesMut::from(
+            &b"\x00\x68\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16\x68\x06"[..],
+        );
+        let payload = src[9..12].as_ptr();
+
+        let (skipped, frame) = BytesFrame::scan_from(&mut src);
+        assert_eq!(skipped, 2);
+        let frame = frame.unwrap();
+        match &frame {
+            BytesFrame::Long { data, .. } => assert_eq!(data.as_ptr(), payload),
+            _ => panic!("expected a long frame, got {:?}", frame),
+        }
+
+        let mut buf = BytesMut::new();
+        assert_eq!(frame.write_to_buf(&mut buf), frame.encoded_len());
+        assert_eq!(
+            buf.as_ref(),
+            b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16"
+        );
+        assert_eq!(BytesFrame::from(Frame::from(frame.clone())), frame);
+        assert_eq!(
+            frame.to_string(),
+            "LONG addr=0xFE ctrl=0x53 ci=0x51 data=01 7A 08"
+        );
+
+        assert_eq!(
+            BytesFrame::scan_from(&mut src),
+            (0, Err(ParseError::Incomplete { needed: 1 }))
+        );
+        assert_eq!(src.as_ref(), b"\x68\x06");
+    }
+}
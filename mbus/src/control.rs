@@ -0,0 +1,290 @@
+pub const SND_NKE: u8 = 0x40;
+pub const SND_UD: u8 = 0x53;
+pub const REQ_UD1: u8 = 0x5A;
+pub const REQ_UD2: u8 = 0x5B;
+pub const RSP_UD: u8 = 0x08;
+
+const PRM: u8 = 0x40;
+const FCB: u8 = 0x20;
+const FCV: u8 = 0x10;
+// slave frames reuse the FCB and FCV positions
+const ACD: u8 = 0x20;
+const DFC: u8 = 0x10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionCode {
+    SndNke,
+    SndUd,
+    ReqUd1,
+    ReqUd2,
+    RspUd,
+    Other(u8),
+}
+
+impl FunctionCode {
+    const fn bits(self) -> u8 {
+        match self {
+            FunctionCode::SndNke => SND_NKE,
+            FunctionCode::SndUd => SND_UD,
+            FunctionCode::ReqUd1 => REQ_UD1,
+            FunctionCode::ReqUd2 => REQ_UD2,
+            FunctionCode::RspUd => RSP_UD,
+            FunctionCode::Other(bits) => bits & !(FCB | FCV),
+        }
+    }
+
+    const fn uses_fcb(self) -> bool {
+        matches!(
+            self,
+            FunctionCode::SndUd | FunctionCode::ReqUd1 | FunctionCode::ReqUd2
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlField(u8);
+
+impl ControlField {
+    /// Builds the control byte for `function`. The FCV bit is set for the functions that take
+    /// part in frame counting, in which case `fcb` is used as the frame count bit.
+    pub const fn new(function: FunctionCode, fcb: bool) -> Self {
+        let bits = function.bits();
+        if function.uses_fcb() {
+            Self(bits | FCV | if fcb { FCB } else { 0 })
+        } else {
+            Self(bits)
+        }
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn function(self) -> FunctionCode {
+        const fn strip(bits: u8) -> u8 {
+            bits & !(FCB | FCV)
+        }
+
+        let bits = strip(self.0);
+        if bits == strip(SND_NKE) {
+            FunctionCode::SndNke
+        } else if bits == strip(SND_UD) {
+            FunctionCode::SndUd
+        } else if bits == strip(REQ_UD1) {
+            FunctionCode::ReqUd1
+        } else if bits == strip(REQ_UD2) {
+            FunctionCode::ReqUd2
+        } else if bits == strip(RSP_UD) {
+            FunctionCode::RspUd
+        } else {
+            FunctionCode::Other(bits)
+        }
+    }
+
+    /// Whether the frame was sent by the master (PRM bit).
+    pub const fn is_from_master(self) -> bool {
+        self.0 & PRM != 0
+    }
+
+    pub const fn fcb(self) -> bool {
+        self.is_from_master() && self.0 & FCB != 0
+    }
+
+    pub const fn fcv(self) -> bool {
+        self.is_from_master() && self.0 & FCV != 0
+    }
+
+    /// Access demand: the slave has class 1 data (e.g. an alarm) to send.
+    pub const fn acd(self) -> bool {
+        !self.is_from_master() && self.0 & ACD != 0
+    }
+
+    /// Data flow control: the slave cannot accept further data.
+    pub const fn dfc(self) -> bool {
+        !self.is_from_master() && self.0 & DFC != 0
+    }
+
+    /// The control field without the FCB and FCV bits of a master. The ACD and DFC bits of a
+    /// slave are kept.
+    pub const fn normalized(self) -> Self {
+        if self.is_from_master() {
+            Self(self.0 & !(FCB | FCV))
+        } else {
+            self
+        }
+    }
+
+    pub const fn with_fcb(self, fcb: bool) -> Self {
+        self.with_bit(FCB, fcb)
+    }
+
+    pub const fn with_acd(self, acd: bool) -> Self {
+        self.with_bit(ACD, acd)
+    }
+
+    pub const fn with_dfc(self, dfc: bool) -> Self {
+        self.with_bit(DFC, dfc)
+    }
+
+    /// What a frame with this control field means: a request of the master or a response of a
+    /// slave.
+    pub const fn role(self) -> Role {
+        let function = self.function();
+        if self.is_from_master() {
+            Role::MasterToSlave(match function {
+                FunctionCode::SndNke => Request::SndNke,
+                FunctionCode::SndUd => Request::SndUd,
+                FunctionCode::ReqUd1 => Request::ReqUd1,
+                FunctionCode::ReqUd2 => Request::ReqUd2,
+                _ => Request::Other(function.bits()),
+            })
+        } else {
+            Role::SlaveToMaster(match function {
+                FunctionCode::RspUd => Response::RspUd,
+                _ => Response::Other(function.bits()),
+            })
+        }
+    }
+
+    const fn with_bit(self, bit: u8, set: bool) -> Self {
+        if set {
+            Self(self.0 | bit)
+        } else {
+            Self(self.0 & !bit)
+        }
+    }
+}
+
+impl From<u8> for ControlField {
+    fn from(bits: u8) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<FunctionCode> for ControlField {
+    fn from(function: FunctionCode) -> Self {
+        Self::new(function, false)
+    }
+}
+
+impl From<ControlField> for u8 {
+    fn from(control: ControlField) -> Self {
+        control.0
+    }
+}
+
+/// Direction and meaning of a frame, see [`Frame::role`](crate::Frame::role).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    MasterToSlave(Request),
+    SlaveToMaster(Response),
+    /// The single character acknowledgement of a slave.
+    Ack,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Request {
+    SndNke,
+    SndUd,
+    ReqUd1,
+    ReqUd2,
+    /// Another function, without the FCB and FCV bits.
+    Other(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    RspUd,
+    /// Another function, without the ACD and DFC bits.
+    Other(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let control = ControlField::from(0x7B);
+        assert_eq!(control.function(), FunctionCode::ReqUd2);
+        assert!(control.is_from_master());
+        assert!(control.fcb());
+        assert!(control.fcv());
+
+        let control = ControlField::from(0x5B);
+        assert_eq!(control.function(), FunctionCode::ReqUd2);
+        assert!(!control.fcb());
+        assert!(control.fcv());
+
+        let control = ControlField::from(0x40);
+        assert_eq!(control.function(), FunctionCode::SndNke);
+        assert!(control.is_from_master());
+        assert!(!control.fcv());
+
+        let control = ControlField::from(0x73);
+        assert_eq!(control.function(), FunctionCode::SndUd);
+        assert!(control.fcb());
+        assert!(control.fcv());
+
+        let control = ControlField::from(0x08);
+        assert_eq!(control.function(), FunctionCode::RspUd);
+        assert!(!control.is_from_master());
+        assert!(!control.fcb());
+        assert!(!control.acd());
+        assert!(!control.dfc());
+
+        let control = ControlField::from(0x38);
+        assert!(control.acd());
+        assert!(control.dfc());
+        assert!(!control.fcb());
+        assert!(!control.fcv());
+        assert!(!ControlField::from(0x7B).acd());
+
+        assert_eq!(
+            ControlField::from(0x38).function(),
+            FunctionCode::RspUd,
+            "the ACD and DFC bits must not change the function"
+        );
+        assert_eq!(
+            ControlField::from(0x4C).function(),
+            FunctionCode::Other(0x4C)
+        );
+    }
+
+    #[test]
+    fn test_role() {
+        assert_eq!(
+            ControlField::from(0x7B).role(),
+            Role::MasterToSlave(Request::ReqUd2)
+        );
+        assert_eq!(
+            ControlField::from(0x38).role(),
+            Role::SlaveToMaster(Response::RspUd)
+        );
+        assert_eq!(
+            ControlField::from(0x4C).role(),
+            Role::MasterToSlave(Request::Other(0x4C))
+        );
+        assert_eq!(
+            ControlField::from(0x0B).role(),
+            Role::SlaveToMaster(Response::Other(0x0B))
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(ControlField::new(FunctionCode::SndNke, true).bits(), 0x40);
+        assert_eq!(ControlField::new(FunctionCode::ReqUd2, true).bits(), 0x7B);
+        assert_eq!(ControlField::new(FunctionCode::ReqUd2, false).bits(), 0x5B);
+        assert_eq!(ControlField::new(FunctionCode::SndUd, true).bits(), 0x73);
+        assert_eq!(ControlField::new(FunctionCode::RspUd, false).bits(), 0x08);
+        assert_eq!(ControlField::from(0x7B).with_fcb(false).bits(), 0x5B);
+        assert_eq!(
+            ControlField::new(FunctionCode::RspUd, false)
+                .with_acd(true)
+                .with_dfc(true)
+                .bits(),
+            0x38
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! CRC-16 of EN 13757 (polynomial 0x3D65, complemented), protecting the blocks of wireless
+//! M-Bus frames and some layers above them. Unlike the checksum of wired frames, it is sent
+//! most significant byte first after the block it covers.
+
+use alloc::vec::Vec;
+
+const CRC_LENGTH: usize = 2;
+
+pub fn calculate_crc<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u16 {
+    let mut crc: u16 = 0;
+    for b in bytes.into_iter() {
+        crc ^= u16::from(*b) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                crc << 1 ^ 0x3D65
+            } else {
+                crc << 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Checks a block followed by its CRC and returns the block without it, or `None` when the CRC
+/// does not match or is missing.
+pub fn verify_block(block: &[u8]) -> Option<&[u8]> {
+    let split = block.len().checked_sub(CRC_LENGTH)?;
+    let (block, crc) = block.split_at(split);
+    (calculate_crc(block).to_be_bytes() == crc).then_some(block)
+}
+
+/// Appends `block` followed by its CRC to `out`.
+pub fn append_block(out: &mut Vec<u8>, block: &[u8]) {
+    out.extend_from_slice(block);
+    out.extend_from_slice(&calculate_crc(block).to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_crc() {
+        assert_eq!(calculate_crc(b"123456789"), 0xC2B7);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let mut out = Vec::new();
+        append_block(&mut out, b"123456789");
+        assert_eq!(&out[9..], [0xC2, 0xB7]);
+        assert_eq!(verify_block(&out), Some(&b"123456789"[..]));
+        out[0] ^= 0x01;
+        assert_eq!(verify_block(&out), None);
+        assert_eq!(verify_block(&[0xFF]), None);
+    }
+}
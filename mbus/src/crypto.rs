@@ -0,0 +1,542 @@
+//! Decryption of user data encrypted by the meter (EN 13757-7, OMS), enabled by the `crypto`
+//! feature. The configuration field, sent as the signature of the [`Header`], tells the security
+//! mode and how many blocks of the data following the header are encrypted.
+//!
+//! Mode 5 uses the key of the meter as is. Mode 7 derives the keys from it and the message
+//! counter of the authentication and fragmentation layer, whose MAC authenticates the telegram.
+
+use crate::oms::{Configuration, SecurityProfile};
+use crate::telegram::{
+    Header, Telegram, TelegramError, CI_RESPONSE_SHORT_HEADER, CI_RESPONSE_VARIABLE,
+};
+use crate::wmbus::{AuthenticationLayer, DeviceAddress, WirelessError, WirelessFrame};
+use aes::cipher::{BlockDecrypt, KeyInit};
+use aes::Aes128;
+use alloc::vec::Vec;
+use cmac::{Cmac, Mac};
+use core::fmt;
+
+const BLOCK_LENGTH: usize = 16;
+/// Two idle filler DIFs starting the plaintext, telling that the key was right.
+const FILLER: [u8; 2] = [0x2F, 0x2F];
+
+/// Key derivation function A of EN 13757-7, selected in the configuration field extension.
+const KDF_A: u8 = 1;
+
+/// IV of mode 5: the address of the meter followed by the access number repeated 8 times.
+fn mode5_iv(header: &Header) -> [u8; BLOCK_LENGTH] {
+    let mut iv = [header.access_number; BLOCK_LENGTH];
+    iv[..2].copy_from_slice(&u16::from(header.manufacturer).to_le_bytes());
+    iv[2..6].copy_from_slice(&header.identification.to_le_bytes());
+    iv[6] = header.version;
+    iv[7] = header.medium.into();
+    iv
+}
+
+/// Key derived in mode 7, telling its use and the direction of the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DerivedKey {
+    EncryptionFromMeter,
+    MacFromMeter,
+    EncryptionToMeter,
+    MacToMeter,
+}
+
+impl From<DerivedKey> for u8 {
+    fn from(key: DerivedKey) -> Self {
+        match key {
+            DerivedKey::EncryptionFromMeter => 0x00,
+            DerivedKey::MacFromMeter => 0x01,
+            DerivedKey::EncryptionToMeter => 0x10,
+            DerivedKey::MacToMeter => 0x11,
+        }
+    }
+}
+
+/// Derives a key of mode 7 from the key of the meter with key derivation function A: a CMAC over
+/// the derivation constant, the message counter and the identification number of the meter.
+pub fn derive_key(
+    key: &[u8; 16],
+    derived: DerivedKey,
+    message_counter: u32,
+    identification: u32,
+) -> [u8; 16] {
+    // padded to a block with 0x07
+    let mut input = [0x07; BLOCK_LENGTH];
+    input[0] = derived.into();
+    input[1..5].copy_from_slice(&message_counter.to_le_bytes());
+    input[5..9].copy_from_slice(&identification.to_le_bytes());
+    let mut mac = <Cmac<Aes128> as KeyInit>::new(key.into());
+    mac.update(&input);
+    mac.finalize().into_bytes().into()
+}
+
+fn decrypt_cbc(key: &[u8; 16], iv: [u8; BLOCK_LENGTH], data: &mut [u8]) {
+    let cipher = Aes128::new(key.into());
+    let mut previous = iv;
+    for block in data.chunks_exact_mut(BLOCK_LENGTH) {
+        let ciphertext: [u8; BLOCK_LENGTH] = (*block).try_into().unwrap();
+        cipher.decrypt_block(block.into());
+        for (byte, chained) in block.iter_mut().zip(previous) {
+            *byte ^= chained;
+        }
+        previous = ciphertext;
+    }
+}
+
+/// Decrypts `data`, the user data following `header`, with `key`. Returns the plaintext, which
+/// still starts with the idle filler, followed by any data that was not encrypted. Data of
+/// unencrypted telegrams is returned as is.
+///
+/// Telegrams encrypted in mode 7 need the message counter, see [`decrypt_transport`].
+pub fn decrypt(key: &[u8; 16], header: &Header, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    decrypt_with(key, header, data, None)
+}
+
+/// Like [`decrypt`], also dropping the configuration field extension of mode 7.
+fn decrypt_with(
+    key: &[u8; 16],
+    header: &Header,
+    data: &[u8],
+    message_counter: Option<u32>,
+) -> Result<Vec<u8>, CryptoError> {
+    let configuration = Configuration::from(header.signature);
+    let length = BLOCK_LENGTH * usize::from(configuration.encrypted_blocks());
+    let (key, iv, data) = match configuration.security_profile() {
+        SecurityProfile::None => return Ok(data.to_vec()),
+        // AES-128 in CBC mode with a static key and an IV built from the header
+        SecurityProfile::A => (*key, mode5_iv(header), data),
+        // AES-128 in CBC mode with a zero IV and keys derived for every message
+        SecurityProfile::B => {
+            let (&extension, data) = data.split_first().ok_or(CryptoError::Truncated)?;
+            let kdf = (extension >> 4) & 0x03;
+            if kdf != KDF_A {
+                return Err(CryptoError::UnsupportedKeyDerivation(kdf));
+            }
+            let message_counter = message_counter.ok_or(CryptoError::MissingMessageCounter)?;
+            let key = derive_key(
+                key,
+                DerivedKey::EncryptionFromMeter,
+                message_counter,
+                header.identification,
+            );
+            (key, [0; BLOCK_LENGTH], data)
+        }
+        _ => return Err(CryptoError::UnsupportedMode(configuration.mode())),
+    };
+    if data.len() < length {
+        return Err(CryptoError::Truncated);
+    }
+
+    let mut plaintext = data.to_vec();
+    decrypt_cbc(&key, iv, &mut plaintext[..length]);
+    if !plaintext.starts_with(&FILLER) {
+        return Err(CryptoError::DecryptionFailed);
+    }
+    Ok(plaintext)
+}
+
+/// Decrypts and parses the user data of a frame with CI 0x72.
+pub fn decrypt_telegram(key: &[u8; 16], data: &[u8]) -> Result<Telegram, CryptoError> {
+    decrypt_telegram_with(key, data, None)
+}
+
+fn decrypt_telegram_with(
+    key: &[u8; 16],
+    data: &[u8],
+    message_counter: Option<u32>,
+) -> Result<Telegram, CryptoError> {
+    let (header, records) = Telegram::parse_lazy(data)?;
+    let mut plaintext = data[..data.len() - records.remaining().len()].to_vec();
+    plaintext.extend(decrypt_with(
+        key,
+        &header,
+        records.remaining(),
+        message_counter,
+    )?);
+    Ok(Telegram::parse(&plaintext)?)
+}
+
+/// Decrypts and parses the telegram carried by a wireless frame, either with a header of its
+/// own (CI 0x72) or with a short header (CI 0x7A), whose address is taken from the link layer.
+/// The telegram may follow an authentication and fragmentation layer, as long as the frame
+/// holds the whole message; reassemble fragments with a
+/// [`FragmentAssembler`](crate::wmbus::FragmentAssembler) and pass them to [`decrypt_message`].
+pub fn decrypt_wireless(
+    key: &[u8; 16],
+    frame: &WirelessFrame,
+) -> Result<DecryptedTelegram, CryptoError> {
+    let address = DeviceAddress {
+        manufacturer: frame.manufacturer,
+        identification: frame.identification,
+        version: frame.version,
+        medium: frame.medium,
+    };
+    if AuthenticationLayer::is_authentication_layer(frame.control_information) {
+        let (layer, message) = AuthenticationLayer::parse(frame.control_information, &frame.data)?;
+        if layer.fragment_control.more_fragments() {
+            return Err(CryptoError::Fragmented);
+        }
+        return decrypt_message(key, &address, &layer, message);
+    }
+    decrypt_transport(key, &address, frame.control_information, &frame.data, None)
+}
+
+/// Decrypts and parses a message following an authentication and fragmentation layer, starting
+/// with the CI field of the transport layer, checking its MAC if there is one.
+pub fn decrypt_message(
+    key: &[u8; 16],
+    address: &DeviceAddress,
+    layer: &AuthenticationLayer,
+    message: &[u8],
+) -> Result<DecryptedTelegram, CryptoError> {
+    let (&control_information, data) = message.split_first().ok_or(CryptoError::Truncated)?;
+    let fields = layer.authenticated_fields();
+    let authentication = layer
+        .message_counter
+        .map(|message_counter| MessageAuthentication {
+            message_counter,
+            fields: &fields,
+            mac: layer.mac.as_deref(),
+        });
+    decrypt_transport(
+        key,
+        address,
+        control_information,
+        data,
+        authentication.as_ref(),
+    )
+}
+
+/// Message counter and MAC of the authentication and fragmentation layer in front of the
+/// transport layer, needed for telegrams encrypted in mode 7.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageAuthentication<'a> {
+    pub message_counter: u32,
+    /// Fields of the layer covered by the MAC, see
+    /// [`AuthenticationLayer::authenticated_fields`].
+    pub fields: &'a [u8],
+    /// The MAC, possibly truncated, if the layer carries one.
+    pub mac: Option<&'a [u8]>,
+}
+
+/// Decrypts and parses the transport layer starting with `control_information`, either with a
+/// header of its own (CI 0x72) or with a short header (CI 0x7A), whose address is `address`.
+/// When `authentication` carries a MAC, it is checked against the transport layer.
+pub fn decrypt_transport(
+    key: &[u8; 16],
+    address: &DeviceAddress,
+    control_information: u8,
+    data: &[u8],
+    authentication: Option<&MessageAuthentication<'_>>,
+) -> Result<DecryptedTelegram, CryptoError> {
+    let message_counter = authentication.map(|authentication| authentication.message_counter);
+    let telegram = match control_information {
+        CI_RESPONSE_VARIABLE => decrypt_telegram_with(key, data, message_counter)?,
+        CI_RESPONSE_SHORT_HEADER => {
+            let mut long = Vec::with_capacity(8 + data.len());
+            long.extend_from_slice(&address.identification.to_le_bytes());
+            long.extend_from_slice(&u16::from(address.manufacturer).to_le_bytes());
+            long.push(address.version);
+            long.push(address.medium.into());
+            long.extend_from_slice(data);
+            decrypt_telegram_with(key, &long, message_counter)?
+        }
+        ci => return Err(TelegramError::UnsupportedControlInformation(ci).into()),
+    };
+
+    let authentication = match authentication {
+        Some(MessageAuthentication {
+            message_counter,
+            fields,
+            mac: Some(mac),
+        }) => {
+            let key = derive_key(
+                key,
+                DerivedKey::MacFromMeter,
+                *message_counter,
+                telegram.header.identification,
+            );
+            let mut expected = <Cmac<Aes128> as KeyInit>::new((&key).into());
+            expected.update(fields);
+            expected.update(&[control_information]);
+            expected.update(data);
+            expected
+                .verify_truncated_left(mac)
+                .map_err(|_| CryptoError::MacMismatch)?;
+            Authentication::Authenticated
+        }
+        _ => Authentication::Unauthenticated,
+    };
+    Ok(DecryptedTelegram {
+        telegram,
+        authentication,
+    })
+}
+
+/// Whether the origin of a decrypted telegram was verified with a MAC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Authentication {
+    Authenticated,
+    /// The telegram carried no MAC. Anyone knowing the key could have sent it.
+    Unauthenticated,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecryptedTelegram {
+    pub telegram: Telegram,
+    pub authentication: Authentication,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CryptoError {
+    /// The configuration field announces a security mode that is not supported.
+    UnsupportedMode(u8),
+    /// The data is shorter than the encrypted blocks announced by the configuration field.
+    Truncated,
+    /// The plaintext does not start with the idle filler, most likely the key is wrong.
+    DecryptionFailed,
+    /// The configuration field extension selects an unsupported key derivation function.
+    UnsupportedKeyDerivation(u8),
+    /// Mode 7 derives its keys from a message counter, which was not given.
+    MissingMessageCounter,
+    /// The frame holds a fragment of a longer message.
+    Fragmented,
+    /// The MAC does not match the telegram.
+    MacMismatch,
+    Wireless(WirelessError),
+    Telegram(TelegramError),
+}
+
+impl From<WirelessError> for CryptoError {
+    fn from(e: WirelessError) -> Self {
+        CryptoError::Wireless(e)
+    }
+}
+
+impl From<TelegramError> for CryptoError {
+    fn from(e: TelegramError) -> Self {
+        CryptoError::Telegram(e)
+    }
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::UnsupportedMode(mode) => {
+                write!(f, "unsupported security mode {}", mode)
+            }
+            CryptoError::Truncated => write!(f, "encrypted data is truncated"),
+            CryptoError::DecryptionFailed => {
+                write!(f, "decrypted data does not start with 0x2F 0x2F")
+            }
+            CryptoError::UnsupportedKeyDerivation(kdf) => {
+                write!(f, "unsupported key derivation function {}", kdf)
+            }
+            CryptoError::MissingMessageCounter => write!(f, "message counter is missing"),
+            CryptoError::MacMismatch => write!(f, "MAC does not match"),
+            CryptoError::Fragmented => write!(f, "frame holds a fragment of a message"),
+            CryptoError::Wireless(e) => write!(f, "{}", e),
+            CryptoError::Telegram(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CryptoError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::{ManufacturerId, Medium, Value};
+    use crate::wmbus::FragmentAssembler;
+    use aes::cipher::BlockEncrypt;
+    use alloc::vec;
+
+    const KEY: [u8; 16] = [
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+        0x11,
+    ];
+
+    fn encrypt_cbc(key: &[u8; 16], iv: [u8; BLOCK_LENGTH], data: &mut [u8]) {
+        let cipher = Aes128::new(key.into());
+        let mut previous = iv;
+        for block in data.chunks_exact_mut(BLOCK_LENGTH) {
+            for (byte, chained) in block.iter_mut().zip(previous) {
+                *byte ^= chained;
+            }
+            cipher.encrypt_block(block.into());
+            previous = (*block).try_into().unwrap();
+        }
+    }
+
+    fn wireless_frame(key: &[u8; 16]) -> WirelessFrame {
+        let header = Header {
+            identification: 0x12345678,
+            manufacturer: ManufacturerId::from(0x1593),
+            version: 0x33,
+            medium: Medium::Water,
+            access_number: 0x2A,
+            status: Default::default(),
+            signature: 0x0510,
+        };
+        let iv = mode5_iv(&header);
+        assert_eq!(
+            iv,
+            [
+                0x93, 0x15, 0x78, 0x56, 0x34, 0x12, 0x33, 0x07, 0x2A, 0x2A, 0x2A, 0x2A, 0x2A, 0x2A,
+                0x2A, 0x2A,
+            ]
+        );
+
+        // volume of 12.345 m³, padded with idle filler, followed by an unencrypted record
+        let mut data = vec![
+            0x2A, 0x00, 0x10, 0x05, 0x2F, 0x2F, 0x0C, 0x13, 0x45, 0x23, 0x01, 0x00, 0x2F, 0x2F,
+            0x2F, 0x2F, 0x2F, 0x2F, 0x2F, 0x2F, 0x2F, 0x2F, 0x01, 0xFD, 0x17, 0x00,
+        ];
+        encrypt_cbc(key, iv, &mut data[4..20]);
+        WirelessFrame {
+            control: 0x44,
+            manufacturer: header.manufacturer,
+            identification: header.identification,
+            version: header.version,
+            medium: header.medium,
+            control_information: CI_RESPONSE_SHORT_HEADER,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_decrypt_mode5() {
+        let decrypted = decrypt_wireless(&KEY, &wireless_frame(&KEY)).unwrap();
+        assert_eq!(decrypted.authentication, Authentication::Unauthenticated);
+        let telegram = decrypted.telegram;
+        assert_eq!(telegram.header.access_number, 0x2A);
+        assert_eq!(telegram.records.len(), 2);
+        assert_eq!(telegram.records[0].value(), Ok(Value::Bcd(12345)));
+
+        let mut key = KEY;
+        key[0] ^= 0x01;
+        assert_eq!(
+            decrypt_wireless(&key, &wireless_frame(&KEY)),
+            Err(CryptoError::DecryptionFailed)
+        );
+
+        let mut frame = wireless_frame(&KEY);
+        frame.data.truncate(10);
+        assert_eq!(decrypt_wireless(&KEY, &frame), Err(CryptoError::Truncated));
+        frame.data[3] = 0x06;
+        assert_eq!(
+            decrypt_wireless(&KEY, &frame),
+            Err(CryptoError::UnsupportedMode(6))
+        );
+    }
+
+    fn address() -> DeviceAddress {
+        DeviceAddress {
+            manufacturer: ManufacturerId::from(0x1593),
+            identification: 0x12345678,
+            version: 0x33,
+            medium: Medium::Water,
+        }
+    }
+    const MESSAGE_COUNTER: u32 = 0x0000_0102;
+    /// Message control with the message counter authenticated and an 8 byte MAC, and the
+    /// message counter.
+    const FIELDS: [u8; 5] = [0x25, 0x02, 0x01, 0x00, 0x00];
+
+    /// Short header encrypted in mode 7, followed by its MAC.
+    fn mode7_data() -> (Vec<u8>, Vec<u8>) {
+        // access number, status, configuration field with one block, KDF A
+        let mut data = vec![
+            0x2A, 0x00, 0x10, 0x07, 0x10, 0x2F, 0x2F, 0x0C, 0x13, 0x45, 0x23, 0x01, 0x00, 0x2F,
+            0x2F, 0x2F, 0x2F, 0x2F, 0x2F, 0x2F, 0x2F,
+        ];
+        let key = derive_key(
+            &KEY,
+            DerivedKey::EncryptionFromMeter,
+            MESSAGE_COUNTER,
+            address().identification,
+        );
+        encrypt_cbc(&key, [0; BLOCK_LENGTH], &mut data[5..]);
+        let key = derive_key(
+            &KEY,
+            DerivedKey::MacFromMeter,
+            MESSAGE_COUNTER,
+            address().identification,
+        );
+        let mut mac = <Cmac<Aes128> as KeyInit>::new((&key).into());
+        mac.update(&FIELDS);
+        mac.update(&[CI_RESPONSE_SHORT_HEADER]);
+        mac.update(&data);
+        let mac = mac.finalize().into_bytes()[..8].to_vec();
+        (data, mac)
+    }
+
+    #[test]
+    fn test_decrypt_mode7() {
+        let (data, mac) = mode7_data();
+        let decrypt = |mac| {
+            let authentication = MessageAuthentication {
+                message_counter: MESSAGE_COUNTER,
+                fields: &FIELDS,
+                mac,
+            };
+            decrypt_transport(
+                &KEY,
+                &address(),
+                CI_RESPONSE_SHORT_HEADER,
+                &data,
+                Some(&authentication),
+            )
+        };
+        let decrypted = decrypt(Some(&mac)).unwrap();
+        assert_eq!(decrypted.authentication, Authentication::Authenticated);
+        assert_eq!(decrypted.telegram.records[0].value(), Ok(Value::Bcd(12345)));
+        assert_eq!(
+            decrypt(None).unwrap().authentication,
+            Authentication::Unauthenticated
+        );
+        assert_eq!(decrypt(Some(&mac[1..])), Err(CryptoError::MacMismatch));
+        assert_eq!(
+            decrypt_transport(&KEY, &address(), CI_RESPONSE_SHORT_HEADER, &data, None),
+            Err(CryptoError::MissingMessageCounter)
+        );
+    }
+
+    #[test]
+    fn test_decrypt_fragments() {
+        let (data, mac) = mode7_data();
+        let (head, tail) = data.split_at(10);
+
+        // fragment 1 with MF, MCL, MCR and MAC, then fragment 2
+        let mut first = vec![0x0F, 0x01, 0x6C];
+        first.extend_from_slice(&FIELDS);
+        first.extend_from_slice(&mac);
+        first.push(CI_RESPONSE_SHORT_HEADER);
+        first.extend_from_slice(head);
+        let mut second = vec![0x02, 0x02, 0x00];
+        second.extend_from_slice(tail);
+
+        let frame = |data| WirelessFrame {
+            control: 0x44,
+            manufacturer: address().manufacturer,
+            identification: address().identification,
+            version: address().version,
+            medium: address().medium,
+            control_information: 0x90,
+            data,
+        };
+        assert_eq!(
+            decrypt_wireless(&KEY, &frame(first.clone())),
+            Err(CryptoError::Fragmented)
+        );
+
+        let mut assembler = FragmentAssembler::new();
+        assert_eq!(assembler.push(&first), Ok(None));
+        let (layer, message) = assembler.push(&second).unwrap().unwrap();
+        let decrypted = decrypt_message(&KEY, &address(), &layer, &message).unwrap();
+        assert_eq!(decrypted.authentication, Authentication::Authenticated);
+        assert_eq!(decrypted.telegram.records.len(), 1);
+    }
+}
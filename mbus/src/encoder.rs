@@ -0,0 +1,167 @@
+use crate::utils::calculate_checksum;
+use crate::*;
+
+/// Number of bytes `frame` takes on the wire.
+pub(crate) fn encoded_len(frame: FrameRef<'_>) -> usize {
+    match frame {
+        FrameRef::Single => 1,
+        FrameRef::Short { .. } => 5,
+        FrameRef::Control { .. } => 9,
+        FrameRef::Long { data, .. } => {
+            let (_, length_field_len) = length_field(data.len() + 3);
+            length_field_len + data.len() + 7
+        }
+    }
+}
+
+/// Encodes `frame` by handing its bytes to `put` in at most three chunks: everything up to the
+/// user data, the user data itself and the checksum with the stop byte.
+pub(crate) fn encode<E>(
+    frame: FrameRef<'_>,
+    mut put: impl FnMut(&[u8]) -> Result<(), E>,
+) -> Result<(), E> {
+    match &frame {
+        FrameRef::Single => put(&[SINGLE_CHAR]),
+        FrameRef::Short { control, address } => put(&[
+            SHORT_START,
+            *control,
+            *address,
+            calculate_checksum(&[*control, *address]),
+            FRAME_END,
+        ]),
+        FrameRef::Control {
+            control,
+            address,
+            control_information,
+        } => put(&[
+            LONG_START,
+            3,
+            3,
+            LONG_START,
+            *control,
+            *address,
+            *control_information,
+            calculate_checksum(&[*control, *address, *control_information]),
+            FRAME_END,
+        ]),
+        FrameRef::Long {
+            control,
+            address,
+            control_information,
+            data,
+        } => {
+            let (header, header_len) =
+                long_header(*control, *address, *control_information, data.len());
+            put(&header[..header_len])?;
+            put(data)?;
+            put(&long_trailer(
+                *control,
+                *address,
+                *control_information,
+                data,
+            ))
+        }
+    }
+}
+
+/// Like `to_bytes`, but encodes a long frame around its own user data allocation.
+pub(crate) fn into_bytes(frame: Frame) -> Vec<u8> {
+    match frame {
+        Frame::Long {
+            control,
+            address,
+            control_information,
+            mut data,
+        } => {
+            let (header, header_len) =
+                long_header(control, address, control_information, data.len());
+            let trailer = long_trailer(control, address, control_information, &data);
+            data.reserve_exact(header_len + trailer.len());
+            data.splice(0..0, header[..header_len].iter().copied());
+            data.extend_from_slice(&trailer);
+            data
+        }
+        frame => frame.to_bytes(),
+    }
+}
+
+fn long_header(
+    control: u8,
+    address: u8,
+    control_information: u8,
+    data_len: usize,
+) -> ([u8; 9], usize) {
+    let (length_field, length_field_len) = length_field(data_len + 3);
+    let mut header = [0u8; 9];
+    header[0] = LONG_START;
+    header[1..=length_field_len].copy_from_slice(&length_field[..length_field_len]);
+    header[length_field_len + 1..length_field_len + 5].copy_from_slice(&[
+        LONG_START,
+        control,
+        address,
+        control_information,
+    ]);
+    (header, length_field_len + 5)
+}
+
+fn long_trailer(control: u8, address: u8, control_information: u8, data: &[u8]) -> [u8; 2] {
+    let checksum = calculate_checksum(
+        [control, address, control_information]
+            .iter()
+            .chain(data.iter()),
+    );
+    [checksum, FRAME_END]
+}
+
+#[cfg(not(feature = "extended-length"))]
+pub(crate) fn length_field(length: usize) -> ([u8; 4], usize) {
+    ([length as u8, length as u8, 0, 0], 2)
+}
+
+#[cfg(feature = "extended-length")]
+pub(crate) fn length_field(length: usize) -> ([u8; 4], usize) {
+    if length > u8::MAX as usize {
+        let [lo, hi] = (length as u16).to_le_bytes();
+        ([EXTENDED_LENGTH, EXTENDED_LENGTH, lo, hi], 4)
+    } else {
+        ([length as u8, length as u8, 0, 0], 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_matches_iterator() {
+        let frames = [
+            Frame::Single,
+            Frame::Short {
+                control: 0x7B,
+                address: 0x49,
+            },
+            Frame::Control {
+                control: 0x53,
+                address: 0xFE,
+                control_information: 0xBD,
+            },
+            Frame::Long {
+                control: 0x53,
+                address: 0xFE,
+                control_information: 0x51,
+                data: (*b"\x01\x7A\x08").into(),
+            },
+        ];
+        for frame in frames {
+            let mut bytes = Vec::new();
+            encode(frame.as_frame_ref(), |chunk| {
+                bytes.extend_from_slice(chunk);
+                Ok::<_, core::convert::Infallible>(())
+            })
+            .unwrap();
+            assert_eq!(bytes, frame.iter_bytes().collect::<Vec<_>>());
+            assert_eq!(encoded_len(frame.as_frame_ref()), bytes.len());
+            assert_eq!(into_bytes(frame), bytes);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{parser, ChecksumStatus, Frame, ParseError, LONG_START, SHORT_START, SINGLE_CHAR};
+use core::fmt;
+use nom::Offset;
+
+/// Frame whose user data borrows from the buffer it was parsed from, so that parsing a long
+/// frame does not allocate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameRef<'a> {
+    Single,
+    Short {
+        control: u8,
+        address: u8,
+    },
+    Control {
+        control: u8,
+        address: u8,
+        control_information: u8,
+    },
+    Long {
+        control: u8,
+        address: u8,
+        control_information: u8,
+        data: &'a [u8],
+    },
+}
+
+impl<'a> FrameRef<'a> {
+    pub fn try_parse(bytes: &'a [u8]) -> Result<(usize, Self), crate::ParseError> {
+        let (ptr, frame) = parser::parse_frame(bytes)?;
+        Ok((bytes.offset(ptr), frame))
+    }
+
+    /// Like [`FrameRef::try_parse`], but returns frames with a checksum mismatch too, leaving it
+    /// to the caller whether to trust their contents.
+    pub fn try_parse_lenient(
+        bytes: &'a [u8],
+    ) -> Result<(usize, Self, ChecksumStatus), crate::ParseError> {
+        let (ptr, (frame, status)) = parser::parse_frame_lenient(bytes)?;
+        Ok((bytes.offset(ptr), frame, status))
+    }
+
+    /// Like [`FrameRef::try_parse`], but skips line noise, see [`Frame::scan`].
+    pub fn scan(bytes: &'a [u8]) -> (usize, Result<(usize, Self), ParseError>) {
+        let mut incomplete = None;
+        for (skipped, b) in bytes.iter().enumerate() {
+            if !matches!(*b, SINGLE_CHAR | SHORT_START | LONG_START) {
+                continue;
+            }
+            match Self::try_parse(&bytes[skipped..]) {
+                Ok(frame) => return (skipped, Ok(frame)),
+                Err(ParseError::Incomplete { .. }) => {
+                    let candidate = &bytes[skipped..];
+                    if parser::long_header_complete(candidate) {
+                        let needed =
+                            parser::frame_length_hint(candidate).saturating_sub(candidate.len());
+                        let err = ParseError::Incomplete {
+                            needed: needed.max(1),
+                        };
+                        return incomplete.unwrap_or((skipped, Err(err)));
+                    }
+                    incomplete.get_or_insert((skipped, Err(ParseError::Incomplete { needed: 1 })));
+                }
+                Err(_) => continue,
+            }
+        }
+        incomplete.unwrap_or((bytes.len(), Err(ParseError::Incomplete { needed: 1 })))
+    }
+
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, crate::ParseError> {
+        let (_, frame) = Self::try_parse(bytes)?;
+        Ok(frame)
+    }
+
+    /// Copies the user data into an owned [`Frame`].
+    pub fn to_owned(self) -> Frame {
+        match self {
+            FrameRef::Single => Frame::Single,
+            FrameRef::Short { control, address } => Frame::Short { control, address },
+            FrameRef::Control {
+                control,
+                address,
+                control_information,
+            } => Frame::Control {
+                control,
+                address,
+                control_information,
+            },
+            FrameRef::Long {
+                control,
+                address,
+                control_information,
+                data,
+            } => Frame::Long {
+                control,
+                address,
+                control_information,
+                data: data.into(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for FrameRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameRef::Single => write!(f, "SINGLE"),
+            FrameRef::Short { control, address } => {
+                write!(f, "SHORT addr=0x{:02X} ctrl=0x{:02X}", address, control)
+            }
+            FrameRef::Control {
+                control,
+                address,
+                control_information,
+            } => write!(
+                f,
+                "CONTROL addr=0x{:02X} ctrl=0x{:02X} ci=0x{:02X}",
+                address, control, control_information
+            ),
+            FrameRef::Long {
+                control,
+                address,
+                control_information,
+                data,
+            } => write!(
+                f,
+                "LONG addr=0x{:02X} ctrl=0x{:02X} ci=0x{:02X} data={:X}",
+                address,
+                control,
+                control_information,
+                crate::HexBytes(data)
+            ),
+        }
+    }
+}
+
+impl<'a> From<FrameRef<'a>> for Frame {
+    fn from(frame: FrameRef<'a>) -> Self {
+        frame.to_owned()
+    }
+}
+
+impl<'a> From<&'a Frame> for FrameRef<'a> {
+    fn from(frame: &'a Frame) -> Self {
+        frame.as_frame_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let bytes = b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16\xE5";
+        let (bytes_read, frame) = FrameRef::try_parse(bytes).unwrap();
+        assert_eq!(bytes_read, 12);
+        match frame {
+            FrameRef::Long { data, .. } => assert_eq!(data.as_ptr(), bytes[7..].as_ptr()),
+            _ => panic!("expected a long frame, got {:?}", frame),
+        }
+
+        let owned = frame.to_owned();
+        assert_eq!(owned, Frame::from_bytes(bytes).unwrap());
+        assert_eq!(owned.as_frame_ref(), frame);
+        assert_eq!(FrameRef::from_bytes(&bytes[12..]), Ok(FrameRef::Single));
+    }
+}
@@ -1,5 +1,7 @@
+use crate::encoder::length_field;
 use crate::utils::calculate_checksum;
 use crate::*;
+use core::iter::FusedIterator;
 
 pub struct FrameIterator<'a> {
     frame: &'a Frame,
@@ -62,36 +64,42 @@ impl<'a> Iterator for FrameIterator<'a> {
                 control_information,
                 data,
             } => {
+                let (length_field, length_field_len) = length_field(data.len() + 3);
                 let b = match self.index {
                     0 => LONG_START,
-                    1 | 2 => (data.len() + 3) as u8,
-                    3 => LONG_START,
-                    4 => *control,
-                    5 => *address,
-                    6 => *control_information,
-                    _ => {
-                        if self.index <= 6 + data.len() {
-                            data[self.index - 7]
-                        } else if self.index == 6 + data.len() + 1 {
-                            calculate_checksum(
-                                (&[*control, *address, *control_information])
-                                    .iter()
-                                    .chain(data.iter()),
-                            )
-                        } else if self.index == 6 + data.len() + 2 {
-                            FRAME_END
-                        } else {
-                            return None;
-                        }
-                    }
+                    i if i <= length_field_len => length_field[i - 1],
+                    i => match i - length_field_len {
+                        1 => LONG_START,
+                        2 => *control,
+                        3 => *address,
+                        4 => *control_information,
+                        i if i <= 4 + data.len() => data[i - 5],
+                        i if i == 4 + data.len() + 1 => calculate_checksum(
+                            [*control, *address, *control_information]
+                                .iter()
+                                .chain(data.iter()),
+                        ),
+                        i if i == 4 + data.len() + 2 => FRAME_END,
+                        _ => return None,
+                    },
                 };
                 self.index += 1;
                 Some(b)
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.frame.encoded_len().saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
 }
 
+impl ExactSizeIterator for FrameIterator<'_> {}
+
+// `index` never moves past the end of the frame
+impl FusedIterator for FrameIterator<'_> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,4 +135,38 @@ mod tests {
             b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16",
         );
     }
+
+    #[test]
+    fn test_exact_size() {
+        let frame = Frame::Long {
+            address: 0xFE,
+            control: 0x53,
+            control_information: 0x51,
+            data: (*b"\x01\x7A\x08").into(),
+        };
+        let mut iter = frame.iter_bytes();
+        assert_eq!(iter.len(), 12);
+        iter.nth(4);
+        assert_eq!(iter.size_hint(), (7, Some(7)));
+        assert_eq!(iter.by_ref().count(), 7);
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[cfg(feature = "extended-length")]
+    #[test]
+    fn test_iterator_extended_length() {
+        use alloc::vec;
+
+        let bytes = Frame::Long {
+            address: 0xFE,
+            control: 0x53,
+            control_information: 0x51,
+            data: vec![0x01; 300],
+        }
+        .to_bytes();
+        assert_eq!(bytes.len(), 6 + 3 + 300 + 2);
+        assert_eq!(&bytes[..9], b"\x68\xFF\xFF\x2F\x01\x68\x53\xFE\x51");
+        assert_eq!(&bytes[bytes.len() - 2..], b"\xCE\x16");
+    }
 }
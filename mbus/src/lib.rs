@@ -1,11 +1,43 @@
-use nom::Offset;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
 
 const SINGLE_CHAR: u8 = 0xE5;
 const SHORT_START: u8 = 0x10;
 const LONG_START: u8 = 0x68;
 const FRAME_END: u8 = 0x16;
 
+/// When both L-field bytes of a long frame carry this value, they are followed by the real
+/// length encoded as a 16-bit little-endian integer: `68 FF FF <lo> <hi> 68 C A CI ... CS 16`.
+/// Only lengths above 255 are encoded this way, smaller ones are rejected. As `68 FF FF 68` also
+/// starts a standard frame with 252 bytes of user data, such a frame is parsed as standard when
+/// its checksum matches.
+#[cfg(feature = "extended-length")]
+const EXTENDED_LENGTH: u8 = 0xFF;
+
+#[cfg(not(feature = "extended-length"))]
+const MAX_LENGTH: usize = u8::MAX as usize;
+#[cfg(feature = "extended-length")]
+const MAX_LENGTH: usize = u16::MAX as usize;
+
+/// Maximum number of user data bytes a long frame can carry.
+pub const MAX_DATA_LENGTH: usize = MAX_LENGTH - 3;
+
+/// With the `serde` feature, frames are serialized as maps tagged with their `type` (`single`,
+/// `short`, `control` or `long`) and the data of long frames as a hex string.
+///
+/// Long frames own their data in a `Vec`. To handle frames without allocating, parse them as a
+/// [`FrameRef`] borrowing the input or, with the `bytes` feature, as a `BytesFrame` sharing
+/// the read buffer.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
 pub enum Frame {
     Single,
     Short {
@@ -21,34 +53,564 @@ pub enum Frame {
         control: u8,
         address: u8,
         control_information: u8,
+        #[cfg_attr(feature = "serde", serde(with = "serde_hex"))]
         data: Vec<u8>,
     },
 }
 
 impl Frame {
-    pub fn try_parse<B: AsRef<[u8]>>(bytes: B) -> Result<(usize, Self), parser::ParseError> {
+    pub fn builder() -> FrameBuilder {
+        FrameBuilder::new()
+    }
+
+    /// Short frame, validated like [`FrameBuilder::build`].
+    pub fn short(
+        control: impl Into<control::ControlField>,
+        address: u8,
+    ) -> Result<Self, FrameError> {
+        Self::builder().control(control).address(address).build()
+    }
+
+    /// Control frame, validated like [`FrameBuilder::build`].
+    pub fn control(
+        control: impl Into<control::ControlField>,
+        address: u8,
+        control_information: u8,
+    ) -> Result<Self, FrameError> {
+        Self::builder()
+            .control(control)
+            .address(address)
+            .control_information(control_information)
+            .build()
+    }
+
+    /// Long frame, validated like [`FrameBuilder::build`]. Without data this would be a control
+    /// frame, so `data` must not be empty.
+    pub fn long(
+        control: impl Into<control::ControlField>,
+        address: u8,
+        control_information: u8,
+        data: impl Into<Vec<u8>>,
+    ) -> Result<Self, FrameError> {
+        let data = data.into();
+        if data.is_empty() {
+            return Err(FrameError::MissingData);
+        }
+        Self::builder()
+            .control(control)
+            .address(address)
+            .control_information(control_information)
+            .data(data)
+            .build()
+    }
+
+    /// The single character acknowledgement.
+    pub const fn ack() -> Self {
+        Frame::Single
+    }
+
+    /// Resets the link of the slave at `address`.
+    pub const fn snd_nke(address: u8) -> Self {
+        Frame::Short {
+            control: control::SND_NKE,
+            address,
+        }
+    }
+
+    /// Requests class 2 data from the slave at `address`.
+    pub const fn req_ud2(address: u8, fcb: bool) -> Self {
+        Frame::Short {
+            control: control::ControlField::new(control::FunctionCode::ReqUd2, fcb).bits(),
+            address,
+        }
+    }
+
+    /// Response of the slave at `address`, not validated unlike [`Frame::long`].
+    pub fn rsp_ud(address: u8, control_information: u8, data: impl Into<Vec<u8>>) -> Self {
+        Frame::Long {
+            control: control::RSP_UD,
+            address,
+            control_information,
+            data: data.into(),
+        }
+    }
+
+    pub fn try_parse<B: AsRef<[u8]>>(bytes: B) -> Result<(usize, Self), ParseError> {
         let bytes = bytes.as_ref();
-        let (ptr, frame) = parser::parse_frame(bytes)?;
-        let bytes_read = bytes.offset(ptr);
-        Ok((bytes_read, frame))
+        let (bytes_read, frame) = FrameRef::try_parse(bytes)?;
+        Ok((bytes_read, frame.to_owned()))
+    }
+
+    /// Like [`Frame::try_parse`], but returns frames with a checksum mismatch too, leaving it to
+    /// the caller whether to trust their contents.
+    pub fn try_parse_lenient(bytes: &[u8]) -> Result<(usize, Self, ChecksumStatus), ParseError> {
+        let (bytes_read, frame, status) = FrameRef::try_parse_lenient(bytes)?;
+        Ok((bytes_read, frame.to_owned(), status))
+    }
+
+    /// Lower bound on the total length of the frame at the start of `bytes`, derived from its
+    /// start byte and L-field. Useful to decide how much data to wait for before parsing again.
+    pub fn length_hint(bytes: &[u8]) -> usize {
+        parser::frame_length_hint(bytes)
     }
 
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, parser::ParseError> {
+    /// Like [`Frame::try_parse`], but skips line noise: bytes that cannot start a frame and start
+    /// bytes at which parsing fails. Returns the number of skipped bytes along with the result
+    /// of parsing the rest, which is `Incomplete` when more data might complete a frame.
+    ///
+    /// A start byte at which the frame is incomplete does not hide a complete frame after it: a
+    /// stray 0x68 in front of an ACK yields the ACK, as the bus would otherwise stall waiting
+    /// for the rest of a long frame that never comes. Once the header of a long frame is
+    /// complete, its payload is not searched for frames and `Incomplete` asks for the rest of
+    /// the frame; until then, every byte might complete a frame of its own and it asks for one
+    /// more.
+    pub fn scan(bytes: &[u8]) -> (usize, Result<(usize, Self), ParseError>) {
+        let (skipped, result) = FrameRef::scan(bytes);
+        (
+            skipped,
+            result.map(|(length, frame)| (length, frame.to_owned())),
+        )
+    }
+
+    pub fn parse_all(bytes: &[u8]) -> (Vec<Self>, usize) {
+        let mut frames = Vec::new();
+        let mut consumed = 0;
+        while consumed < bytes.len() {
+            match Self::try_parse(&bytes[consumed..]) {
+                Ok((bytes_read, frame)) => {
+                    frames.push(frame);
+                    consumed += bytes_read;
+                }
+                Err(_) => break,
+            }
+        }
+        (frames, consumed)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
         let (_, frame) = Self::try_parse(bytes)?;
         Ok(frame)
     }
 
+    /// Borrows the frame, e.g. to hand it to code that also works on frames parsed in place.
+    pub fn as_frame_ref(&self) -> FrameRef<'_> {
+        match self {
+            Frame::Single => FrameRef::Single,
+            Frame::Short { control, address } => FrameRef::Short {
+                control: *control,
+                address: *address,
+            },
+            Frame::Control {
+                control,
+                address,
+                control_information,
+            } => FrameRef::Control {
+                control: *control,
+                address: *address,
+                control_information: *control_information,
+            },
+            Frame::Long {
+                control,
+                address,
+                control_information,
+                data,
+            } => FrameRef::Long {
+                control: *control,
+                address: *address,
+                control_information: *control_information,
+                data,
+            },
+        }
+    }
+
+    pub fn addressed(&self) -> Option<AddressedFrame<'_>> {
+        match self {
+            Frame::Single => None,
+            Frame::Short { control, address } => Some(AddressedFrame {
+                control: *control,
+                address: *address,
+                control_information: None,
+                data: &[],
+            }),
+            Frame::Control {
+                control,
+                address,
+                control_information,
+            } => Some(AddressedFrame {
+                control: *control,
+                address: *address,
+                control_information: Some(*control_information),
+                data: &[],
+            }),
+            Frame::Long {
+                control,
+                address,
+                control_information,
+                data,
+            } => Some(AddressedFrame {
+                control: *control,
+                address: *address,
+                control_information: Some(*control_information),
+                data,
+            }),
+        }
+    }
+
+    pub fn address(&self) -> Option<u8> {
+        self.addressed().map(|addressed| addressed.address())
+    }
+
+    /// The C-field, named this way as [`Frame::control`] builds a control frame.
+    pub fn control_field(&self) -> Option<control::ControlField> {
+        self.addressed()
+            .map(|addressed| control::ControlField::from(addressed.control()))
+    }
+
+    /// Whether the frame is a request of the master, a response of a slave or an
+    /// acknowledgement.
+    pub fn role(&self) -> control::Role {
+        self.control_field()
+            .map_or(control::Role::Ack, control::ControlField::role)
+    }
+
+    pub fn control_information(&self) -> Option<u8> {
+        self.addressed()
+            .and_then(|addressed| addressed.control_information())
+    }
+
+    /// The frame with the FCB and FCV bits cleared, see [`ControlField::normalized`]. Frames
+    /// differing only in frame counting normalize to the same frame, and so to the same bytes.
+    ///
+    /// [`ControlField::normalized`]: control::ControlField::normalized
+    pub fn normalized(&self) -> Frame {
+        let normalize = |control: u8| control::ControlField::from(control).normalized().bits();
+        match self {
+            Frame::Single => Frame::Single,
+            Frame::Short { control, address } => Frame::Short {
+                control: normalize(*control),
+                address: *address,
+            },
+            Frame::Control {
+                control,
+                address,
+                control_information,
+            } => Frame::Control {
+                control: normalize(*control),
+                address: *address,
+                control_information: *control_information,
+            },
+            Frame::Long {
+                control,
+                address,
+                control_information,
+                data,
+            } => Frame::Long {
+                control: normalize(*control),
+                address: *address,
+                control_information: *control_information,
+                data: data.clone(),
+            },
+        }
+    }
+
+    /// Whether both frames are equal apart from their FCB and FCV bits, without allocating
+    /// normalized copies.
+    pub fn matches_ignoring_fcb(&self, other: &Frame) -> bool {
+        let normalize = |control| control::ControlField::from(control).normalized();
+        match (self.addressed(), other.addressed()) {
+            (Some(a), Some(b)) => {
+                core::mem::discriminant(self) == core::mem::discriminant(other)
+                    && normalize(a.control) == normalize(b.control)
+                    && a == AddressedFrame {
+                        control: a.control,
+                        ..b
+                    }
+            }
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+
+    /// The user data decoded according to the CI, see [`telegram::UserData`].
+    pub fn user_data(&self) -> Result<telegram::UserData, telegram::TelegramError> {
+        telegram::UserData::from_frame(self)
+    }
+
+    /// User data of a long frame.
+    pub fn payload(&self) -> Option<&[u8]> {
+        match self {
+            Frame::Long { data, .. } => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Checks that the frame can be encoded. Parsed frames always can, as their L-field cannot
+    /// exceed the maximum; only frames built in code may carry too much data.
+    pub fn validate(&self) -> Result<(), FrameError> {
+        match self {
+            Frame::Long { data, .. } if data.len() > MAX_DATA_LENGTH => {
+                Err(FrameError::DataTooLong(data.len()))
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn iter_bytes(&self) -> iterator::FrameIterator<'_> {
-        iterator::FrameIterator::new(&self)
+        iterator::FrameIterator::new(self)
     }
 
+    /// Number of bytes the encoded frame takes on the wire.
+    pub fn encoded_len(&self) -> usize {
+        encoder::encoded_len(self.as_frame_ref())
+    }
+
+    /// Encodes the frame without validating it, see [`Frame::try_to_bytes`].
     pub fn to_bytes(&self) -> Vec<u8> {
-        self.iter_bytes().collect::<Vec<u8>>()
+        let mut bytes = Vec::with_capacity(self.encoded_len());
+        let _ = encoder::encode(self.as_frame_ref(), |chunk| {
+            bytes.extend_from_slice(chunk);
+            Ok::<_, core::convert::Infallible>(())
+        });
+        bytes
+    }
+
+    /// Encodes the frame without validating it, reusing the user data allocation of a long frame.
+    pub fn into_bytes(self) -> Vec<u8> {
+        encoder::into_bytes(self)
+    }
+
+    /// Encodes the frame, refusing payloads whose length does not fit in the L-field.
+    pub fn try_to_bytes(&self) -> Result<Vec<u8>, FrameError> {
+        self.validate()?;
+        Ok(self.to_bytes())
+    }
+
+    /// Encodes the frame at the start of `buf` without validating it and returns the number of
+    /// bytes written. Nothing is written when `buf` is shorter than [`Frame::encoded_len`].
+    pub fn encode_into(&self, buf: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        let length = self.encoded_len();
+        let mut rest = buf.get_mut(..length).ok_or(BufferTooSmall)?;
+        let _ = encoder::encode(self.as_frame_ref(), |chunk| {
+            let (head, tail) = core::mem::take(&mut rest).split_at_mut(chunk.len());
+            head.copy_from_slice(chunk);
+            rest = tail;
+            Ok::<_, core::convert::Infallible>(())
+        });
+        Ok(length)
+    }
+
+    /// Appends the encoded frame to `buf` without validating it and returns the number of bytes
+    /// written.
+    #[cfg(feature = "bytes")]
+    pub fn write_to_buf<B: ::bytes::BufMut>(&self, buf: &mut B) -> usize {
+        let length = self.encoded_len();
+        let _ = encoder::encode(self.as_frame_ref(), |chunk| {
+            buf.put_slice(chunk);
+            Ok::<_, core::convert::Infallible>(())
+        });
+        length
+    }
+
+    /// Validates and writes the frame to `w` without building an intermediate `Vec`, returning
+    /// the number of bytes written. Invalid frames are refused with
+    /// [`std::io::ErrorKind::InvalidInput`].
+    #[cfg(feature = "std")]
+    pub fn write_to<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<usize> {
+        self.validate()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        encoder::encode(self.as_frame_ref(), |chunk| w.write_all(chunk))?;
+        Ok(self.encoded_len())
+    }
+
+    pub fn to_hex_string(&self) -> String {
+        format!("{:X}", self)
     }
 }
 
-pub type ParseError = parser::ParseError;
-pub type ParseSizeNeeded = parser::ParseSizeNeeded;
+/// Formats bytes as space separated hex, with `{:x}` or `{:X}`.
+pub(crate) struct HexBytes<'a>(pub &'a [u8]);
+
+impl fmt::LowerHex for HexBytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, self.0.iter().copied(), false)
+    }
+}
+
+impl fmt::UpperHex for HexBytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, self.0.iter().copied(), true)
+    }
+}
+
+fn write_hex(
+    f: &mut fmt::Formatter<'_>,
+    bytes: impl IntoIterator<Item = u8>,
+    upper: bool,
+) -> fmt::Result {
+    for (i, b) in bytes.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(" ")?;
+        }
+        if upper {
+            write!(f, "{:02X}", b)?;
+        } else {
+            write!(f, "{:02x}", b)?;
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_frame_ref().fmt(f)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Frame {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let frame = match u.int_in_range(0..=3)? {
+            0 => Frame::Single,
+            1 => Frame::Short {
+                control: u.arbitrary()?,
+                address: u.arbitrary()?,
+            },
+            2 => Frame::Control {
+                control: u.arbitrary()?,
+                address: u.arbitrary()?,
+                control_information: u.arbitrary()?,
+            },
+            _ => {
+                // an empty payload would be encoded as a control frame
+                let length = u.int_in_range(1..=MAX_DATA_LENGTH)?;
+                Frame::Long {
+                    control: u.arbitrary()?,
+                    address: u.arbitrary()?,
+                    control_information: u.arbitrary()?,
+                    data: u.bytes(length)?.to_vec(),
+                }
+            }
+        };
+        Ok(frame)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FrameError {
+    DataTooLong(usize),
+    MissingControl,
+    MissingAddress,
+    MissingControlInformation,
+    MissingData,
+    UnexpectedFcb,
+    UnexpectedUserData(control::FunctionCode),
+}
+
+/// Formats the wire bytes of the frame as space separated hex, e.g. `{:x}` gives `10 7b 49 c4 16`.
+impl fmt::LowerHex for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, self.iter_bytes(), false)
+    }
+}
+
+impl fmt::UpperHex for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, self.iter_bytes(), true)
+    }
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::DataTooLong(length) => write!(
+                f,
+                "frame carries {} data bytes, at most {} are allowed",
+                length, MAX_DATA_LENGTH
+            ),
+            FrameError::MissingControl => write!(f, "frame has no control field"),
+            FrameError::MissingAddress => write!(f, "frame has no address"),
+            FrameError::MissingControlInformation => {
+                write!(f, "frame carries data but no control information")
+            }
+            FrameError::MissingData => write!(f, "long frame carries no data"),
+            FrameError::UnexpectedFcb => {
+                write!(
+                    f,
+                    "frame count bit set on a function without frame counting"
+                )
+            }
+            FrameError::UnexpectedUserData(function) => {
+                write!(f, "{:?} frames cannot carry user data", function)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameError {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BufferTooSmall;
+
+impl fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "buffer too small to hold the encoded frame")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BufferTooSmall {}
+
+/// Borrowed view over the fields shared by all frames that are sent to or from a slave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddressedFrame<'a> {
+    control: u8,
+    address: u8,
+    control_information: Option<u8>,
+    data: &'a [u8],
+}
+
+impl<'a> AddressedFrame<'a> {
+    pub fn control(&self) -> u8 {
+        self.control
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    pub fn control_information(&self) -> Option<u8> {
+        self.control_information
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+pub use builder::FrameBuilder;
+#[cfg(feature = "bytes")]
+pub use bytes_frame::BytesFrame;
+pub use frame_ref::FrameRef;
+pub use parser::{ChecksumStatus, ParseError};
+pub use reader::FrameReader;
+
+impl IntoIterator for Frame {
+    type Item = u8;
+    type IntoIter = alloc::vec::IntoIter<u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_bytes().into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Frame {
+    type Item = u8;
+    type IntoIter = iterator::FrameIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_bytes()
+    }
+}
 
 impl<'a> TryFrom<&'a [u8]> for Frame {
     type Error = ParseError;
@@ -58,6 +620,521 @@ impl<'a> TryFrom<&'a [u8]> for Frame {
     }
 }
 
+pub mod address;
+mod builder;
+#[cfg(feature = "bytes")]
+mod bytes_frame;
+pub mod control;
+pub mod crc;
+#[cfg(feature = "crypto")]
+pub mod crypto;
+mod encoder;
+mod frame_ref;
 mod iterator;
+pub mod master;
+pub mod oms;
 mod parser;
+#[cfg(all(test, feature = "std"))]
+mod proptests;
+mod reader;
+#[cfg(feature = "serde")]
+mod serde_hex;
+pub mod slave;
+#[cfg(any(feature = "proptest", all(test, feature = "std")))]
+pub mod strategy;
+pub mod telegram;
 mod utils;
+pub mod wmbus;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec};
+
+    #[test]
+    fn test_addressed() {
+        assert_eq!(Frame::Single.addressed(), None);
+
+        let frame = Frame::Short {
+            control: 0x7B,
+            address: 0x49,
+        };
+        let addressed = frame.addressed().unwrap();
+        assert_eq!(addressed.control(), 0x7B);
+        assert_eq!(addressed.address(), 0x49);
+        assert_eq!(addressed.control_information(), None);
+        assert!(addressed.data().is_empty());
+
+        let frame = Frame::Control {
+            control: 0x53,
+            address: 0xFE,
+            control_information: 0xBD,
+        };
+        let addressed = frame.addressed().unwrap();
+        assert_eq!(addressed.control(), 0x53);
+        assert_eq!(addressed.address(), 0xFE);
+        assert_eq!(addressed.control_information(), Some(0xBD));
+        assert!(addressed.data().is_empty());
+
+        let frame = Frame::Long {
+            control: 0x53,
+            address: 0xFE,
+            control_information: 0x51,
+            data: vec![0x01, 0x7A, 0x08],
+        };
+        let addressed = frame.addressed().unwrap();
+        assert_eq!(addressed.control(), 0x53);
+        assert_eq!(addressed.address(), 0xFE);
+        assert_eq!(addressed.control_information(), Some(0x51));
+        assert_eq!(addressed.data(), &[0x01, 0x7A, 0x08]);
+    }
+
+    #[test]
+    fn test_address() {
+        assert_eq!(Frame::Single.address(), None);
+        assert_eq!(
+            Frame::Short {
+                control: 0x7B,
+                address: 0x49,
+            }
+            .address(),
+            Some(0x49)
+        );
+        assert_eq!(
+            Frame::Control {
+                control: 0x53,
+                address: 0xFE,
+                control_information: 0xBD,
+            }
+            .address(),
+            Some(0xFE)
+        );
+        assert_eq!(
+            Frame::Long {
+                control: 0x08,
+                address: 0x5A,
+                control_information: 0x72,
+                data: vec![0x01],
+            }
+            .address(),
+            Some(0x5A)
+        );
+    }
+
+    #[test]
+    fn test_convenience_constructors() {
+        assert_eq!(Frame::ack().to_hex_string(), "E5");
+        assert_eq!(Frame::snd_nke(0x01).to_hex_string(), "10 40 01 41 16");
+        assert_eq!(Frame::req_ud2(0x01, true).to_hex_string(), "10 7B 01 7C 16");
+        assert_eq!(
+            Frame::req_ud2(0x01, false).to_hex_string(),
+            "10 5B 01 5C 16"
+        );
+        assert_eq!(
+            Frame::rsp_ud(0x01, 0x72, [0x01]).to_hex_string(),
+            "68 04 04 68 08 01 72 01 7C 16"
+        );
+    }
+
+    #[test]
+    fn test_payload() {
+        let short = Frame::Short {
+            control: 0x7B,
+            address: 0x49,
+        };
+        assert_eq!(short.control_information(), None);
+        assert_eq!(short.payload(), None);
+
+        let long = Frame::Long {
+            control: 0x08,
+            address: 0x5A,
+            control_information: 0x72,
+            data: vec![0x01],
+        };
+        assert_eq!(long.control_information(), Some(0x72));
+        assert_eq!(long.payload(), Some(&[0x01][..]));
+    }
+
+    #[test]
+    fn test_control_field() {
+        assert_eq!(Frame::Single.control_field(), None);
+        assert_eq!(
+            Frame::Short {
+                control: 0x7B,
+                address: 0x5A,
+            }
+            .control_field()
+            .map(|control| control.function()),
+            Some(control::FunctionCode::ReqUd2)
+        );
+    }
+
+    #[test]
+    fn test_normalized() {
+        let first = Frame::req_ud2(0x01, true);
+        let repeated = Frame::req_ud2(0x01, false);
+        assert_ne!(first, repeated);
+        assert_eq!(first.normalized(), repeated.normalized());
+        assert_eq!(
+            first.normalized().to_bytes(),
+            [0x10, 0x4B, 0x01, 0x4C, 0x16]
+        );
+        assert!(first.matches_ignoring_fcb(&repeated));
+        assert!(!first.matches_ignoring_fcb(&Frame::req_ud2(0x02, true)));
+        assert!(Frame::ack().matches_ignoring_fcb(&Frame::ack()));
+
+        // ACD and DFC of a slave are kept
+        let response = Frame::rsp_ud(0x01, 0x72, [0x00]);
+        let demanding = Frame::Long {
+            control: 0x28,
+            address: 0x01,
+            control_information: 0x72,
+            data: vec![0x00],
+        };
+        assert_eq!(demanding.normalized(), demanding);
+        assert!(!response.matches_ignoring_fcb(&demanding));
+    }
+
+    #[test]
+    fn test_role() {
+        use control::{Request, Response, Role};
+
+        assert_eq!(Frame::ack().role(), Role::Ack);
+        assert_eq!(
+            Frame::req_ud2(0x01, true).role(),
+            Role::MasterToSlave(Request::ReqUd2)
+        );
+        assert_eq!(
+            Frame::rsp_ud(0x01, 0x72, []).role(),
+            Role::SlaveToMaster(Response::RspUd)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Frame::Single.to_string(), "SINGLE");
+        assert_eq!(
+            Frame::Short {
+                control: 0x7B,
+                address: 0x49,
+            }
+            .to_string(),
+            "SHORT addr=0x49 ctrl=0x7B"
+        );
+        assert_eq!(
+            Frame::Control {
+                control: 0x53,
+                address: 0xFE,
+                control_information: 0xBD,
+            }
+            .to_string(),
+            "CONTROL addr=0xFE ctrl=0x53 ci=0xBD"
+        );
+
+        let frame = Frame::Long {
+            control: 0x53,
+            address: 0x5A,
+            control_information: 0x51,
+            data: vec![0x01, 0x7A, 0x08],
+        };
+        assert_eq!(
+            frame.to_string(),
+            "LONG addr=0x5A ctrl=0x53 ci=0x51 data=01 7A 08"
+        );
+        assert_eq!(frame.to_hex_string(), "68 06 06 68 53 5A 51 01 7A 08 81 16");
+        assert_eq!(
+            format!("{:x}", frame),
+            "68 06 06 68 53 5a 51 01 7a 08 81 16"
+        );
+        assert_eq!(format!("{:X}", Frame::Single), "E5");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let frame = Frame::Long {
+            control: 0x08,
+            address: 0x01,
+            control_information: 0x72,
+            data: vec![0xCA, 0xFE],
+        };
+        let json = serde_json::to_string(&frame).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"long","control":8,"address":1,"control_information":114,"data":"CAFE"}"#
+        );
+        assert_eq!(serde_json::from_str::<Frame>(&json).unwrap(), frame);
+        assert_eq!(
+            serde_json::from_str::<Frame>(
+                r#"{"type":"long","control":8,"address":1,"control_information":114,"data":"ca fe"}"#
+            )
+            .unwrap(),
+            frame
+        );
+        assert!(serde_json::from_str::<Frame>(
+            r#"{"type":"long","control":8,"address":1,"control_information":114,"data":"CAF"}"#
+        )
+        .is_err());
+        assert_eq!(
+            serde_json::to_string(&Frame::Single).unwrap(),
+            r#"{"type":"single"}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"\x10\x7b\x49\xc4\x16");
+        bytes.extend_from_slice(b"\xe5");
+        bytes.extend_from_slice(b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16");
+        bytes.extend_from_slice(b"\x68\x03\x03\x68\x53");
+
+        let (frames, consumed) = Frame::parse_all(&bytes);
+        assert_eq!(
+            frames,
+            vec![
+                Frame::Short {
+                    control: 0x7B,
+                    address: 0x49,
+                },
+                Frame::Single,
+                Frame::Long {
+                    control: 0x53,
+                    address: 0xFE,
+                    control_information: 0x51,
+                    data: vec![0x01, 0x7A, 0x08],
+                },
+            ]
+        );
+        assert_eq!(consumed, 18);
+
+        // a corrupt frame stops parsing but keeps what was decoded before it
+        let (frames, consumed) = Frame::parse_all(b"\xe5\x10\x7b\x49\xc5\x16\xe5");
+        assert_eq!(frames, vec![Frame::Single]);
+        assert_eq!(consumed, 1);
+    }
+
+    #[test]
+    fn test_checked_constructors() {
+        use control::FunctionCode;
+
+        assert_eq!(
+            Frame::short(FunctionCode::SndNke, 0x01),
+            Ok(Frame::Short {
+                control: 0x40,
+                address: 0x01
+            })
+        );
+        assert_eq!(
+            Frame::control(0x53, 0xFE, 0xBD),
+            Ok(Frame::Control {
+                control: 0x53,
+                address: 0xFE,
+                control_information: 0xBD
+            })
+        );
+        assert_eq!(
+            Frame::long(0x53, 0xFE, 0x51, [0x01, 0x7A, 0x08]),
+            Ok(Frame::Long {
+                control: 0x53,
+                address: 0xFE,
+                control_information: 0x51,
+                data: vec![0x01, 0x7A, 0x08]
+            })
+        );
+        assert_eq!(
+            Frame::long(0x53, 0xFE, 0x51, []),
+            Err(FrameError::MissingData)
+        );
+        assert_eq!(
+            Frame::long(0x53, 0xFE, 0x51, vec![0x00; MAX_DATA_LENGTH + 1]),
+            Err(FrameError::DataTooLong(MAX_DATA_LENGTH + 1))
+        );
+        assert_eq!(
+            Frame::control(FunctionCode::ReqUd2, 0x01, 0x51),
+            Err(FrameError::UnexpectedUserData(FunctionCode::ReqUd2))
+        );
+    }
+
+    #[test]
+    fn test_scan() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"\x00\xFF");
+        // short frame with a bad checksum
+        bytes.extend_from_slice(b"\x10\x7b\x49\xc5\x16");
+        bytes.extend_from_slice(b"\x10\x7b\x49\xc4\x16");
+        bytes.extend_from_slice(b"\x42\x68\x06");
+
+        let (skipped, result) = Frame::scan(&bytes);
+        assert_eq!(skipped, 7);
+        assert_eq!(
+            result.unwrap(),
+            (
+                5,
+                Frame::Short {
+                    control: 0x7B,
+                    address: 0x49
+                }
+            )
+        );
+
+        let (skipped, result) = Frame::scan(&bytes[12..]);
+        assert_eq!(skipped, 1);
+        assert!(matches!(result, Err(ParseError::Incomplete { .. })));
+
+        let (skipped, result) = Frame::scan(b"\x00\x01");
+        assert_eq!(skipped, 2);
+        assert!(matches!(result, Err(ParseError::Incomplete { .. })));
+
+        // waiting for the rest of a long frame once its header is complete
+        assert_eq!(
+            Frame::scan(b"\x68\x06\x06\x68\x53"),
+            (0, Err(ParseError::Incomplete { needed: 7 }))
+        );
+        assert_eq!(
+            Frame::scan(b"\x68\x06\x06"),
+            (0, Err(ParseError::Incomplete { needed: 1 }))
+        );
+
+        // an ACK or short frame in the payload of a long frame still arriving is not a frame
+        assert_eq!(
+            Frame::scan(b"\x68\x06\x06\x68\x53\xFE\x51\xE5"),
+            (0, Err(ParseError::Incomplete { needed: 4 }))
+        );
+        assert_eq!(
+            Frame::scan(b"\x68\x0A\x0A\x68\x53\xFE\x51\x10\x7b\x49\xc4\x16"),
+            (0, Err(ParseError::Incomplete { needed: 4 }))
+        );
+
+        // a stray start byte of a long frame in front of an ACK and a short frame
+        assert_eq!(Frame::scan(b"\x68\xE5"), (1, Ok((1, Frame::Single))));
+        assert_eq!(
+            Frame::scan(b"\x68\x10\x7b\x49\xc4\x16"),
+            (
+                1,
+                Ok((
+                    5,
+                    Frame::Short {
+                        control: 0x7B,
+                        address: 0x49
+                    }
+                ))
+            )
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let frame = Frame::Long {
+            control: 0x53,
+            address: 0xFE,
+            control_information: 0x51,
+            data: vec![0x00; MAX_DATA_LENGTH],
+        };
+        assert_eq!(frame.validate(), Ok(()));
+
+        let frame = Frame::Long {
+            control: 0x53,
+            address: 0xFE,
+            control_information: 0x51,
+            data: vec![0x00; MAX_DATA_LENGTH + 1],
+        };
+        assert_eq!(
+            frame.validate(),
+            Err(FrameError::DataTooLong(MAX_DATA_LENGTH + 1))
+        );
+        assert_eq!(
+            frame.try_to_bytes(),
+            Err(FrameError::DataTooLong(MAX_DATA_LENGTH + 1))
+        );
+        #[cfg(feature = "std")]
+        assert_eq!(
+            frame.write_to(&mut Vec::new()).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+    }
+
+    fn sample_frames() -> Vec<Frame> {
+        vec![
+            Frame::Single,
+            Frame::Short {
+                control: 0x7B,
+                address: 0x49,
+            },
+            Frame::Control {
+                control: 0x53,
+                address: 0xFE,
+                control_information: 0xBD,
+            },
+            Frame::Long {
+                control: 0x53,
+                address: 0xFE,
+                control_information: 0x51,
+                data: (0..200).collect(),
+            },
+        ]
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_to() -> std::io::Result<()> {
+        for frame in sample_frames() {
+            let mut out = Vec::new();
+            let written = frame.write_to(&mut out)?;
+            assert_eq!(out, frame.to_bytes());
+            assert_eq!(written, out.len());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_into_short_buffer() {
+        for frame in sample_frames() {
+            let mut buf = [0u8; 256];
+            let written = frame.encode_into(&mut buf).unwrap();
+            assert_eq!(&buf[..written], frame.to_bytes());
+            assert_eq!(
+                frame.encode_into(&mut buf[..written - 1]),
+                Err(BufferTooSmall)
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_into() {
+        for frame in sample_frames() {
+            let mut buf = [0u8; 256];
+            assert_eq!(frame.encode_into(&mut buf), Ok(frame.encoded_len()));
+            assert_eq!(
+                &buf[..frame.encoded_len()],
+                frame.iter_bytes().collect::<Vec<_>>()
+            );
+
+            #[cfg(feature = "bytes")]
+            {
+                let mut dst = ::bytes::BytesMut::from(&b"\xAA"[..]);
+                assert_eq!(frame.write_to_buf(&mut dst), frame.encoded_len());
+                assert_eq!(dst[1..], frame.to_bytes());
+            }
+        }
+    }
+
+    #[test]
+    fn test_length_hint() {
+        assert_eq!(Frame::length_hint(b""), 1);
+        assert_eq!(Frame::length_hint(b"\xe5"), 1);
+        assert_eq!(Frame::length_hint(b"\x10\x7b"), 5);
+        assert_eq!(Frame::length_hint(b"\x68"), 9);
+        assert_eq!(Frame::length_hint(b"\x68\x06\x06"), 12);
+    }
+
+    #[test]
+    fn test_round_trip_without_std() -> Result<(), ParseError> {
+        let bytes = b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16";
+        let frame = Frame::from_bytes(bytes)?;
+        assert_eq!(frame.to_bytes(), bytes);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+//! Link layer procedure (EN 13757-2) of a master reading out a slave, as a state machine that
+//! does no I/O itself.
+//!
+//! The caller sends the frames returned by [`Master::poll_transmit`], arms a timer after each
+//! one (the standard asks for 330 bit times plus 50 ms) and feeds back the frames it receives
+//! with [`Master::handle_frame`] or the expiry of the timer with [`Master::handle_timeout`].
+
+use crate::control::{ControlField, FunctionCode};
+use crate::telegram::Telegram;
+use crate::Frame;
+use alloc::collections::VecDeque;
+use core::fmt;
+
+/// Number of times a request is repeated when the slave does not answer it.
+pub const DEFAULT_MAX_RETRIES: u8 = 2;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Event {
+    /// An RSP_UD of the slave, the readout continues if its telegram ends with DIF 0x1F.
+    Response(Frame),
+    /// The readout is done, all responses have been reported.
+    Complete,
+    Failed(MasterError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MasterError {
+    /// The slave did not answer a request, nor any of its repetitions.
+    NoResponse { address: u8 },
+}
+
+impl fmt::Display for MasterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MasterError::NoResponse { address } => {
+                write!(f, "slave 0x{:02X} did not respond", address)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MasterError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle,
+    /// SND_NKE was sent, waiting for the single character acknowledgement.
+    WaitingAck,
+    /// REQ_UD2 was sent, waiting for the RSP_UD.
+    WaitingResponse,
+}
+
+#[derive(Debug)]
+pub struct Master {
+    state: State,
+    address: u8,
+    fcb: bool,
+    retries: u8,
+    max_retries: u8,
+    transmit: Option<Frame>,
+    events: VecDeque<Event>,
+}
+
+impl Default for Master {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Master {
+    pub fn new() -> Self {
+        Self::with_max_retries(DEFAULT_MAX_RETRIES)
+    }
+
+    pub fn with_max_retries(max_retries: u8) -> Self {
+        Self {
+            state: State::Idle,
+            address: 0,
+            fcb: false,
+            retries: 0,
+            max_retries,
+            transmit: None,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Starts reading out the slave at `address`: it is reset with SND_NKE and then asked for
+    /// class 2 data until it has no more records to send. A readout in progress is abandoned.
+    pub fn start_readout(&mut self, address: u8) {
+        self.address = address;
+        self.send(State::WaitingAck);
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.state == State::Idle
+    }
+
+    /// The next frame to send, if any.
+    pub fn poll_transmit(&mut self) -> Option<Frame> {
+        self.transmit.take()
+    }
+
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    /// Handles a frame received from the bus. Anything but the expected answer counts as a
+    /// failed transmission, like a timeout.
+    pub fn handle_frame(&mut self, frame: Frame) {
+        match self.state {
+            State::Idle => {}
+            State::WaitingAck if frame == Frame::Single => {
+                // the first request after a reset carries FCB=1
+                self.fcb = true;
+                self.send(State::WaitingResponse);
+            }
+            State::WaitingResponse if self.is_response(&frame) => {
+                let more_records_follow = Telegram::from_frame(&frame)
+                    .map(|telegram| telegram.more_records_follow)
+                    .unwrap_or(false);
+                self.events.push_back(Event::Response(frame));
+                if more_records_follow {
+                    self.fcb = !self.fcb;
+                    self.send(State::WaitingResponse);
+                } else {
+                    self.state = State::Idle;
+                    self.events.push_back(Event::Complete);
+                }
+            }
+            State::WaitingAck | State::WaitingResponse => self.handle_timeout(),
+        }
+    }
+
+    /// Handles the expiry of the timer armed after the last transmitted frame. The frame is
+    /// repeated with the same FCB, so that the slave can tell it apart from the next request.
+    pub fn handle_timeout(&mut self) {
+        if self.state == State::Idle {
+            return;
+        }
+        if self.retries < self.max_retries {
+            self.retries += 1;
+            self.transmit = Some(self.frame());
+        } else {
+            self.state = State::Idle;
+            self.transmit = None;
+            self.events
+                .push_back(Event::Failed(MasterError::NoResponse {
+                    address: self.address,
+                }));
+        }
+    }
+
+    fn send(&mut self, state: State) {
+        self.state = state;
+        self.retries = 0;
+        self.transmit = Some(self.frame());
+    }
+
+    fn frame(&self) -> Frame {
+        match self.state {
+            State::WaitingAck => Frame::snd_nke(self.address),
+            _ => Frame::req_ud2(self.address, self.fcb),
+        }
+    }
+
+    fn is_response(&self, frame: &Frame) -> bool {
+        matches!(frame, Frame::Long { address, control, .. }
+            if *address == self.address
+                && ControlField::from(*control).function() == FunctionCode::RspUd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::{Header, ManufacturerId, Medium, Status};
+    use alloc::vec::Vec;
+
+    fn response(more_records_follow: bool) -> Frame {
+        Telegram {
+            header: Header {
+                identification: 0x12345678,
+                manufacturer: ManufacturerId::from(0x4024),
+                version: 0x01,
+                medium: Medium::Water,
+                access_number: 0x55,
+                status: Status::default(),
+                signature: 0x0000,
+            },
+            records: Vec::new(),
+            more_records_follow,
+            manufacturer_data: Vec::new(),
+        }
+        .to_frame(0x01)
+    }
+
+    #[test]
+    fn test_readout() {
+        let mut master = Master::new();
+        master.start_readout(0x01);
+        assert_eq!(master.poll_transmit(), Some(Frame::snd_nke(0x01)));
+        assert_eq!(master.poll_transmit(), None);
+
+        master.handle_frame(Frame::ack());
+        assert_eq!(master.poll_transmit(), Some(Frame::req_ud2(0x01, true)));
+
+        // the slave is not heard, so the request is repeated as is
+        master.handle_timeout();
+        assert_eq!(master.poll_transmit(), Some(Frame::req_ud2(0x01, true)));
+
+        master.handle_frame(response(true));
+        assert_eq!(master.poll_event(), Some(Event::Response(response(true))));
+        assert_eq!(master.poll_transmit(), Some(Frame::req_ud2(0x01, false)));
+
+        master.handle_frame(response(false));
+        assert_eq!(master.poll_event(), Some(Event::Response(response(false))));
+        assert_eq!(master.poll_event(), Some(Event::Complete));
+        assert_eq!(master.poll_transmit(), None);
+        assert!(master.is_idle());
+    }
+
+    #[test]
+    fn test_no_response() {
+        let mut master = Master::with_max_retries(1);
+        master.start_readout(0x01);
+        assert_eq!(master.poll_transmit(), Some(Frame::snd_nke(0x01)));
+        master.handle_timeout();
+        assert_eq!(master.poll_transmit(), Some(Frame::snd_nke(0x01)));
+        // an answer from the wrong slave counts as no answer
+        master.handle_frame(Frame::Short {
+            control: crate::control::RSP_UD,
+            address: 0x02,
+        });
+        assert_eq!(master.poll_transmit(), None);
+        assert_eq!(
+            master.poll_event(),
+            Some(Event::Failed(MasterError::NoResponse { address: 0x01 }))
+        );
+        assert!(master.is_idle());
+    }
+}
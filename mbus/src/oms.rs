@@ -0,0 +1,303 @@
+//! Conventions of the Open Metering System (OMS 4.x) on top of the telegram parser: the
+//! transport layer headers used by wireless meters, the security profiles announced by their
+//! configuration field and the names of the common data points.
+
+use crate::telegram::{
+    DataRecord, FunctionField, Header, Quantity, Status, Telegram, TelegramError,
+    CI_RESPONSE_NO_HEADER, CI_RESPONSE_SHORT_HEADER, CI_RESPONSE_VARIABLE,
+};
+use core::fmt;
+
+/// Transport layer header in front of the data records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportHeader {
+    /// CI 0x78: the records follow the CI field right away.
+    None,
+    /// CI 0x7A: the address is that of the link layer.
+    Short(ShortHeader),
+    /// CI 0x72: the header carries an address of its own.
+    Long(Header),
+}
+
+/// The header following CI 0x7A.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortHeader {
+    pub access_number: u8,
+    pub status: Status,
+    pub configuration: Configuration,
+}
+
+impl TransportHeader {
+    /// Parses the header introduced by `control_information` at the start of `data`, returning
+    /// it together with the data that follows.
+    pub fn parse(control_information: u8, data: &[u8]) -> Result<(Self, &[u8]), TelegramError> {
+        match control_information {
+            CI_RESPONSE_NO_HEADER => Ok((TransportHeader::None, data)),
+            CI_RESPONSE_SHORT_HEADER => match data {
+                [access_number, status, cf0, cf1, rest @ ..] => {
+                    let header = ShortHeader {
+                        access_number: *access_number,
+                        status: (*status).into(),
+                        configuration: u16::from_le_bytes([*cf0, *cf1]).into(),
+                    };
+                    Ok((TransportHeader::Short(header), rest))
+                }
+                _ => Err(TelegramError::UnexpectedEnd),
+            },
+            CI_RESPONSE_VARIABLE => {
+                let (header, records) = Telegram::parse_lazy(data)?;
+                Ok((TransportHeader::Long(header), records.remaining()))
+            }
+            ci => Err(TelegramError::UnsupportedControlInformation(ci)),
+        }
+    }
+
+    /// The configuration field, which telegrams without header do not have.
+    pub fn configuration(&self) -> Option<Configuration> {
+        match self {
+            TransportHeader::None => None,
+            TransportHeader::Short(header) => Some(header.configuration),
+            TransportHeader::Long(header) => Some(header.signature.into()),
+        }
+    }
+
+    pub fn security_profile(&self) -> SecurityProfile {
+        self.configuration()
+            .map_or(SecurityProfile::None, Configuration::security_profile)
+    }
+}
+
+/// The configuration field of the transport layer, sent in place of the signature of the
+/// [`Header`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Configuration(u16);
+
+impl Configuration {
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// The meter accepts commands after this telegram.
+    pub const fn bidirectional(self) -> bool {
+        self.0 & 0x8000 != 0
+    }
+
+    pub const fn accessibility(self) -> bool {
+        self.0 & 0x4000 != 0
+    }
+
+    pub const fn synchronous(self) -> bool {
+        self.0 & 0x2000 != 0
+    }
+
+    /// Security mode of EN 13757-7, 0 when the data is not encrypted.
+    pub const fn mode(self) -> u8 {
+        ((self.0 >> 8) & 0x1F) as u8
+    }
+
+    /// Number of encrypted 16 byte blocks following the header.
+    pub const fn encrypted_blocks(self) -> u8 {
+        ((self.0 >> 4) & 0x0F) as u8
+    }
+
+    pub const fn security_profile(self) -> SecurityProfile {
+        match self.mode() {
+            0 => SecurityProfile::None,
+            5 => SecurityProfile::A,
+            7 => SecurityProfile::B,
+            13 => SecurityProfile::C,
+            mode => SecurityProfile::Unknown(mode),
+        }
+    }
+}
+
+impl From<u16> for Configuration {
+    fn from(bits: u16) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<Configuration> for u16 {
+    fn from(configuration: Configuration) -> Self {
+        configuration.0
+    }
+}
+
+/// Security profile of OMS, each tied to a security mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityProfile {
+    None,
+    /// Mode 5: AES-128-CBC with a static key.
+    A,
+    /// Mode 7: AES-128-CBC with derived keys, authenticated with AES-CMAC.
+    B,
+    /// Mode 13: TLS.
+    C,
+    /// Another mode, not part of a profile.
+    Unknown(u8),
+}
+
+/// Common data points of OMS meters, recognized by the quantity, storage number and function
+/// of their record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataPoint {
+    CurrentVolume,
+    DueDateVolume,
+    CurrentEnergy,
+    DueDateEnergy,
+    CurrentHeatCostAllocation,
+    DueDateHeatCostAllocation,
+    VolumeFlow,
+    MaximumVolumeFlow,
+    Power,
+    MaximumPower,
+    FlowTemperature,
+    ReturnTemperature,
+    TemperatureDifference,
+    ExternalTemperature,
+    OperatingTime,
+    DateTime,
+    DueDate,
+    ErrorFlags,
+}
+
+impl DataPoint {
+    /// The data point `record` holds, if it is one of the common ones. Records of a tariff or
+    /// subunit are not.
+    pub fn of(record: &DataRecord) -> Option<Self> {
+        let header = &record.header;
+        if header.tariff != 0 || header.subunit != 0 {
+            return None;
+        }
+        let due_date = match header.storage_number {
+            0 => false,
+            1 => true,
+            _ => return None,
+        };
+        let quantity = record.value_information().quantity;
+        let point = match (quantity, due_date, header.function) {
+            (_, _, FunctionField::DuringError | FunctionField::Minimum) => return None,
+            (Quantity::Volume, false, FunctionField::Instantaneous) => DataPoint::CurrentVolume,
+            (Quantity::Volume, true, FunctionField::Instantaneous) => DataPoint::DueDateVolume,
+            (Quantity::Energy, false, FunctionField::Instantaneous) => DataPoint::CurrentEnergy,
+            (Quantity::Energy, true, FunctionField::Instantaneous) => DataPoint::DueDateEnergy,
+            (Quantity::HeatCostAllocation, false, FunctionField::Instantaneous) => {
+                DataPoint::CurrentHeatCostAllocation
+            }
+            (Quantity::HeatCostAllocation, true, FunctionField::Instantaneous) => {
+                DataPoint::DueDateHeatCostAllocation
+            }
+            (Quantity::Date | Quantity::DateTime, true, _) => DataPoint::DueDate,
+            (_, true, _) => return None,
+            (Quantity::VolumeFlow, false, FunctionField::Instantaneous) => DataPoint::VolumeFlow,
+            (Quantity::VolumeFlow, false, FunctionField::Maximum) => DataPoint::MaximumVolumeFlow,
+            (Quantity::Power, false, FunctionField::Instantaneous) => DataPoint::Power,
+            (Quantity::Power, false, FunctionField::Maximum) => DataPoint::MaximumPower,
+            (_, false, FunctionField::Maximum) => return None,
+            (Quantity::FlowTemperature, ..) => DataPoint::FlowTemperature,
+            (Quantity::ReturnTemperature, ..) => DataPoint::ReturnTemperature,
+            (Quantity::TemperatureDifference, ..) => DataPoint::TemperatureDifference,
+            (Quantity::ExternalTemperature, ..) => DataPoint::ExternalTemperature,
+            (Quantity::OperatingTime, ..) => DataPoint::OperatingTime,
+            (Quantity::Date | Quantity::DateTime, ..) => DataPoint::DateTime,
+            (Quantity::ErrorFlags, ..) => DataPoint::ErrorFlags,
+            _ => return None,
+        };
+        Some(point)
+    }
+
+    /// Name of the data point in snake case, as used by OMS tooling.
+    pub const fn name(self) -> &'static str {
+        match self {
+            DataPoint::CurrentVolume => "current_volume",
+            DataPoint::DueDateVolume => "due_date_volume",
+            DataPoint::CurrentEnergy => "current_energy",
+            DataPoint::DueDateEnergy => "due_date_energy",
+            DataPoint::CurrentHeatCostAllocation => "current_hca",
+            DataPoint::DueDateHeatCostAllocation => "due_date_hca",
+            DataPoint::VolumeFlow => "volume_flow",
+            DataPoint::MaximumVolumeFlow => "max_volume_flow",
+            DataPoint::Power => "power",
+            DataPoint::MaximumPower => "max_power",
+            DataPoint::FlowTemperature => "flow_temperature",
+            DataPoint::ReturnTemperature => "return_temperature",
+            DataPoint::TemperatureDifference => "temperature_difference",
+            DataPoint::ExternalTemperature => "external_temperature",
+            DataPoint::OperatingTime => "operating_time",
+            DataPoint::DateTime => "date_time",
+            DataPoint::DueDate => "due_date",
+            DataPoint::ErrorFlags => "error_flags",
+        }
+    }
+}
+
+impl fmt::Display for DataPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The records of `telegram` that hold common data points, in order.
+pub fn data_points(telegram: &Telegram) -> impl Iterator<Item = (DataPoint, &DataRecord)> {
+    telegram
+        .records
+        .iter()
+        .filter_map(|record| DataPoint::of(record).map(|point| (point, record)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::Records;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_transport_header() {
+        let data = [0x2A, 0x00, 0x10, 0x05, 0x2F, 0x2F];
+        let (header, rest) = TransportHeader::parse(CI_RESPONSE_SHORT_HEADER, &data).unwrap();
+        assert_eq!(rest, [0x2F, 0x2F]);
+        assert_eq!(header.security_profile(), SecurityProfile::A);
+        let configuration = header.configuration().unwrap();
+        assert_eq!(configuration.encrypted_blocks(), 1);
+
+        let (header, rest) = TransportHeader::parse(CI_RESPONSE_NO_HEADER, &data).unwrap();
+        assert_eq!((header, rest), (TransportHeader::None, &data[..]));
+        assert_eq!(header.security_profile(), SecurityProfile::None);
+
+        assert_eq!(
+            TransportHeader::parse(CI_RESPONSE_SHORT_HEADER, &data[..3]),
+            Err(TelegramError::UnexpectedEnd)
+        );
+        assert_eq!(
+            Configuration::from(0x0D00).security_profile(),
+            SecurityProfile::C
+        );
+    }
+
+    #[test]
+    fn test_data_points() {
+        // volume, volume at the due date, the due date, maximum flow, flow at a tariff and
+        // error flags
+        let data = [
+            0x0C, 0x13, 0x45, 0x23, 0x01, 0x00, 0x4C, 0x13, 0x00, 0x20, 0x01, 0x00, 0x42, 0x6C,
+            0x9F, 0x2C, 0x1B, 0x3B, 0x01, 0x00, 0x00, 0x8B, 0x10, 0x3B, 0x02, 0x00, 0x00, 0x01,
+            0xFD, 0x17, 0x00,
+        ];
+        let points = Records::new(&data)
+            .map(|record| DataPoint::of(&record.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            [
+                Some(DataPoint::CurrentVolume),
+                Some(DataPoint::DueDateVolume),
+                Some(DataPoint::DueDate),
+                Some(DataPoint::MaximumVolumeFlow),
+                None,
+                Some(DataPoint::ErrorFlags),
+            ]
+        );
+        assert_eq!(DataPoint::DueDate.to_string(), "due_date");
+    }
+}
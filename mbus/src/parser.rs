@@ -1,122 +1,360 @@
 use crate::utils::calculate_checksum;
-use crate::{Frame, FRAME_END, LONG_START, SHORT_START, SINGLE_CHAR};
+use crate::{FrameRef, FRAME_END, LONG_START, SHORT_START, SINGLE_CHAR};
+use core::fmt;
 use nom::{
     branch::alt,
     bytes::streaming::{tag, take},
-    combinator::{cut, map_res},
+    combinator::cut,
     sequence::{tuple, Tuple},
-    Err, IResult, Parser,
+    Err, IResult, Needed, Parser,
 };
 
-#[derive(Debug, Eq, PartialEq)]
-pub enum FrameParseError {
-    MalformedChecksum,
-    InconsistentLengthValues,
-    Nom(nom::error::ErrorKind),
+/// Reasons a frame could not be parsed. Offsets count from the start of the parsed bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The frame is not complete, at least `needed` more bytes are required.
+    Incomplete { needed: usize },
+    BadChecksum {
+        offset: usize,
+        expected: u8,
+        actual: u8,
+    },
+    /// The two L-fields of a long frame differ, `offset` points at the second one.
+    LengthMismatch {
+        offset: usize,
+        first: u8,
+        second: u8,
+    },
+    /// The L-field is too small to hold the control, address and CI fields.
+    BadLength { offset: usize, length: usize },
+    /// A byte that is not allowed at its position, e.g. instead of a start or stop byte.
+    UnexpectedByte { offset: usize, byte: u8 },
 }
 
-impl<'a> nom::error::ParseError<&'a [u8]> for FrameParseError {
-    fn from_error_kind(_: &'a [u8], kind: nom::error::ErrorKind) -> Self {
-        Self::Nom(kind)
+impl ParseError {
+    /// Offset of the offending byte, unless the frame is incomplete.
+    pub fn offset(&self) -> Option<usize> {
+        match *self {
+            ParseError::Incomplete { .. } => None,
+            ParseError::BadChecksum { offset, .. }
+            | ParseError::LengthMismatch { offset, .. }
+            | ParseError::BadLength { offset, .. }
+            | ParseError::UnexpectedByte { offset, .. } => Some(offset),
+        }
     }
 
-    fn append(_: &'a [u8], _: nom::error::ErrorKind, other: Self) -> Self {
-        other
+    fn with_offset(mut self, new_offset: usize) -> Self {
+        match &mut self {
+            ParseError::Incomplete { .. } => {}
+            ParseError::BadChecksum { offset, .. }
+            | ParseError::LengthMismatch { offset, .. }
+            | ParseError::BadLength { offset, .. }
+            | ParseError::UnexpectedByte { offset, .. } => *offset = new_offset,
+        }
+        self
     }
 }
 
-impl<'a> nom::error::FromExternalError<&'a [u8], FrameParseError> for FrameParseError {
-    fn from_external_error(_: &'a [u8], _: nom::error::ErrorKind, e: FrameParseError) -> Self {
-        e
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Incomplete { needed } => {
+                write!(f, "incomplete frame, need at least {} more bytes", needed)
+            }
+            ParseError::BadChecksum {
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "checksum mismatch at offset {}: expected 0x{:02X}, got 0x{:02X}",
+                offset, expected, actual
+            ),
+            ParseError::LengthMismatch {
+                offset,
+                first,
+                second,
+            } => write!(
+                f,
+                "L-field mismatch at offset {}: 0x{:02X} and 0x{:02X}",
+                offset, first, second
+            ),
+            ParseError::BadLength { offset, length } => {
+                write!(f, "invalid L-field {} at offset {}", length, offset)
+            }
+            ParseError::UnexpectedByte { offset, byte } => {
+                write!(f, "unexpected byte 0x{:02X} at offset {}", byte, offset)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Keeps nom's error traits off the public error type. Parsers only know the rest of the input
+/// at the offending byte, so its offset is filled in once the error reaches [`parse_frame`].
+#[derive(Debug)]
+pub(crate) struct Error {
+    error: ParseError,
+    remaining: usize,
+}
+
+impl Error {
+    fn at(i: &[u8], error: ParseError) -> Err<Self> {
+        Err::Failure(Self {
+            error,
+            remaining: i.len(),
+        })
     }
 }
 
-fn tag_short_start(i: &[u8]) -> IResult<&[u8], &[u8], FrameParseError> {
+impl<'a> nom::error::ParseError<&'a [u8]> for Error {
+    fn from_error_kind(i: &'a [u8], _: nom::error::ErrorKind) -> Self {
+        Self {
+            error: match i.first() {
+                Some(b) => ParseError::UnexpectedByte {
+                    offset: 0,
+                    byte: *b,
+                },
+                None => ParseError::Incomplete { needed: 1 },
+            },
+            remaining: i.len(),
+        }
+    }
+
+    fn append(_: &'a [u8], _: nom::error::ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+fn tag_short_start(i: &[u8]) -> IResult<&[u8], &[u8], Error> {
     tag(&[SHORT_START])(i)
 }
 
-fn tag_long_start(i: &[u8]) -> IResult<&[u8], &[u8], FrameParseError> {
+fn tag_long_start(i: &[u8]) -> IResult<&[u8], &[u8], Error> {
     tag(&[LONG_START])(i)
 }
 
-fn tag_frame_end(i: &[u8]) -> IResult<&[u8], &[u8], FrameParseError> {
-    tag(&[FRAME_END])(i)
+fn tag_frame_end(i: &[u8]) -> IResult<&[u8], &[u8], Error> {
+    cut(tag(&[FRAME_END]))(i)
+}
+
+/// Outcome of checking the checksum of a frame parsed with [`crate::Frame::try_parse_lenient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumStatus {
+    Valid,
+    Invalid { expected: u8, actual: u8 },
 }
 
+type Checksummed<'a> = (&'a [u8], ChecksumStatus);
+
 fn checksummed_buf<'a>(
     n: usize,
-) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], &'a [u8], FrameParseError> {
-    cut(map_res(take(n), |i: &[u8]| {
-        let l = i.len();
-        if calculate_checksum(&i[0..l - 1]) != i[l - 1] {
-            Err(FrameParseError::MalformedChecksum)
+    lenient: bool,
+) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], Checksummed<'a>, Error> {
+    move |i| {
+        let (rest, buf) = take(n)(i)?;
+        let (data, checksum) = buf.split_at(n - 1);
+        let expected = calculate_checksum(data);
+        if expected == checksum[0] {
+            Ok((rest, (data, ChecksumStatus::Valid)))
+        } else if lenient {
+            let status = ChecksumStatus::Invalid {
+                expected,
+                actual: checksum[0],
+            };
+            Ok((rest, (data, status)))
         } else {
-            Ok(&i[0..l - 1])
+            Err(Error::at(
+                &i[n - 1..],
+                ParseError::BadChecksum {
+                    offset: 0,
+                    expected,
+                    actual: checksum[0],
+                },
+            ))
         }
-    }))
+    }
 }
 
-fn length_value(i: &[u8]) -> IResult<&[u8], usize, FrameParseError> {
-    cut(map_res(take(2usize), |i: &[u8]| {
-        if i[0] != i[1] {
-            Err(FrameParseError::InconsistentLengthValues)
-        } else {
-            Ok(i[0] as usize)
-        }
-    }))(i)
+fn length_value(i: &[u8]) -> IResult<&[u8], usize, Error> {
+    let (rest, buf) = take(2usize)(i)?;
+    if buf[0] != buf[1] {
+        return Err(Error::at(
+            &i[1..],
+            ParseError::LengthMismatch {
+                offset: 0,
+                first: buf[0],
+                second: buf[1],
+            },
+        ));
+    }
+    Ok((rest, usize::from(buf[0])))
 }
 
-fn single(i: &[u8]) -> IResult<&[u8], Frame, FrameParseError> {
-    tag(&[SINGLE_CHAR]).map(|_| Frame::Single).parse(i)
+#[cfg(feature = "extended-length")]
+fn extended_length_value(i: &[u8]) -> IResult<&[u8], usize, Error> {
+    nom::number::streaming::le_u16.map(usize::from).parse(i)
 }
 
-fn short_frame(i: &[u8]) -> IResult<&[u8], Frame, FrameParseError> {
-    tuple((tag_short_start, checksummed_buf(3), tag_frame_end))
-        .map(|(_, i, _)| Frame::Short {
-            control: i[0],
-            address: i[1],
+type Parsed<'a> = (FrameRef<'a>, ChecksumStatus);
+
+fn single(i: &[u8]) -> IResult<&[u8], Parsed<'_>, Error> {
+    tag(&[SINGLE_CHAR])
+        .map(|_| (FrameRef::Single, ChecksumStatus::Valid))
+        .parse(i)
+}
+
+fn short_frame(i: &[u8], lenient: bool) -> IResult<&[u8], Parsed<'_>, Error> {
+    tuple((tag_short_start, checksummed_buf(3, lenient), tag_frame_end))
+        .map(|(_, (i, status), _)| {
+            let frame = FrameRef::Short {
+                control: i[0],
+                address: i[1],
+            };
+            (frame, status)
         })
         .parse(i)
 }
 
-fn long_frame(i: &[u8]) -> IResult<&[u8], Frame, FrameParseError> {
-    let (i, (_, length)) = (tag_long_start, length_value).parse(i)?;
-    let (i, (_, buf, _)) = (tag_long_start, checksummed_buf(length + 1), tag_frame_end).parse(i)?;
+fn long_frame(i: &[u8], lenient: bool) -> IResult<&[u8], Parsed<'_>, Error> {
+    let (rest, (_, length)) = (tag_long_start, length_value).parse(i)?;
+    let length_field = &i[1..];
+    let i = rest;
+    #[cfg(feature = "extended-length")]
+    let (i, length) = if length == crate::EXTENDED_LENGTH as usize {
+        // `68 FF FF 68` also starts a standard frame with 252 bytes of user data, which wins
+        // when it checksums
+        if i.first() == Some(&LONG_START) {
+            match long_frame_body(i, length_field, length, lenient) {
+                Ok(parsed @ (_, (_, ChecksumStatus::Valid))) => return Ok(parsed),
+                Err(Err::Incomplete(needed)) => return Err(Err::Incomplete(needed)),
+                _ => {}
+            }
+        }
+        let (i, length) = extended_length_value(i)?;
+        // shorter frames have to use the standard L-field
+        if length <= usize::from(u8::MAX) {
+            return Err(Error::at(
+                length_field,
+                ParseError::BadLength { offset: 0, length },
+            ));
+        }
+        (i, length)
+    } else {
+        (i, length)
+    };
+    long_frame_body(i, length_field, length, lenient)
+}
 
+/// Parses the rest of a long frame of `length` after its L-field(s).
+fn long_frame_body<'a>(
+    i: &'a [u8],
+    length_field: &'a [u8],
+    length: usize,
+    lenient: bool,
+) -> IResult<&'a [u8], Parsed<'a>, Error> {
+    // control, address and CI are mandatory
     if length < 3 {
-        return Err(Err::Failure(FrameParseError::InconsistentLengthValues));
+        return Err(Error::at(
+            length_field,
+            ParseError::BadLength { offset: 0, length },
+        ));
     }
+    let (i, (_, (buf, status), _)) = (
+        cut(tag_long_start),
+        checksummed_buf(length + 1, lenient),
+        tag_frame_end,
+    )
+        .parse(i)?;
 
     let frame = if length == 3 {
-        Frame::Control {
+        FrameRef::Control {
             control: buf[0],
             address: buf[1],
             control_information: buf[2],
         }
     } else {
-        Frame::Long {
+        FrameRef::Long {
             control: buf[0],
             address: buf[1],
             control_information: buf[2],
-            data: Vec::from(&buf[3..]),
+            data: &buf[3..],
         }
     };
 
-    Ok((i, frame))
+    Ok((i, (frame, status)))
+}
+
+/// Whether `i` starts with the complete header of a long frame: the start byte, the repeated
+/// L-field and the second start byte. Until then, the start byte might be line noise.
+pub fn long_header_complete(i: &[u8]) -> bool {
+    match i {
+        #[cfg(feature = "extended-length")]
+        [LONG_START, crate::EXTENDED_LENGTH, crate::EXTENDED_LENGTH, _, _, LONG_START, ..] => true,
+        [LONG_START, first, second, LONG_START, ..] => first == second,
+        _ => false,
+    }
+}
+
+pub fn frame_length_hint(i: &[u8]) -> usize {
+    match i {
+        [] => 1,
+        [SHORT_START, ..] => 5,
+        // a standard frame with 252 bytes of user data, unless it turns out not to be
+        #[cfg(feature = "extended-length")]
+        [LONG_START, crate::EXTENDED_LENGTH, crate::EXTENDED_LENGTH, LONG_START, ..]
+            if i.len() < usize::from(crate::EXTENDED_LENGTH) + 6 =>
+        {
+            usize::from(crate::EXTENDED_LENGTH) + 6
+        }
+        #[cfg(feature = "extended-length")]
+        [LONG_START, crate::EXTENDED_LENGTH, _, lo, hi, ..] => {
+            usize::from(u16::from_le_bytes([*lo, *hi])) + 8
+        }
+        #[cfg(feature = "extended-length")]
+        [LONG_START, crate::EXTENDED_LENGTH, ..] => 6,
+        [LONG_START, length, ..] => usize::from(*length).max(3) + 6,
+        // the smallest long frame is a control frame
+        [LONG_START] => 9,
+        // single character or garbage, the parser will tell
+        _ => 1,
+    }
+}
+
+pub fn parse_frame(i: &[u8]) -> Result<(&[u8], FrameRef<'_>), ParseError> {
+    parse_frame_with(i, false).map(|(rest, (frame, _))| (rest, frame))
+}
+
+/// Like [`parse_frame`], but accepts frames with a checksum mismatch.
+pub fn parse_frame_lenient(i: &[u8]) -> Result<(&[u8], Parsed<'_>), ParseError> {
+    parse_frame_with(i, true)
 }
 
-pub type ParseError = Err<FrameParseError>;
-pub type ParseSizeNeeded = nom::Needed;
-pub fn parse_frame(i: &[u8]) -> IResult<&[u8], Frame, FrameParseError> {
-    alt((single, short_frame, long_frame))(i)
+fn parse_frame_with(i: &[u8], lenient: bool) -> Result<(&[u8], Parsed<'_>), ParseError> {
+    alt((
+        single,
+        |i| short_frame(i, lenient),
+        |i| long_frame(i, lenient),
+    ))(i)
+    .map_err(|err| match err {
+        Err::Incomplete(Needed::Size(needed)) => ParseError::Incomplete {
+            needed: needed.get(),
+        },
+        Err::Incomplete(Needed::Unknown) => ParseError::Incomplete { needed: 1 },
+        Err::Error(err) | Err::Failure(err) => err.error.with_offset(i.len() - err.remaining),
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Frame;
+    use alloc::string::ToString;
 
     #[test]
-    fn test_parse_frame() -> Result<(), Box<dyn std::error::Error>> {
+    fn test_parse_frame() -> Result<(), ParseError> {
         assert_eq!(Frame::from_bytes(b"\xe5")?, Frame::Single,);
         assert_eq!(
             Frame::from_bytes(b"\x10\x7b\x49\xc4\x16")?,
@@ -144,14 +382,153 @@ mod tests {
         );
 
         // faulty frames
-        assert!(matches!(
+        assert_eq!(
             Frame::from_bytes(b"\x10\x7b\x49\xc5\x16"),
-            Err(Err::Failure(FrameParseError::MalformedChecksum))
-        ));
-        assert!(matches!(
+            Err(ParseError::BadChecksum {
+                offset: 3,
+                expected: 0xC4,
+                actual: 0xC5
+            })
+        );
+        assert_eq!(
             Frame::from_bytes(b"\x68\x03\x02\x68\x53\xFE\xBD\x0E\x16"),
-            Err(Err::Failure(FrameParseError::InconsistentLengthValues))
-        ));
+            Err(ParseError::LengthMismatch {
+                offset: 2,
+                first: 0x03,
+                second: 0x02
+            })
+        );
+        assert_eq!(
+            Frame::from_bytes(b"\x68\x02\x02\x68\x53\xFE\x51\x16"),
+            Err(ParseError::BadLength {
+                offset: 1,
+                length: 2
+            })
+        );
+        assert_eq!(
+            Frame::from_bytes(b"\x10\x7b\x49\xc4\x17"),
+            Err(ParseError::UnexpectedByte {
+                offset: 4,
+                byte: 0x17
+            })
+        );
+        assert_eq!(
+            Frame::from_bytes(b"\x42"),
+            Err(ParseError::UnexpectedByte {
+                offset: 0,
+                byte: 0x42
+            })
+        );
+        assert_eq!(
+            Frame::from_bytes(b"\x68\x06\x06\x68\x53"),
+            Err(ParseError::Incomplete { needed: 6 })
+        );
+        assert_eq!(
+            Frame::try_parse_lenient(b"\x10\x7b\x49\xc5\x16"),
+            Ok((
+                5,
+                Frame::Short {
+                    address: 0x49,
+                    control: 0x7B
+                },
+                ChecksumStatus::Invalid {
+                    expected: 0xC4,
+                    actual: 0xC5
+                }
+            ))
+        );
+        assert_eq!(
+            Frame::try_parse_lenient(b"\x10\x7b\x49\xc4\x16").map(|(_, _, status)| status),
+            Ok(ChecksumStatus::Valid)
+        );
+        assert_eq!(
+            Frame::try_parse_lenient(b"\x68\x03\x02\x68\x53\xFE\xBD\x0E\x16")
+                .map(|(_, _, status)| status),
+            Frame::from_bytes(b"\x68\x03\x02\x68\x53\xFE\xBD\x0E\x16")
+                .map(|_| ChecksumStatus::Valid)
+        );
+        assert_eq!(
+            Frame::from_bytes(b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x26\x16")
+                .unwrap_err()
+                .to_string(),
+            "checksum mismatch at offset 10: expected 0x25, got 0x26"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "extended-length")]
+    #[test]
+    fn test_parse_extended_length_frame() -> Result<(), ParseError> {
+        use alloc::vec;
+
+        assert_eq!(
+            Frame::from_bytes(b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16")?,
+            Frame::Long {
+                address: 0xFE,
+                control: 0x53,
+                control_information: 0x51,
+                data: (*b"\x01\x7A\x08").into()
+            }
+        );
+
+        let data = vec![0x01; 300];
+        let mut bytes = vec![0x68, 0xFF, 0xFF, 0x2F, 0x01, 0x68, 0x53, 0xFE, 0x51];
+        bytes.extend_from_slice(&data);
+        bytes.extend_from_slice(&[0xCE, 0x16]);
+        assert_eq!(
+            Frame::from_bytes(&bytes)?,
+            Frame::Long {
+                address: 0xFE,
+                control: 0x53,
+                control_information: 0x51,
+                data,
+            }
+        );
+
+        // an L-field of 0xFF followed by the second start byte is a standard frame
+        let frame = Frame::Long {
+            address: 0xFE,
+            control: 0x53,
+            control_information: 0x51,
+            data: vec![0x01; 252],
+        };
+        let bytes = frame.to_bytes();
+        assert_eq!(bytes[..4], [0x68, 0xFF, 0xFF, 0x68]);
+        assert_eq!(bytes.len(), 261);
+        assert_eq!(Frame::length_hint(&bytes[..4]), 261);
+        assert_eq!(Frame::from_bytes(&bytes)?, frame);
+
+        // unless it does not checksum, as an extended frame of 0x168 bytes
+        let data = vec![0x01; 0x168 - 3];
+        let mut bytes = vec![0x68, 0xFF, 0xFF, 0x68, 0x01, 0x68, 0x53, 0xFE, 0x51];
+        bytes.extend_from_slice(&data);
+        bytes.extend_from_slice(&[0x07, 0x16]);
+        assert_eq!(
+            Frame::try_parse(&bytes[..261]),
+            Err(ParseError::Incomplete { needed: 106 })
+        );
+        assert_eq!(
+            Frame::from_bytes(&bytes)?,
+            Frame::Long {
+                address: 0xFE,
+                control: 0x53,
+                control_information: 0x51,
+                data,
+            }
+        );
+
+        // an extended length that fits the standard L-field is rejected
+        let mut bytes = vec![0x68, 0xFF, 0xFF, 0x10, 0x00, 0x68, 0x53, 0xFE, 0x51];
+        bytes.extend_from_slice(&[0x01; 13]);
+        bytes.extend_from_slice(&[0xB5, 0x16]);
+        assert_eq!(
+            Frame::try_parse(&bytes),
+            Err(ParseError::BadLength {
+                offset: 1,
+                length: 0x10
+            })
+        );
 
         Ok(())
     }
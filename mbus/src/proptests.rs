@@ -0,0 +1,31 @@
+use crate::strategy::frame;
+use crate::Frame;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn test_round_trip(frame in frame()) {
+        let bytes = frame.to_bytes();
+        prop_assert_eq!(bytes.len(), frame.iter_bytes().count());
+        prop_assert_eq!(Frame::from_bytes(&bytes), Ok(frame));
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_round_trip(bytes in vec(any::<u8>(), 0..1024)) {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        if let Ok(frame) = Frame::arbitrary(&mut Unstructured::new(&bytes)) {
+            prop_assert_eq!(frame.validate(), Ok(()));
+            prop_assert_eq!(Frame::from_bytes(&frame.to_bytes()), Ok(frame));
+        }
+    }
+
+    #[test]
+    fn test_parse_random_bytes(bytes in vec(any::<u8>(), 0..512)) {
+        let _ = Frame::from_bytes(&bytes);
+        let _ = Frame::try_parse(&bytes);
+        let _ = Frame::parse_all(&bytes);
+    }
+}
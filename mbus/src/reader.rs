@@ -0,0 +1,114 @@
+use crate::{Frame, ParseError};
+use alloc::vec::Vec;
+
+/// Buffers bytes as they come in from the bus and hands out the frames in them, skipping line
+/// noise. It does no I/O itself, so it works with blocking readers and embedded loops alike.
+#[derive(Debug, Default)]
+pub struct FrameReader {
+    buffer: Vec<u8>,
+    // don't bother parsing until this many bytes are buffered
+    needed_bytes: usize,
+    skipped_bytes: usize,
+}
+
+impl FrameReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Returns the next complete frame, or `None` until more bytes are pushed.
+    pub fn next_frame(&mut self) -> Option<Frame> {
+        if self.buffer.len() < self.needed_bytes {
+            return None;
+        }
+
+        let (skipped, result) = Frame::scan(&self.buffer);
+        self.buffer.drain(..skipped);
+        self.skipped_bytes += skipped;
+        match result {
+            Ok((bytes_read, frame)) => {
+                self.buffer.drain(..bytes_read);
+                self.needed_bytes = 0;
+                Some(frame)
+            }
+            Err(err) => {
+                let missing = match err {
+                    ParseError::Incomplete { needed } => needed,
+                    _ => 1,
+                };
+                self.needed_bytes = self.buffer.len() + missing;
+                None
+            }
+        }
+    }
+
+    /// Number of bytes buffered that are not part of a returned frame yet.
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Total number of bytes dropped as line noise.
+    pub fn skipped_bytes(&self) -> usize {
+        self.skipped_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_reader() {
+        let mut reader = FrameReader::new();
+        let mut frames = vec![];
+        for b in b"\x00\xE5\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16\x10\x7b" {
+            reader.push_bytes(&[*b]);
+            frames.extend(reader.next_frame());
+        }
+        reader.push_bytes(b"\x49\xc4\x16\x10");
+        frames.extend(reader.next_frame());
+        assert_eq!(reader.next_frame(), None);
+
+        assert_eq!(
+            frames,
+            vec![
+                Frame::Single,
+                Frame::from_bytes(b"\x68\x06\x06\x68\x53\xFE\x51\x01\x7A\x08\x25\x16").unwrap(),
+                Frame::Short {
+                    control: 0x7B,
+                    address: 0x49
+                },
+            ]
+        );
+        assert_eq!(reader.skipped_bytes(), 1);
+        assert_eq!(reader.buffered_bytes(), 1);
+
+        // a stray start byte does not hold back the ACK after it
+        let mut reader = FrameReader::new();
+        reader.push_bytes(b"\x68");
+        assert_eq!(reader.next_frame(), None);
+        reader.push_bytes(b"\xE5");
+        assert_eq!(reader.next_frame(), Some(Frame::Single));
+
+        // nor are an ACK and a short frame in the payload of a long frame taken for frames
+        let frame = Frame::Long {
+            control: 0x08,
+            address: 0x01,
+            control_information: 0x72,
+            data: vec![0xE5, 0x10, 0x7B, 0x49, 0xC4, 0x16],
+        };
+        let mut reader = FrameReader::new();
+        let mut frames = vec![];
+        for chunk in frame.to_bytes().chunks(3) {
+            reader.push_bytes(chunk);
+            frames.extend(reader.next_frame());
+        }
+        assert_eq!(frames, vec![frame]);
+        assert_eq!(reader.skipped_bytes(), 0);
+    }
+}
@@ -0,0 +1,33 @@
+//! Serializes byte buffers as hex strings, e.g. `"CAFEBABE"`, so serialized frames stay
+//! readable. Whitespace is ignored when deserializing.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        write!(hex, "{:02X}", b).expect("writing to a string cannot fail");
+    }
+    serializer.serialize_str(&hex)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    let digits = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| D::Error::custom("invalid hex digit"))?;
+    if !digits.len().is_multiple_of(2) {
+        return Err(D::Error::custom("odd number of hex digits"));
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
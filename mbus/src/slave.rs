@@ -0,0 +1,177 @@
+//! Link layer procedure (EN 13757-2) of a slave, as a state machine that does no I/O itself:
+//! every frame received from the bus goes through [`Slave::handle_frame`], which tells what to
+//! send back.
+
+use crate::address::{BROADCAST_NO_REPLY, BROADCAST_WITH_REPLY, NETWORK_LAYER};
+use crate::control::FunctionCode;
+use crate::telegram::{Header, SecondaryAddress, CI_SLAVE_SELECT};
+use crate::Frame;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+#[derive(Debug)]
+pub struct Slave {
+    address: u8,
+    header: Header,
+    selected: bool,
+    /// FCB of the last REQ_UD2 with FCV set, `None` after a reset.
+    last_fcb: Option<bool>,
+    /// The front response has been sent at least once.
+    delivered: bool,
+    responses: VecDeque<(u8, Vec<u8>)>,
+}
+
+impl Slave {
+    /// Slave with primary `address`, which can also be selected by the secondary address in
+    /// `header`.
+    pub fn new(address: u8, header: Header) -> Self {
+        Self {
+            address,
+            header,
+            selected: false,
+            last_fcb: None,
+            delivered: false,
+            responses: VecDeque::new(),
+        }
+    }
+
+    /// Queues the user data of an RSP_UD answering REQ_UD2. Queued responses are sent in order,
+    /// moving on when the master toggles the FCB; the last one is repeated until another one is
+    /// queued.
+    pub fn queue_response(&mut self, control_information: u8, data: Vec<u8>) {
+        self.responses.push_back((control_information, data));
+    }
+
+    /// Whether the slave was selected by its secondary address and answers at address 0xFD.
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    /// Handles a frame received from the bus and returns the frame to send in reply, if any.
+    pub fn handle_frame(&mut self, frame: &Frame) -> Option<Frame> {
+        let addressed = frame.addressed()?;
+        let control = frame.control_field()?;
+        if !control.is_from_master() {
+            return None;
+        }
+
+        let address = addressed.address();
+        if address == NETWORK_LAYER
+            && addressed.control_information() == Some(CI_SLAVE_SELECT)
+            && control.function() == FunctionCode::SndUd
+        {
+            self.selected = SecondaryAddress::parse(addressed.data())
+                .is_ok_and(|selection| selection.matches(&self.header));
+            return self.selected.then(Frame::ack);
+        }
+
+        let for_us = address == self.address
+            || address == BROADCAST_WITH_REPLY
+            || address == BROADCAST_NO_REPLY
+            || (address == NETWORK_LAYER && self.selected);
+        if !for_us {
+            return None;
+        }
+
+        let reply = match control.function() {
+            FunctionCode::SndNke => {
+                self.last_fcb = None;
+                self.delivered = false;
+                // a reset of the network layer also ends the selection
+                if address == NETWORK_LAYER {
+                    self.selected = false;
+                }
+                Some(Frame::ack())
+            }
+            FunctionCode::SndUd => Some(Frame::ack()),
+            FunctionCode::ReqUd2 => {
+                // without FCV the master does not count frames, so neither do we
+                if control.fcv() {
+                    let toggled = self.last_fcb != Some(control.fcb());
+                    self.last_fcb = Some(control.fcb());
+                    if toggled && self.delivered && self.responses.len() > 1 {
+                        self.responses.pop_front();
+                    }
+                }
+                let (control_information, data) = self.responses.front()?;
+                let response = Frame::rsp_ud(self.address, *control_information, data.clone());
+                self.delivered = true;
+                Some(response)
+            }
+            _ => None,
+        };
+        reply.filter(|_| address != BROADCAST_NO_REPLY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::{ManufacturerId, Medium, Status};
+    use alloc::vec;
+
+    fn slave() -> Slave {
+        let mut slave = Slave::new(
+            0x01,
+            Header {
+                identification: 0x12345678,
+                manufacturer: ManufacturerId::from(0x4024),
+                version: 0x01,
+                medium: Medium::Water,
+                access_number: 0x55,
+                status: Status::default(),
+                signature: 0x0000,
+            },
+        );
+        slave.queue_response(0x72, vec![0x01]);
+        slave.queue_response(0x72, vec![0x02]);
+        slave
+    }
+
+    #[test]
+    fn test_readout() {
+        let mut slave = slave();
+        assert_eq!(
+            slave.handle_frame(&Frame::snd_nke(0x01)),
+            Some(Frame::ack())
+        );
+        assert_eq!(slave.handle_frame(&Frame::snd_nke(0x02)), None);
+
+        let first = Some(Frame::rsp_ud(0x01, 0x72, [0x01]));
+        let second = Some(Frame::rsp_ud(0x01, 0x72, [0x02]));
+        assert_eq!(slave.handle_frame(&Frame::req_ud2(0x01, true)), first);
+        // the master did not get the response and repeats its request
+        assert_eq!(slave.handle_frame(&Frame::req_ud2(0x01, true)), first);
+        assert_eq!(slave.handle_frame(&Frame::req_ud2(0x01, false)), second);
+        assert_eq!(slave.handle_frame(&Frame::req_ud2(0x01, true)), second);
+
+        assert_eq!(
+            slave.handle_frame(&Frame::snd_nke(BROADCAST_NO_REPLY)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_select() {
+        let mut slave = slave();
+        let mut selection = SecondaryAddress::WILDCARD;
+        selection.identification = 0x1234FFFF;
+        assert_eq!(
+            slave.handle_frame(&selection.to_frame()),
+            Some(Frame::ack())
+        );
+        assert!(slave.is_selected());
+        assert_eq!(
+            slave.handle_frame(&Frame::req_ud2(NETWORK_LAYER, true)),
+            Some(Frame::rsp_ud(0x01, 0x72, [0x01]))
+        );
+
+        selection.identification = 0x8765FFFF;
+        assert_eq!(slave.handle_frame(&selection.to_frame()), None);
+        assert!(!slave.is_selected());
+        assert_eq!(
+            slave.handle_frame(&Frame::req_ud2(NETWORK_LAYER, false)),
+            None
+        );
+    }
+}
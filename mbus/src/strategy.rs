@@ -0,0 +1,36 @@
+//! Proptest strategies, enabled with the `proptest` feature.
+
+use crate::{Frame, MAX_DATA_LENGTH};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::strategy::LazyJust;
+
+/// Valid frames of every kind, with long frames carrying 1 up to [`MAX_DATA_LENGTH`] bytes.
+pub fn frame() -> impl Strategy<Value = Frame> {
+    prop_oneof![
+        LazyJust::new(|| Frame::Single),
+        (any::<u8>(), any::<u8>()).prop_map(|(control, address)| Frame::Short { control, address }),
+        (any::<u8>(), any::<u8>(), any::<u8>()).prop_map(
+            |(control, address, control_information)| Frame::Control {
+                control,
+                address,
+                control_information,
+            }
+        ),
+        (
+            any::<u8>(),
+            any::<u8>(),
+            any::<u8>(),
+            // an empty long frame would be a control frame
+            vec(any::<u8>(), 1..=MAX_DATA_LENGTH),
+        )
+            .prop_map(
+                |(control, address, control_information, data)| Frame::Long {
+                    control,
+                    address,
+                    control_information,
+                    data,
+                }
+            ),
+    ]
+}
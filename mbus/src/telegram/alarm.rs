@@ -0,0 +1,63 @@
+use super::{Status, TelegramError, CI_ALARM_STATUS};
+use crate::Frame;
+use alloc::vec::Vec;
+
+/// Alarm status a slave returns with CI 0x71 in response to REQ_UD1. The alarm byte is decoded
+/// with the same flags as the status byte of the variable data header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlarmStatus(pub Status);
+
+impl AlarmStatus {
+    pub fn from_frame(frame: &Frame) -> Result<Self, TelegramError> {
+        match frame {
+            Frame::Long {
+                control_information: CI_ALARM_STATUS,
+                data,
+                ..
+            } => data
+                .first()
+                .map(|alarm| Self((*alarm).into()))
+                .ok_or(TelegramError::UnexpectedEnd),
+            Frame::Control {
+                control_information,
+                ..
+            }
+            | Frame::Long {
+                control_information,
+                ..
+            } => Err(TelegramError::UnsupportedControlInformation(
+                *control_information,
+            )),
+            Frame::Single | Frame::Short { .. } => Err(TelegramError::NoUserData),
+        }
+    }
+
+    /// Builds the RSP_UD a slave sends to report its alarm status.
+    pub fn to_frame(self, address: u8) -> Frame {
+        Frame::Long {
+            control: crate::control::RSP_UD,
+            address,
+            control_information: CI_ALARM_STATUS,
+            data: Vec::from([self.0.bits()]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let alarm = AlarmStatus(Status::from(
+            Status::POWER_LOW.bits() | Status::TEMPORARY_ERROR.bits(),
+        ));
+        let frame = alarm.to_frame(0x05);
+        assert_eq!(frame.to_hex_string(), "68 04 04 68 08 05 71 14 92 16");
+
+        let decoded = AlarmStatus::from_frame(&frame).unwrap();
+        assert_eq!(decoded, alarm);
+        assert!(decoded.0.contains(Status::POWER_LOW));
+        assert!(decoded.0.has_error());
+    }
+}
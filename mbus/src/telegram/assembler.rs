@@ -0,0 +1,163 @@
+use super::{DataRecord, Header, Telegram, TelegramError};
+use alloc::vec::Vec;
+
+/// Merges the telegrams of a readout that is spread over several RSP_UDs, each but the last one
+/// ending with DIF 0x1F.
+#[derive(Debug, Clone, Default)]
+pub struct TelegramAssembler {
+    header: Option<Header>,
+    records: Vec<DataRecord>,
+    access_numbers: Vec<u8>,
+    more_records_follow: bool,
+    manufacturer_data: Vec<u8>,
+}
+
+impl TelegramAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the next telegram of the readout. A repetition of the telegram merged last,
+    /// recognized by its access number, is ignored as the slave repeats its last response when
+    /// the master did not toggle the FCB. Earlier access numbers may come back as they wrap.
+    pub fn push(&mut self, telegram: Telegram) -> Result<(), TelegramError> {
+        if let Some(header) = &self.header {
+            if header.identification != telegram.header.identification
+                || header.manufacturer != telegram.header.manufacturer
+            {
+                return Err(TelegramError::MismatchedTelegram);
+            }
+            if self.access_numbers.last() == Some(&telegram.header.access_number) {
+                return Ok(());
+            }
+        }
+
+        self.access_numbers.push(telegram.header.access_number);
+        self.more_records_follow = telegram.more_records_follow;
+        // the most recent header carries the most recent status
+        self.header = Some(telegram.header);
+        self.records.extend(telegram.records);
+        self.manufacturer_data.extend(telegram.manufacturer_data);
+        Ok(())
+    }
+
+    /// Whether the master has to send another REQ_UD2, with toggled FCB, to get the rest of the
+    /// readout.
+    pub fn needs_more(&self) -> bool {
+        self.header.is_none() || self.more_records_follow
+    }
+
+    /// Access numbers of the telegrams merged so far.
+    pub fn access_numbers(&self) -> &[u8] {
+        &self.access_numbers
+    }
+
+    pub fn records(&self) -> &[DataRecord] {
+        &self.records
+    }
+
+    /// Returns the combined telegram, or `None` when nothing was pushed.
+    pub fn finish(self) -> Option<Telegram> {
+        Some(Telegram {
+            header: self.header?,
+            records: self.records,
+            more_records_follow: self.more_records_follow,
+            manufacturer_data: self.manufacturer_data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn telegram(access_number: u8, record: u8, more_records_follow: bool) -> Vec<u8> {
+        let mut data = vec![
+            0x78,
+            0x56,
+            0x34,
+            0x12,
+            0x24,
+            0x40,
+            0x01,
+            0x07,
+            access_number,
+            0x00,
+            0x00,
+            0x00,
+            0x01,
+            0x13,
+            record,
+        ];
+        if more_records_follow {
+            data.push(0x1F);
+        }
+        data
+    }
+
+    #[test]
+    fn test_assemble() {
+        let mut assembler = TelegramAssembler::new();
+        assert!(assembler.needs_more());
+
+        assembler
+            .push(Telegram::parse(&telegram(0x10, 0x01, true)).unwrap())
+            .unwrap();
+        assert!(assembler.needs_more());
+
+        // repeated response
+        assembler
+            .push(Telegram::parse(&telegram(0x10, 0x01, true)).unwrap())
+            .unwrap();
+        assembler
+            .push(Telegram::parse(&telegram(0x11, 0x02, false)).unwrap())
+            .unwrap();
+        assert!(!assembler.needs_more());
+        assert_eq!(assembler.access_numbers(), [0x10, 0x11]);
+
+        let telegram = assembler.finish().unwrap();
+        assert_eq!(telegram.header.access_number, 0x11);
+        assert!(!telegram.more_records_follow);
+        assert_eq!(
+            telegram
+                .records
+                .iter()
+                .map(|record| record.data.clone())
+                .collect::<Vec<_>>(),
+            [[0x01], [0x02]]
+        );
+    }
+
+    #[test]
+    fn test_access_number_reused() {
+        let mut assembler = TelegramAssembler::new();
+        for (access_number, record, more_records_follow) in
+            [(0xFF, 0x01, true), (0x00, 0x02, true), (0xFF, 0x03, false)]
+        {
+            assembler
+                .push(
+                    Telegram::parse(&telegram(access_number, record, more_records_follow)).unwrap(),
+                )
+                .unwrap();
+        }
+        assert!(!assembler.needs_more());
+        assert_eq!(assembler.access_numbers(), [0xFF, 0x00, 0xFF]);
+        assert_eq!(assembler.records().len(), 3);
+    }
+
+    #[test]
+    fn test_mismatched() {
+        let mut assembler = TelegramAssembler::new();
+        assembler
+            .push(Telegram::parse(&telegram(0x10, 0x01, true)).unwrap())
+            .unwrap();
+
+        let mut other = Telegram::parse(&telegram(0x11, 0x02, false)).unwrap();
+        other.header.identification = 0x87654321;
+        assert_eq!(
+            assembler.push(other),
+            Err(TelegramError::MismatchedTelegram)
+        );
+    }
+}
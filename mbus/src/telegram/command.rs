@@ -0,0 +1,100 @@
+//! SND_UD telegrams for the standard configuration commands.
+
+use super::{BaudRate, DataField, DataRecord, DataRecordHeader, FunctionField};
+use crate::control::SND_UD;
+use crate::Frame;
+use alloc::vec::Vec;
+
+const CI_DATA_SEND: u8 = 0x51;
+const VIF_BUS_ADDRESS: u8 = 0x7A;
+const VIF_ENHANCED_IDENTIFICATION: u8 = 0x79;
+
+fn data_send(address: u8, record: DataRecord) -> Frame {
+    let mut data = Vec::new();
+    record.encode(&mut data);
+    Frame::Long {
+        control: SND_UD,
+        address,
+        control_information: CI_DATA_SEND,
+        data,
+    }
+}
+
+fn record(data_field: DataField, vif: u8, data: &[u8]) -> DataRecord {
+    DataRecord {
+        header: DataRecordHeader {
+            data_field,
+            function: FunctionField::Instantaneous,
+            storage_number: 0,
+            tariff: 0,
+            subunit: 0,
+        },
+        vif,
+        vife: Vec::new(),
+        plaintext_unit: None,
+        raw_header: Vec::new(),
+        data: data.into(),
+    }
+}
+
+/// Changes the primary address of the slave at `address` to `new_address`.
+pub fn set_primary_address(address: u8, new_address: u8) -> Frame {
+    data_send(
+        address,
+        record(DataField::Int8, VIF_BUS_ADDRESS, &[new_address]),
+    )
+}
+
+/// Changes the identification number (8 BCD digits, e.g. `0x12345678`) of the slave at
+/// `address`, typically sent to the network layer address after selecting the slave.
+pub fn set_identification(address: u8, identification: u32) -> Frame {
+    data_send(
+        address,
+        record(
+            DataField::Bcd8,
+            VIF_ENHANCED_IDENTIFICATION,
+            &identification.to_le_bytes(),
+        ),
+    )
+}
+
+/// Switches the slave at `address` to `baud_rate`, which has to be one of the rates from 300
+/// to 38400 baud. The slave acknowledges at the old baud rate before switching.
+pub fn set_baud_rate(address: u8, baud_rate: u32) -> Option<Frame> {
+    BaudRate::from_baud(baud_rate).map(|rate| switch_baud_rate(address, rate))
+}
+
+/// Switches the slave at `address` to `rate`, see [`set_baud_rate`].
+pub fn switch_baud_rate(address: u8, rate: BaudRate) -> Frame {
+    Frame::Control {
+        control: SND_UD,
+        address,
+        control_information: rate.code(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(
+            set_primary_address(0x01, 0x05).to_hex_string(),
+            "68 06 06 68 53 01 51 01 7A 05 25 16"
+        );
+        assert_eq!(
+            set_identification(0xFD, 0x12345678).to_hex_string(),
+            "68 09 09 68 53 FD 51 0C 79 78 56 34 12 3A 16"
+        );
+        assert_eq!(
+            set_baud_rate(0x01, 9600).map(|frame| frame.to_hex_string()),
+            Some("68 03 03 68 53 01 BD 11 16".into())
+        );
+        assert_eq!(set_baud_rate(0x01, 115200), None);
+        assert_eq!(
+            switch_baud_rate(0x01, BaudRate::Baud2400).to_hex_string(),
+            "68 03 03 68 53 01 BB 0F 16"
+        );
+    }
+}
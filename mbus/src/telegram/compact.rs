@@ -0,0 +1,158 @@
+use super::record::{record_data, record_format};
+use super::{
+    DataRecord, ParseLimits, TelegramError, CI_RESPONSE_COMPACT, CI_RESPONSE_NO_HEADER,
+    CI_RESPONSE_VARIABLE, IDLE_FILLER, MANUFACTURER_DATA, MORE_RECORDS_FOLLOW,
+};
+use crate::crc::calculate_crc;
+use crate::Frame;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use nom::number::complete::le_u16;
+use nom::Offset;
+
+/// Size of the variable data header that precedes the records with CI 0x72.
+const HEADER_LENGTH: usize = 12;
+
+/// Remembers the record formats of full frames, so that compact frames (CI 0x79), which only
+/// carry the data, can be expanded into records again.
+#[derive(Debug, Clone, Default)]
+pub struct FormatCache {
+    formats: BTreeMap<u16, Vec<u8>>,
+}
+
+impl FormatCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers the format of the data records of a full frame and returns its signature.
+    pub fn learn(&mut self, records: &[u8]) -> Result<u16, TelegramError> {
+        let mut format = Vec::new();
+        let mut i = records;
+        loop {
+            match i.first() {
+                None | Some(&MANUFACTURER_DATA) | Some(&MORE_RECORDS_FOLLOW) => break,
+                Some(&IDLE_FILLER) => i = &i[1..],
+                Some(_) => {
+                    let (rest, record) = record_format(ParseLimits::default())(i)?;
+                    format.extend_from_slice(&i[..i.offset(rest)]);
+                    let (rest, _) = record_data(record.data_field(), ParseLimits::default())(rest)?;
+                    i = rest;
+                }
+            }
+        }
+
+        let signature = calculate_crc(&format);
+        self.formats.insert(signature, format);
+        Ok(signature)
+    }
+
+    /// Expands the user data of a compact frame: the format signature, the CRC of the full
+    /// frame's records and the data of each record.
+    pub fn expand(&self, compact: &[u8]) -> Result<Vec<DataRecord>, TelegramError> {
+        let (mut values, (signature, data_crc)) = nom::sequence::tuple((le_u16, le_u16))(compact)?;
+        let mut format = &self
+            .formats
+            .get(&signature)
+            .ok_or(TelegramError::UnknownFormat(signature))?[..];
+
+        let mut records = Vec::new();
+        // the records as they would appear in the full frame
+        let mut full = Vec::new();
+        while !format.is_empty() {
+            let (rest, record) = record_format(ParseLimits::default())(format)?;
+            full.extend_from_slice(&format[..format.offset(rest)]);
+            format = rest;
+
+            let (rest, data) = record_data(record.data_field(), ParseLimits::default())(values)?;
+            full.extend_from_slice(data);
+            values = rest;
+
+            records.push(record.with_data(data));
+        }
+
+        if calculate_crc(&full) != data_crc {
+            return Err(TelegramError::DataCrcMismatch);
+        }
+        Ok(records)
+    }
+
+    /// Learns from full frames and expands compact ones, returning the records of either.
+    pub fn records(&mut self, frame: &Frame) -> Result<Vec<DataRecord>, TelegramError> {
+        match frame {
+            Frame::Long {
+                control_information: CI_RESPONSE_COMPACT,
+                data,
+                ..
+            } => self.expand(data),
+            Frame::Long {
+                control_information: CI_RESPONSE_NO_HEADER,
+                data,
+                ..
+            } => {
+                self.learn(data)?;
+                super::records(data).map(|(records, _)| records)
+            }
+            Frame::Long {
+                control_information: CI_RESPONSE_VARIABLE,
+                data,
+                ..
+            } => {
+                self.learn(data.get(HEADER_LENGTH..).unwrap_or_default())?;
+                super::Telegram::parse(data).map(|telegram| telegram.records)
+            }
+            Frame::Control {
+                control_information,
+                ..
+            }
+            | Frame::Long {
+                control_information,
+                ..
+            } => Err(TelegramError::UnsupportedControlInformation(
+                *control_information,
+            )),
+            Frame::Single | Frame::Short { .. } => Err(TelegramError::NoUserData),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_expand() {
+        let full = [
+            0x03, 0x13, 0x15, 0x31, 0x00, 0x2F, 0xDA, 0x02, 0x3B, 0x13, 0x01,
+        ];
+        let mut cache = FormatCache::new();
+        let signature = cache.learn(&full).unwrap();
+        assert_eq!(signature, calculate_crc(&[0x03, 0x13, 0xDA, 0x02, 0x3B]));
+
+        let data_crc = calculate_crc(&[0x03, 0x13, 0x15, 0x31, 0x00, 0xDA, 0x02, 0x3B, 0x13, 0x01]);
+        let mut compact = vec![];
+        compact.extend_from_slice(&signature.to_le_bytes());
+        compact.extend_from_slice(&data_crc.to_le_bytes());
+        compact.extend_from_slice(&[0x15, 0x31, 0x00, 0x13, 0x01]);
+
+        let frame = Frame::Long {
+            control: 0x08,
+            address: 0x01,
+            control_information: CI_RESPONSE_COMPACT,
+            data: compact.clone(),
+        };
+        let records = cache.records(&frame).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].data, [0x15, 0x31, 0x00]);
+        assert_eq!(records[1].vif, 0x3B);
+        assert_eq!(records[1].data, [0x13, 0x01]);
+
+        compact[4] = 0x16;
+        assert_eq!(cache.expand(&compact), Err(TelegramError::DataCrcMismatch));
+        assert_eq!(
+            FormatCache::new().expand(&compact),
+            Err(TelegramError::UnknownFormat(signature))
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use super::dlms::{
+    DLMS_LONG_HEADER, DLMS_OBIS_LONG_HEADER, DLMS_OBIS_SHORT_HEADER, DLMS_SHORT_HEADER,
+};
+use super::sml::{SML_LONG_HEADER, SML_SHORT_HEADER};
+use super::{
+    CI_ALARM_STATUS, CI_APPLICATION_ERROR, CI_APPLICATION_RESET, CI_RESPONSE_COMPACT,
+    CI_RESPONSE_FIXED, CI_RESPONSE_FIXED_MSB_FIRST, CI_RESPONSE_NO_HEADER, CI_RESPONSE_VARIABLE,
+    CI_RESPONSE_VARIABLE_MSB_FIRST, CI_SLAVE_SELECT,
+};
+
+const DATA_SEND: u8 = 0x51;
+const DATA_SEND_MSB_FIRST: u8 = 0x55;
+const SLAVE_SELECT_MSB_FIRST: u8 = 0x56;
+const SYNCHRONIZE_ACTION: u8 = 0x5C;
+const SET_BAUD_RATE_FIRST: u8 = 0xB8;
+const SET_BAUD_RATE_LAST: u8 = 0xBF;
+
+/// Meaning of the CI field of control and long frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControlInformation {
+    ApplicationReset,
+    DataSend,
+    SlaveSelect,
+    DataSendMsbFirst,
+    SlaveSelectMsbFirst,
+    SynchronizeAction,
+    ApplicationError,
+    AlarmStatus,
+    ResponseVariable,
+    ResponseFixed,
+    ResponseVariableMsbFirst,
+    ResponseFixedMsbFirst,
+    ResponseNoHeader,
+    ResponseCompact,
+    /// Switch the slave to another baud rate, carrying the code (0xB8 to 0xBF).
+    SetBaudRate(u8),
+    /// A DLMS/COSEM APDU follows, carrying the code (0x60, 0x61, 0x64 or 0x65).
+    Dlms(u8),
+    /// An SML file follows, carrying the code (0x7E or 0x7F).
+    Sml(u8),
+    Unknown(u8),
+}
+
+impl ControlInformation {
+    /// The baud rate a [`ControlInformation::SetBaudRate`] switches to.
+    pub fn baud_rate(self) -> Option<BaudRate> {
+        match self {
+            ControlInformation::SetBaudRate(code) => BaudRate::from_code(code),
+            _ => None,
+        }
+    }
+
+    /// Whether the CI is sent by a slave in response to a request.
+    pub fn is_response(self) -> bool {
+        matches!(
+            self,
+            ControlInformation::ApplicationError
+                | ControlInformation::AlarmStatus
+                | ControlInformation::ResponseVariable
+                | ControlInformation::ResponseFixed
+                | ControlInformation::ResponseVariableMsbFirst
+                | ControlInformation::ResponseFixedMsbFirst
+                | ControlInformation::ResponseNoHeader
+                | ControlInformation::ResponseCompact
+                | ControlInformation::Sml(_)
+        )
+    }
+}
+
+impl From<u8> for ControlInformation {
+    fn from(code: u8) -> Self {
+        match code {
+            CI_APPLICATION_RESET => ControlInformation::ApplicationReset,
+            DATA_SEND => ControlInformation::DataSend,
+            CI_SLAVE_SELECT => ControlInformation::SlaveSelect,
+            DATA_SEND_MSB_FIRST => ControlInformation::DataSendMsbFirst,
+            SLAVE_SELECT_MSB_FIRST => ControlInformation::SlaveSelectMsbFirst,
+            SYNCHRONIZE_ACTION => ControlInformation::SynchronizeAction,
+            CI_APPLICATION_ERROR => ControlInformation::ApplicationError,
+            CI_ALARM_STATUS => ControlInformation::AlarmStatus,
+            CI_RESPONSE_VARIABLE => ControlInformation::ResponseVariable,
+            CI_RESPONSE_FIXED => ControlInformation::ResponseFixed,
+            CI_RESPONSE_VARIABLE_MSB_FIRST => ControlInformation::ResponseVariableMsbFirst,
+            CI_RESPONSE_FIXED_MSB_FIRST => ControlInformation::ResponseFixedMsbFirst,
+            CI_RESPONSE_NO_HEADER => ControlInformation::ResponseNoHeader,
+            CI_RESPONSE_COMPACT => ControlInformation::ResponseCompact,
+            SET_BAUD_RATE_FIRST..=SET_BAUD_RATE_LAST => ControlInformation::SetBaudRate(code),
+            DLMS_LONG_HEADER
+            | DLMS_SHORT_HEADER
+            | DLMS_OBIS_LONG_HEADER
+            | DLMS_OBIS_SHORT_HEADER => ControlInformation::Dlms(code),
+            SML_LONG_HEADER | SML_SHORT_HEADER => ControlInformation::Sml(code),
+            code => ControlInformation::Unknown(code),
+        }
+    }
+}
+
+impl From<ControlInformation> for u8 {
+    fn from(control_information: ControlInformation) -> Self {
+        match control_information {
+            ControlInformation::ApplicationReset => CI_APPLICATION_RESET,
+            ControlInformation::DataSend => DATA_SEND,
+            ControlInformation::SlaveSelect => CI_SLAVE_SELECT,
+            ControlInformation::DataSendMsbFirst => DATA_SEND_MSB_FIRST,
+            ControlInformation::SlaveSelectMsbFirst => SLAVE_SELECT_MSB_FIRST,
+            ControlInformation::SynchronizeAction => SYNCHRONIZE_ACTION,
+            ControlInformation::ApplicationError => CI_APPLICATION_ERROR,
+            ControlInformation::AlarmStatus => CI_ALARM_STATUS,
+            ControlInformation::ResponseVariable => CI_RESPONSE_VARIABLE,
+            ControlInformation::ResponseFixed => CI_RESPONSE_FIXED,
+            ControlInformation::ResponseVariableMsbFirst => CI_RESPONSE_VARIABLE_MSB_FIRST,
+            ControlInformation::ResponseFixedMsbFirst => CI_RESPONSE_FIXED_MSB_FIRST,
+            ControlInformation::ResponseNoHeader => CI_RESPONSE_NO_HEADER,
+            ControlInformation::ResponseCompact => CI_RESPONSE_COMPACT,
+            ControlInformation::SetBaudRate(code)
+            | ControlInformation::Dlms(code)
+            | ControlInformation::Sml(code)
+            | ControlInformation::Unknown(code) => code,
+        }
+    }
+}
+
+/// Baud rates a slave can be switched to with CI 0xB8 to 0xBF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BaudRate {
+    Baud300,
+    Baud600,
+    Baud1200,
+    Baud2400,
+    Baud4800,
+    Baud9600,
+    Baud19200,
+    Baud38400,
+}
+
+impl BaudRate {
+    const ALL: [BaudRate; 8] = [
+        BaudRate::Baud300,
+        BaudRate::Baud600,
+        BaudRate::Baud1200,
+        BaudRate::Baud2400,
+        BaudRate::Baud4800,
+        BaudRate::Baud9600,
+        BaudRate::Baud19200,
+        BaudRate::Baud38400,
+    ];
+
+    /// The baud rate for a CI field from 0xB8 to 0xBF.
+    pub fn from_code(code: u8) -> Option<Self> {
+        let index = code.checked_sub(SET_BAUD_RATE_FIRST)?;
+        Self::ALL.get(usize::from(index)).copied()
+    }
+
+    pub fn from_baud(baud: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|rate| rate.baud() == baud)
+    }
+
+    /// The CI field switching to this baud rate.
+    pub const fn code(self) -> u8 {
+        SET_BAUD_RATE_FIRST + self as u8
+    }
+
+    pub const fn baud(self) -> u32 {
+        300 << self as u32
+    }
+}
+
+impl From<BaudRate> for ControlInformation {
+    fn from(rate: BaudRate) -> Self {
+        ControlInformation::SetBaudRate(rate.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for code in 0..=u8::MAX {
+            assert_eq!(u8::from(ControlInformation::from(code)), code);
+        }
+        assert_eq!(
+            ControlInformation::from(0x72),
+            ControlInformation::ResponseVariable
+        );
+        assert_eq!(
+            ControlInformation::from(0xBB),
+            ControlInformation::SetBaudRate(0xBB)
+        );
+        assert_eq!(
+            ControlInformation::from(0x00),
+            ControlInformation::Unknown(0x00)
+        );
+        assert_eq!(
+            ControlInformation::from(0xBD).baud_rate(),
+            Some(BaudRate::Baud9600)
+        );
+        assert_eq!(ControlInformation::from(0x72).baud_rate(), None);
+        assert!(ControlInformation::from(0x78).is_response());
+        assert!(!ControlInformation::from(0x51).is_response());
+    }
+}
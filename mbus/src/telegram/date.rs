@@ -0,0 +1,194 @@
+//! Date and time encodings of EN 13757-3 annex A.
+
+const INVALID: u8 = 0x80;
+const SUMMER_TIME: u8 = 0x80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DateTime {
+    pub date: Date,
+    pub time: Time,
+    /// The meter flagged the date and time as invalid (IV bit).
+    pub invalid: bool,
+    /// The time is in summer time (SU bit).
+    pub summer_time: bool,
+}
+
+fn year(low: u8, high: u8, hundred_year: u8) -> u16 {
+    let year = u16::from(high << 3 | low);
+    if hundred_year == 0 && year <= 80 {
+        // without century information, assume 2000-2080
+        2000 + year
+    } else {
+        1900 + 100 * u16::from(hundred_year) + year
+    }
+}
+
+/// Type G: date in 16 bits.
+pub(super) fn date_g(data: &[u8; 2]) -> Date {
+    Date {
+        year: year(data[0] >> 5, data[1] >> 4, 0),
+        month: data[1] & 0x0F,
+        day: data[0] & 0x1F,
+    }
+}
+
+/// Type F: date and time to the minute in 32 bits.
+pub(super) fn date_time_f(data: &[u8; 4]) -> DateTime {
+    DateTime {
+        date: Date {
+            year: year(data[2] >> 5, data[3] >> 4, (data[1] >> 5) & 0x03),
+            month: data[3] & 0x0F,
+            day: data[2] & 0x1F,
+        },
+        time: Time {
+            hour: data[1] & 0x1F,
+            minute: data[0] & 0x3F,
+            second: 0,
+        },
+        invalid: data[0] & INVALID != 0,
+        summer_time: data[1] & SUMMER_TIME != 0,
+    }
+}
+
+/// Type I: date and time to the second in 48 bits.
+pub(super) fn date_time_i(data: &[u8; 6]) -> DateTime {
+    DateTime {
+        date: Date {
+            year: year(data[3] >> 5, data[4] >> 4, 0),
+            month: data[4] & 0x0F,
+            day: data[3] & 0x1F,
+        },
+        time: Time {
+            hour: data[2] & 0x1F,
+            minute: data[1] & 0x3F,
+            second: data[0] & 0x3F,
+        },
+        invalid: data[1] & INVALID != 0,
+        summer_time: data[5] & 0x40 != 0,
+    }
+}
+
+/// Type J: time of day in 24 bits.
+pub(super) fn time_j(data: &[u8; 3]) -> Time {
+    Time {
+        hour: data[2] & 0x1F,
+        minute: data[1] & 0x3F,
+        second: data[0] & 0x3F,
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Date {
+    pub fn to_naive_date(&self) -> Option<chrono::NaiveDate> {
+        chrono::NaiveDate::from_ymd_opt(self.year.into(), self.month.into(), self.day.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Time {
+    pub fn to_naive_time(&self) -> Option<chrono::NaiveTime> {
+        chrono::NaiveTime::from_hms_opt(self.hour.into(), self.minute.into(), self.second.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl DateTime {
+    /// Returns `None` for dates flagged as invalid or that do not exist.
+    pub fn to_naive_date_time(&self) -> Option<chrono::NaiveDateTime> {
+        if self.invalid {
+            return None;
+        }
+        Some(
+            self.date
+                .to_naive_date()?
+                .and_time(self.time.to_naive_time()?),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        // 2008-11-19
+        assert_eq!(
+            date_g(&[0x13, 0x1B]),
+            Date {
+                year: 2008,
+                month: 11,
+                day: 19
+            }
+        );
+
+        // 2021-03-27 13:45 summer time
+        let date_time = date_time_f(&[0x2D, 0x8D, 0xBB, 0x23]);
+        assert_eq!(
+            date_time,
+            DateTime {
+                date: Date {
+                    year: 2021,
+                    month: 3,
+                    day: 27
+                },
+                time: Time {
+                    hour: 13,
+                    minute: 45,
+                    second: 0
+                },
+                invalid: false,
+                summer_time: true,
+            }
+        );
+        assert!(date_time_f(&[0xAD, 0x0D, 0xBB, 0x23]).invalid);
+
+        assert_eq!(
+            date_time_i(&[0x1E, 0x2D, 0x0D, 0xBB, 0x23, 0x0C]).time,
+            Time {
+                hour: 13,
+                minute: 45,
+                second: 30
+            }
+        );
+        assert_eq!(
+            time_j(&[0x1E, 0x2D, 0x0D]),
+            Time {
+                hour: 13,
+                minute: 45,
+                second: 30
+            }
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono() {
+        let date_time = date_time_f(&[0x2D, 0x8D, 0xBB, 0x23]);
+        assert_eq!(
+            date_time.to_naive_date_time(),
+            chrono::NaiveDate::from_ymd_opt(2021, 3, 27).and_then(|d| d.and_hms_opt(13, 45, 0))
+        );
+        assert_eq!(
+            date_time_f(&[0xAD, 0x0D, 0xBB, 0x23]).to_naive_date_time(),
+            None
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Multi-line description of a telegram for logs and command line tools.
+
+use super::{DataRecord, FunctionField, Telegram, Value};
+use crate::HexBytes;
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The header on the first line, followed by a line per record with its DIF/VIF bytes,
+/// quantity, value and unit.
+impl fmt::Display for Telegram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = &self.header;
+        write!(
+            f,
+            "{:08X} {} v{} {:?}, access number {}, status {}",
+            header.identification,
+            header.manufacturer,
+            header.version,
+            header.medium,
+            header.access_number,
+            header.status
+        )?;
+        for record in &self.records {
+            writeln!(f)?;
+            write_record(f, record)?;
+        }
+        if self.more_records_follow {
+            write!(f, "\n  more records follow")?;
+        }
+        if !self.manufacturer_data.is_empty() {
+            write!(
+                f,
+                "\n  manufacturer data {:X}",
+                HexBytes(&self.manufacturer_data)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn write_record(f: &mut fmt::Formatter<'_>, record: &DataRecord) -> fmt::Result {
+    // everything but the data, including a plain-text unit
+    let mut bytes = Vec::new();
+    record.encode(&mut bytes);
+    bytes.truncate(bytes.len() - record.data.len());
+
+    let header = &record.header;
+    let value_information = record.value_information();
+    let mut quantity = format!("{:?}", value_information.quantity);
+    if let Some(direction) = value_information.direction {
+        quantity += &format!(" {:?}", direction);
+    }
+    if header.function != FunctionField::Instantaneous {
+        quantity += &format!(" {:?}", header.function);
+    }
+    for (name, n) in [
+        ("storage", header.storage_number),
+        ("tariff", header.tariff.into()),
+        ("subunit", header.subunit.into()),
+    ] {
+        if n != 0 {
+            quantity += &format!(" {} {}", name, n);
+        }
+    }
+    let bytes = format!("{:X}", HexBytes(&bytes));
+    write!(f, "  {:<17} {:<32} ", bytes, quantity)?;
+
+    let plaintext_unit = record.plaintext_unit.as_deref();
+    match record.normalized() {
+        Ok(Some((value, unit))) => {
+            let unit = plaintext_unit.unwrap_or(unit.symbol());
+            write!(f, "{} {}", value, unit)?;
+            if let Some(per) = value_information.per {
+                write!(f, "/{}", per.symbol())?;
+            }
+        }
+        Ok(None) => write_value(f, &record.value().unwrap_or(Value::None))?,
+        Err(err) => write!(f, "invalid: {}", err)?,
+    }
+    Ok(())
+}
+
+fn write_value(f: &mut fmt::Formatter<'_>, value: &Value) -> fmt::Result {
+    match value {
+        Value::None => write!(f, "-"),
+        Value::I64(value) | Value::Bcd(value) => write!(f, "{}", value),
+        Value::F64(value) => write!(f, "{}", value),
+        Value::Date(date) => write!(f, "{:04}-{:02}-{:02}", date.year, date.month, date.day),
+        Value::Time(time) => write!(f, "{:02}:{:02}:{:02}", time.hour, time.minute, time.second),
+        Value::DateTime(date_time) => {
+            let (date, time) = (date_time.date, date_time.time);
+            write!(
+                f,
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                date.year, date.month, date.day, time.hour, time.minute, time.second
+            )?;
+            if date_time.summer_time {
+                write!(f, " (summer time)")?;
+            }
+            if date_time.invalid {
+                write!(f, " (invalid)")?;
+            }
+            Ok(())
+        }
+        Value::Text(text) => write!(f, "{:?}", text),
+        Value::Binary(data) => write!(f, "{:X}", HexBytes(data)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_display() {
+        // example from EN 13757-3 annex, followed by manufacturer data
+        let telegram = Telegram::parse(&[
+            0x78, 0x56, 0x34, 0x12, 0x24, 0x40, 0x01, 0x07, 0x55, 0x00, 0x00, 0x00, 0x03, 0x13,
+            0x15, 0x31, 0x00, 0xDA, 0x02, 0x3B, 0x13, 0x01, 0x8B, 0x60, 0x04, 0x37, 0x18, 0x02,
+            0x0F, 0x01, 0x02,
+        ])
+        .unwrap();
+        let expected = [
+            "12345678 PAD v1 Water, access number 85, status ok",
+            "  03 13             Volume                           12.565 m³",
+            "  DA 02 3B          VolumeFlow Maximum storage 5     0.113 m³/h",
+            "  8B 60 04          Energy tariff 2 subunit 1        218370 Wh",
+            "  manufacturer data 01 02",
+        ];
+        assert_eq!(telegram.to_string(), expected.join("\n"));
+    }
+}
@@ -0,0 +1,94 @@
+use super::{TelegramError, CI_RESPONSE_SHORT_HEADER, CI_RESPONSE_VARIABLE};
+use crate::oms::TransportHeader;
+use crate::Frame;
+use alloc::vec::Vec;
+
+pub(super) const DLMS_LONG_HEADER: u8 = 0x60;
+pub(super) const DLMS_SHORT_HEADER: u8 = 0x61;
+pub(super) const DLMS_OBIS_LONG_HEADER: u8 = 0x64;
+pub(super) const DLMS_OBIS_SHORT_HEADER: u8 = 0x65;
+
+/// A DLMS/COSEM APDU tunneled over M-Bus, following CI 0x60, 0x61, 0x64 or 0x65 and a transport
+/// header. The APDU is left as is, to be handed to a DLMS stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DlmsData {
+    pub control_information: u8,
+    pub header: TransportHeader,
+    pub apdu: Vec<u8>,
+}
+
+impl DlmsData {
+    /// Whether `control_information` introduces a DLMS APDU.
+    pub fn is_dlms(control_information: u8) -> bool {
+        matches!(
+            control_information,
+            DLMS_LONG_HEADER | DLMS_SHORT_HEADER | DLMS_OBIS_LONG_HEADER | DLMS_OBIS_SHORT_HEADER
+        )
+    }
+
+    pub fn from_frame(frame: &Frame) -> Result<Self, TelegramError> {
+        match frame {
+            Frame::Control {
+                control_information,
+                ..
+            } => Err(TelegramError::UnsupportedControlInformation(
+                *control_information,
+            )),
+            Frame::Long {
+                control_information,
+                data,
+                ..
+            } => Self::parse(*control_information, data),
+            Frame::Single | Frame::Short { .. } => Err(TelegramError::NoUserData),
+        }
+    }
+
+    /// Splits the data following `control_information` into the transport header and the APDU.
+    pub fn parse(control_information: u8, data: &[u8]) -> Result<Self, TelegramError> {
+        // the transport headers are those of the M-Bus responses
+        let header_ci = match control_information {
+            DLMS_LONG_HEADER | DLMS_OBIS_LONG_HEADER => CI_RESPONSE_VARIABLE,
+            DLMS_SHORT_HEADER | DLMS_OBIS_SHORT_HEADER => CI_RESPONSE_SHORT_HEADER,
+            ci => return Err(TelegramError::UnsupportedControlInformation(ci)),
+        };
+        let (header, apdu) = TransportHeader::parse(header_ci, data)?;
+        Ok(Self {
+            control_information,
+            header,
+            apdu: apdu.to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::ControlInformation;
+
+    #[test]
+    fn test_parse() {
+        // short header followed by a GET request APDU
+        let frame = Frame::Long {
+            control: 0x53,
+            address: 0x01,
+            control_information: DLMS_SHORT_HEADER,
+            data: [0x2A, 0x00, 0x00, 0x00, 0xC0, 0x01, 0xC1].to_vec(),
+        };
+        let dlms = DlmsData::from_frame(&frame).unwrap();
+        assert!(matches!(dlms.header, TransportHeader::Short(_)));
+        assert_eq!(dlms.apdu, [0xC0, 0x01, 0xC1]);
+        assert_eq!(
+            ControlInformation::from(DLMS_SHORT_HEADER),
+            ControlInformation::Dlms(DLMS_SHORT_HEADER)
+        );
+
+        assert_eq!(
+            DlmsData::parse(DLMS_LONG_HEADER, &[0x00; 4]),
+            Err(TelegramError::UnexpectedEnd)
+        );
+        assert_eq!(
+            DlmsData::parse(CI_RESPONSE_VARIABLE, &[]),
+            Err(TelegramError::UnsupportedControlInformation(0x72))
+        );
+    }
+}
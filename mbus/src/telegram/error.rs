@@ -0,0 +1,119 @@
+use super::{TelegramError, CI_APPLICATION_ERROR};
+use crate::Frame;
+use alloc::vec::Vec;
+
+/// Error reported by a slave in a response with CI 0x70.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplicationError {
+    Unspecified,
+    UnimplementedControlInformation,
+    BufferTooLong,
+    TooManyRecords,
+    PrematureEndOfRecord,
+    MoreThan10Difes,
+    MoreThan10Vifes,
+    Reserved,
+    ApplicationBusy,
+    TooManyReadouts,
+    Other(u8),
+}
+
+impl From<u8> for ApplicationError {
+    fn from(code: u8) -> Self {
+        match code {
+            0x00 => ApplicationError::Unspecified,
+            0x01 => ApplicationError::UnimplementedControlInformation,
+            0x02 => ApplicationError::BufferTooLong,
+            0x03 => ApplicationError::TooManyRecords,
+            0x04 => ApplicationError::PrematureEndOfRecord,
+            0x05 => ApplicationError::MoreThan10Difes,
+            0x06 => ApplicationError::MoreThan10Vifes,
+            0x07 => ApplicationError::Reserved,
+            0x08 => ApplicationError::ApplicationBusy,
+            0x09 => ApplicationError::TooManyReadouts,
+            code => ApplicationError::Other(code),
+        }
+    }
+}
+
+impl From<ApplicationError> for u8 {
+    fn from(error: ApplicationError) -> Self {
+        match error {
+            ApplicationError::Unspecified => 0x00,
+            ApplicationError::UnimplementedControlInformation => 0x01,
+            ApplicationError::BufferTooLong => 0x02,
+            ApplicationError::TooManyRecords => 0x03,
+            ApplicationError::PrematureEndOfRecord => 0x04,
+            ApplicationError::MoreThan10Difes => 0x05,
+            ApplicationError::MoreThan10Vifes => 0x06,
+            ApplicationError::Reserved => 0x07,
+            ApplicationError::ApplicationBusy => 0x08,
+            ApplicationError::TooManyReadouts => 0x09,
+            ApplicationError::Other(code) => code,
+        }
+    }
+}
+
+impl ApplicationError {
+    pub fn from_frame(frame: &Frame) -> Result<Self, TelegramError> {
+        match frame {
+            // the error code is optional
+            Frame::Control {
+                control_information: CI_APPLICATION_ERROR,
+                ..
+            } => Ok(ApplicationError::Unspecified),
+            Frame::Long {
+                control_information: CI_APPLICATION_ERROR,
+                data,
+                ..
+            } => Ok(data
+                .first()
+                .map_or(ApplicationError::Unspecified, |code| (*code).into())),
+            Frame::Control {
+                control_information,
+                ..
+            }
+            | Frame::Long {
+                control_information,
+                ..
+            } => Err(TelegramError::UnsupportedControlInformation(
+                *control_information,
+            )),
+            Frame::Single | Frame::Short { .. } => Err(TelegramError::NoUserData),
+        }
+    }
+
+    /// Builds the RSP_UD a slave sends to report this error.
+    pub fn to_frame(self, address: u8) -> Frame {
+        Frame::Long {
+            control: crate::control::RSP_UD,
+            address,
+            control_information: CI_APPLICATION_ERROR,
+            data: Vec::from([self.into()]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let frame = ApplicationError::TooManyRecords.to_frame(0x01);
+        assert_eq!(frame.to_hex_string(), "68 04 04 68 08 01 70 03 7C 16");
+        assert_eq!(
+            ApplicationError::from_frame(&frame),
+            Ok(ApplicationError::TooManyRecords)
+        );
+        assert_eq!(ApplicationError::from(0x42), ApplicationError::Other(0x42));
+        assert_eq!(
+            ApplicationError::from_frame(&Frame::Control {
+                control: 0x08,
+                address: 0x01,
+                control_information: CI_APPLICATION_ERROR,
+            }),
+            Ok(ApplicationError::Unspecified)
+        );
+    }
+}
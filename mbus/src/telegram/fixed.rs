@@ -0,0 +1,187 @@
+use super::{TelegramError, CI_RESPONSE_FIXED, CI_RESPONSE_FIXED_MSB_FIRST};
+use crate::Frame;
+use nom::number::complete::{u32, u8};
+use nom::number::Endianness;
+use nom::sequence::tuple;
+
+const STATUS_BINARY_COUNTERS: u8 = 0x80;
+const STATUS_STORED_COUNTERS: u8 = 0x40;
+const MEDIUM_MASK: u8 = 0xC0;
+const UNIT_MASK: u8 = 0x3F;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedCounter {
+    /// Unit code from the fixed data structure unit table.
+    pub unit: u8,
+    pub value: u32,
+}
+
+/// Decoded fixed data structure, sent by older meters following CI 0x73.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedDataStructure {
+    /// Identification number as 8 BCD digits, print it with `{:08X}`.
+    pub identification: u32,
+    pub access_number: u8,
+    pub status: u8,
+    /// Medium code, assembled from the upper two bits of both unit bytes.
+    pub medium: u8,
+    pub counters: [FixedCounter; 2],
+}
+
+impl FixedDataStructure {
+    pub fn from_frame(frame: &Frame) -> Result<Self, TelegramError> {
+        match frame {
+            Frame::Long {
+                control_information: CI_RESPONSE_FIXED,
+                data,
+                ..
+            } => Self::parse(data),
+            Frame::Long {
+                control_information: CI_RESPONSE_FIXED_MSB_FIRST,
+                data,
+                ..
+            } => Self::parse_msb_first(data),
+            Frame::Control {
+                control_information,
+                ..
+            }
+            | Frame::Long {
+                control_information,
+                ..
+            } => Err(TelegramError::UnsupportedControlInformation(
+                *control_information,
+            )),
+            Frame::Single | Frame::Short { .. } => Err(TelegramError::NoUserData),
+        }
+    }
+
+    /// Parses the user data of a frame with CI 0x73.
+    pub fn parse(data: &[u8]) -> Result<Self, TelegramError> {
+        Self::parse_with(data, Endianness::Little)
+    }
+
+    /// Parses the user data of a frame with CI 0x77, in which the identification number and
+    /// counters are sent most significant byte first.
+    pub fn parse_msb_first(data: &[u8]) -> Result<Self, TelegramError> {
+        Self::parse_with(data, Endianness::Big)
+    }
+
+    fn parse_with(data: &[u8], endianness: Endianness) -> Result<Self, TelegramError> {
+        let (_, (identification, access_number, status, unit1, unit2, value1, value2)) =
+            tuple((
+                u32(endianness),
+                u8,
+                u8,
+                u8,
+                u8,
+                u32(endianness),
+                u32(endianness),
+            ))(data)?;
+
+        let value = |raw: u32| {
+            if status & STATUS_BINARY_COUNTERS != 0 {
+                Ok(raw)
+            } else {
+                bcd_u32(raw)
+            }
+        };
+
+        Ok(Self {
+            identification,
+            access_number,
+            status,
+            medium: (unit1 & MEDIUM_MASK) >> 6 | (unit2 & MEDIUM_MASK) >> 4,
+            counters: [
+                FixedCounter {
+                    unit: unit1 & UNIT_MASK,
+                    value: value(value1)?,
+                },
+                FixedCounter {
+                    unit: unit2 & UNIT_MASK,
+                    value: value(value2)?,
+                },
+            ],
+        })
+    }
+
+    /// Whether the counters hold values stored at a fixed date rather than actual values.
+    pub fn is_stored(&self) -> bool {
+        self.status & STATUS_STORED_COUNTERS != 0
+    }
+}
+
+fn bcd_u32(raw: u32) -> Result<u32, TelegramError> {
+    let mut value = 0;
+    for shift in (0..32).step_by(4).rev() {
+        let digit = (raw >> shift) & 0xF;
+        if digit > 9 {
+            return Err(TelegramError::InvalidBcd);
+        }
+        value = value * 10 + digit;
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_parse() {
+        let frame = Frame::Long {
+            control: 0x08,
+            address: 0x01,
+            control_information: CI_RESPONSE_FIXED,
+            data: vec![
+                0x78, 0x56, 0x34, 0x12, 0x0A, 0x00, 0x4E, 0x86, 0x45, 0x23, 0x01, 0x00, 0x09, 0x00,
+                0x00, 0x00,
+            ],
+        };
+        assert_eq!(
+            FixedDataStructure::from_frame(&frame),
+            Ok(FixedDataStructure {
+                identification: 0x12345678,
+                access_number: 0x0A,
+                status: 0x00,
+                medium: 0x09,
+                counters: [
+                    FixedCounter {
+                        unit: 0x0E,
+                        value: 12345,
+                    },
+                    FixedCounter {
+                        unit: 0x06,
+                        value: 9,
+                    },
+                ],
+            })
+        );
+
+        let mut binary = vec![
+            0x78, 0x56, 0x34, 0x12, 0x0A, 0xC0, 0x0E, 0x06, 0x39, 0x30, 0x00, 0x00, 0xFF, 0xFF,
+            0xFF, 0xFF,
+        ];
+        let fixed = FixedDataStructure::parse(&binary).unwrap();
+        assert!(fixed.is_stored());
+        assert_eq!(fixed.counters[0].value, 12345);
+        assert_eq!(fixed.counters[1].value, u32::MAX);
+
+        binary[5] = 0x00;
+        assert_eq!(
+            FixedDataStructure::parse(&binary),
+            Err(TelegramError::InvalidBcd)
+        );
+        assert_eq!(
+            FixedDataStructure::parse_msb_first(&[
+                0x12, 0x34, 0x56, 0x78, 0x0A, 0x00, 0x4E, 0x86, 0x00, 0x01, 0x23, 0x45, 0x00, 0x00,
+                0x00, 0x09,
+            ]),
+            FixedDataStructure::from_frame(&frame)
+        );
+        assert_eq!(
+            FixedDataStructure::parse(&binary[..15]),
+            Err(TelegramError::UnexpectedEnd)
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use super::{IResult, ManufacturerId, Medium, Status};
+use alloc::vec::Vec;
+use nom::number::complete::{u16, u32, u8};
+use nom::number::Endianness;
+use nom::sequence::tuple;
+use nom::Parser;
+
+/// Fixed part of the variable data structure, following CI 0x72.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Header {
+    /// Identification number as 8 BCD digits, print it with `{:08X}`.
+    pub identification: u32,
+    pub manufacturer: ManufacturerId,
+    pub version: u8,
+    pub medium: Medium,
+    pub access_number: u8,
+    pub status: Status,
+    pub signature: u16,
+}
+
+impl Header {
+    /// Appends the header as it follows CI 0x72.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.identification.to_le_bytes());
+        out.extend_from_slice(&u16::from(self.manufacturer).to_le_bytes());
+        out.push(self.version);
+        out.push(self.medium.into());
+        out.push(self.access_number);
+        out.push(self.status.bits());
+        out.extend_from_slice(&self.signature.to_le_bytes());
+    }
+}
+
+pub(super) fn header(i: &[u8], endianness: Endianness) -> IResult<'_, Header> {
+    tuple((
+        u32(endianness),
+        u16(endianness),
+        u8,
+        u8,
+        u8,
+        u8,
+        u16(endianness),
+    ))
+    .map(
+        |(identification, manufacturer, version, medium, access_number, status, signature)| {
+            Header {
+                identification,
+                manufacturer: manufacturer.into(),
+                version,
+                medium: medium.into(),
+                access_number,
+                status: status.into(),
+                signature,
+            }
+        },
+    )
+    .parse(i)
+}
@@ -0,0 +1,73 @@
+//! Pairing of historical values with the time point of their storage slot.
+
+use super::{DataRecord, DateTime, Quantity, Telegram, Time, Value};
+
+impl Telegram {
+    /// The time point recorded for the storage slot of `record`: the value of the date or
+    /// date/time record with the same storage number and subunit. A date gives midnight.
+    pub fn time_point(&self, record: &DataRecord) -> Option<DateTime> {
+        self.records
+            .iter()
+            .filter(|other| {
+                other.header.storage_number == record.header.storage_number
+                    && other.header.subunit == record.header.subunit
+            })
+            .find_map(time_point)
+    }
+
+    /// The records of every storage slot with a time point, each paired with that time point,
+    /// in order. The date and time records themselves are left out.
+    pub fn historical_values(&self) -> impl Iterator<Item = (DateTime, &DataRecord)> {
+        self.records
+            .iter()
+            .filter(|record| time_point(record).is_none())
+            .filter_map(|record| self.time_point(record).map(|time| (time, record)))
+    }
+}
+
+fn time_point(record: &DataRecord) -> Option<DateTime> {
+    if !matches!(
+        record.value_information().quantity,
+        Quantity::Date | Quantity::DateTime
+    ) {
+        return None;
+    }
+    match record.value().ok()? {
+        Value::DateTime(date_time) => Some(date_time),
+        Value::Date(date) => Some(DateTime {
+            date,
+            time: Time {
+                hour: 0,
+                minute: 0,
+                second: 0,
+            },
+            invalid: false,
+            summer_time: false,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_historical_values() {
+        // current volume and date, the volume and energy of storage 1 with its date, and the
+        // volume of storage 2 without one
+        let telegram = Telegram::parse(&[
+            0x78, 0x56, 0x34, 0x12, 0x24, 0x40, 0x01, 0x07, 0x55, 0x00, 0x00, 0x00, 0x01, 0x13,
+            0x05, 0x02, 0x6C, 0x3F, 0x2C, 0x41, 0x13, 0x04, 0x41, 0x03, 0x07, 0x42, 0x6C, 0x3F,
+            0x2B, 0x81, 0x01, 0x13, 0x03,
+        ])
+        .unwrap();
+        let values = telegram
+            .historical_values()
+            .map(|(time, record)| (time.date.month, record.data[0]))
+            .collect::<Vec<_>>();
+        assert_eq!(values, [(12, 0x05), (11, 0x04), (11, 0x07)]);
+        assert_eq!(telegram.time_point(&telegram.records[5]), None);
+    }
+}
@@ -0,0 +1,56 @@
+use super::{DataRecord, Quantity, Value, ValueError};
+
+/// Record identifying the meter rather than measuring something. The numbers are given as
+/// transmitted, so BCD coded numbers read like their digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Identification {
+    /// VIF 0x78.
+    FabricationNumber(u64),
+    /// VIF 0x79, usually the identification number of the header.
+    EnhancedIdentification(u64),
+    /// VIF 0x7A.
+    BusAddress(u64),
+    /// VIF 0xFD with VIFE 0x08.
+    AccessNumber(u64),
+}
+
+impl DataRecord {
+    /// Decodes records describing the identity of the meter, `Ok(None)` for other records.
+    pub fn identification(&self) -> Result<Option<Identification>, ValueError> {
+        let kind = match self.value_information().quantity {
+            Quantity::FabricationNumber => Identification::FabricationNumber,
+            Quantity::EnhancedIdentification => Identification::EnhancedIdentification,
+            Quantity::BusAddress => Identification::BusAddress,
+            Quantity::AccessNumber => Identification::AccessNumber,
+            _ => return Ok(None),
+        };
+        match self.value()? {
+            Value::I64(value) | Value::Bcd(value) if value >= 0 => Ok(Some(kind(value as u64))),
+            _ => Err(ValueError::UnsupportedDataField(self.header.data_field)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::record::data_record;
+
+    #[test]
+    fn test_identification() {
+        let (_, record) = data_record(&[0x0C, 0x78, 0x78, 0x56, 0x34, 0x12]).unwrap();
+        assert_eq!(
+            record.identification(),
+            Ok(Some(Identification::FabricationNumber(12345678)))
+        );
+
+        let (_, record) = data_record(&[0x01, 0xFD, 0x08, 0x55]).unwrap();
+        assert_eq!(
+            record.identification(),
+            Ok(Some(Identification::AccessNumber(0x55)))
+        );
+
+        let (_, record) = data_record(&[0x0C, 0x13, 0x45, 0x23, 0x01, 0x00]).unwrap();
+        assert_eq!(record.identification(), Ok(None));
+    }
+}
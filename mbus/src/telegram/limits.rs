@@ -0,0 +1,53 @@
+use core::fmt;
+
+/// Bounds on what the record parser accepts, so that a hostile or corrupt device cannot make
+/// it allocate or loop without bound. The defaults accept everything EN 13757-3 allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Records per telegram.
+    pub max_records: usize,
+    /// Data bytes of a variable length record, following the LVAR byte.
+    pub max_lvar_length: usize,
+    /// VIFEs following the VIF of a record.
+    pub max_vifes: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_records: 1024,
+            max_lvar_length: 192,
+            max_vifes: 10,
+        }
+    }
+}
+
+/// How records are parsed, see [`Telegram::parse_with_options`] and [`Records::with_options`].
+///
+/// [`Telegram::parse_with_options`]: super::Telegram::parse_with_options
+/// [`Records::with_options`]: super::Records::with_options
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Keep the bytes each record was parsed from, up to its data, in
+    /// [`DataRecord::raw_header`](super::DataRecord::raw_header).
+    pub keep_raw: bool,
+    pub limits: ParseLimits,
+}
+
+/// The limit of [`ParseLimits`] a telegram exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Records,
+    LvarLength,
+    Vifes,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::Records => write!(f, "records"),
+            Limit::LvarLength => write!(f, "variable length data"),
+            Limit::Vifes => write!(f, "VIFEs"),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use core::fmt;
+use core::str::FromStr;
+
+/// Manufacturer code as assigned by the FLAG association, packed into 15 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct ManufacturerId(u16);
+
+impl ManufacturerId {
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// The three letters of the code, e.g. `*b"KAM"`.
+    pub const fn letters(self) -> [u8; 3] {
+        [
+            b'@' + ((self.0 >> 10) & 0x1F) as u8,
+            b'@' + ((self.0 >> 5) & 0x1F) as u8,
+            b'@' + (self.0 & 0x1F) as u8,
+        ]
+    }
+}
+
+impl From<u16> for ManufacturerId {
+    fn from(bits: u16) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<ManufacturerId> for u16 {
+    fn from(id: ManufacturerId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for ManufacturerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for letter in self.letters() {
+            write!(f, "{}", char::from(letter))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidManufacturerId;
+
+impl fmt::Display for InvalidManufacturerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "manufacturer ID must consist of three letters A-Z")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidManufacturerId {}
+
+impl FromStr for ManufacturerId {
+    type Err = InvalidManufacturerId;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            letters @ [_, _, _] if letters.iter().all(u8::is_ascii_uppercase) => {
+                Ok(Self(letters.iter().fold(0, |bits, letter| {
+                    bits << 5 | u16::from(letter - b'@')
+                })))
+            }
+            _ => Err(InvalidManufacturerId),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(ManufacturerId::from(0x2C2D).to_string(), "KAM");
+        assert_eq!(ManufacturerId::from(0x4024).to_string(), "PAD");
+        assert_eq!("ISK".parse(), Ok(ManufacturerId::from(0x266B)));
+        assert_eq!("KAM".parse::<ManufacturerId>().map(u16::from), Ok(0x2C2D));
+        assert_eq!(ManufacturerId::from(0x266B).letters(), *b"ISK");
+
+        assert_eq!("KA".parse::<ManufacturerId>(), Err(InvalidManufacturerId));
+        assert_eq!("kam".parse::<ManufacturerId>(), Err(InvalidManufacturerId));
+    }
+}
@@ -0,0 +1,84 @@
+macro_rules! media {
+    ($($name:ident = $code:literal,)*) => {
+        /// Device type from the medium byte of the variable data header.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum Medium {
+            $($name,)*
+            /// Codes that are reserved or not covered by the variants above, including 0x00
+            /// ("other").
+            Other(u8),
+        }
+
+        impl From<u8> for Medium {
+            fn from(code: u8) -> Self {
+                match code {
+                    $($code => Medium::$name,)*
+                    code => Medium::Other(code),
+                }
+            }
+        }
+
+        impl From<Medium> for u8 {
+            fn from(medium: Medium) -> Self {
+                match medium {
+                    $(Medium::$name => $code,)*
+                    Medium::Other(code) => code,
+                }
+            }
+        }
+    };
+}
+
+media! {
+    Oil = 0x01,
+    Electricity = 0x02,
+    Gas = 0x03,
+    HeatOutlet = 0x04,
+    Steam = 0x05,
+    WarmWater = 0x06,
+    Water = 0x07,
+    HeatCostAllocator = 0x08,
+    CompressedAir = 0x09,
+    CoolingOutlet = 0x0A,
+    CoolingInlet = 0x0B,
+    HeatInlet = 0x0C,
+    HeatAndCooling = 0x0D,
+    BusSystemComponent = 0x0E,
+    Unknown = 0x0F,
+    CalorificValue = 0x14,
+    HotWater = 0x15,
+    ColdWater = 0x16,
+    DualRegisterWater = 0x17,
+    Pressure = 0x18,
+    AdConverter = 0x19,
+    SmokeDetector = 0x1A,
+    RoomSensor = 0x1B,
+    GasDetector = 0x1C,
+    Breaker = 0x20,
+    Valve = 0x21,
+    CustomerUnit = 0x25,
+    WasteWater = 0x28,
+    Garbage = 0x29,
+    CommunicationController = 0x31,
+    UnidirectionalRepeater = 0x32,
+    BidirectionalRepeater = 0x33,
+    RadioConverterSystemSide = 0x36,
+    RadioConverterMeterSide = 0x37,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion() {
+        assert_eq!(Medium::from(0x07), Medium::Water);
+        assert_eq!(Medium::from(0x04), Medium::HeatOutlet);
+        assert_eq!(Medium::from(0x00), Medium::Other(0x00));
+        assert_eq!(Medium::from(0x3F), Medium::Other(0x3F));
+        for code in 0..=u8::MAX {
+            assert_eq!(u8::from(Medium::from(code)), code);
+        }
+    }
+}
@@ -0,0 +1,582 @@
+//! Application layer (EN 13757-3) carried in the user data of long frames.
+
+use crate::{Frame, FrameRef};
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+use nom::number::Endianness;
+
+mod alarm;
+mod assembler;
+pub mod command;
+mod compact;
+mod control_information;
+mod date;
+mod display;
+mod dlms;
+mod error;
+mod fixed;
+mod header;
+mod history;
+mod identification;
+mod limits;
+mod manufacturer;
+mod medium;
+mod reading;
+mod record;
+mod records;
+mod reset;
+mod select;
+mod sml;
+mod status;
+#[cfg(feature = "uom")]
+mod typed;
+mod user_data;
+mod value;
+mod vif;
+
+pub use alarm::AlarmStatus;
+pub use assembler::TelegramAssembler;
+pub use compact::FormatCache;
+pub use control_information::{BaudRate, ControlInformation};
+pub use date::{Date, DateTime, Time};
+pub use dlms::DlmsData;
+pub use error::ApplicationError;
+pub use fixed::{FixedCounter, FixedDataStructure};
+pub use header::Header;
+pub use identification::Identification;
+pub use limits::{Limit, ParseLimits, ParseOptions};
+pub use manufacturer::{InvalidManufacturerId, ManufacturerId};
+pub use medium::Medium;
+pub use reading::Reading;
+pub use record::{DataField, DataRecord, DataRecordHeader, FunctionField};
+pub use records::Records;
+pub use reset::{ApplicationReset, ResetSubcode, TelegramSelection};
+pub use select::SecondaryAddress;
+pub use sml::SmlData;
+pub use status::Status;
+#[cfg(feature = "uom")]
+pub use typed::TypedValue;
+pub use user_data::UserData;
+pub use value::{Value, ValueError};
+pub use vif::{FlowDirection, PerUnit, Quantity, Unit, ValueInformation};
+
+/// CI of a master request restarting the application of a slave.
+pub const CI_APPLICATION_RESET: u8 = 0x50;
+/// CI of a master request selecting a slave by its secondary address.
+pub const CI_SLAVE_SELECT: u8 = 0x52;
+/// CI of a slave response reporting an application error.
+pub const CI_APPLICATION_ERROR: u8 = 0x70;
+/// CI of a slave response reporting its alarm status.
+pub const CI_ALARM_STATUS: u8 = 0x71;
+/// CI of a slave response using the variable data structure with a long header.
+pub const CI_RESPONSE_VARIABLE: u8 = 0x72;
+/// CI of a slave response using the fixed data structure.
+pub const CI_RESPONSE_FIXED: u8 = 0x73;
+/// Like [`CI_RESPONSE_VARIABLE`], with multi-byte values sent most significant byte first.
+pub const CI_RESPONSE_VARIABLE_MSB_FIRST: u8 = 0x76;
+/// Like [`CI_RESPONSE_FIXED`], with multi-byte values sent most significant byte first.
+pub const CI_RESPONSE_FIXED_MSB_FIRST: u8 = 0x77;
+/// CI of a slave response with data records but without header.
+pub const CI_RESPONSE_NO_HEADER: u8 = 0x78;
+/// CI of a slave response with a compact frame: the data of records whose format is known
+/// from an earlier full frame.
+pub const CI_RESPONSE_COMPACT: u8 = 0x79;
+/// CI of a wireless slave response with a short header: the address is that of the link layer.
+pub const CI_RESPONSE_SHORT_HEADER: u8 = 0x7A;
+
+const IDLE_FILLER: u8 = 0x2F;
+const MANUFACTURER_DATA: u8 = 0x0F;
+const MORE_RECORDS_FOLLOW: u8 = 0x1F;
+
+type IResult<'a, T> = nom::IResult<&'a [u8], T, TelegramError>;
+
+/// Decoded variable data structure: the fixed header followed by a list of data records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Telegram {
+    pub header: Header,
+    pub records: Vec<DataRecord>,
+    /// The telegram ended with DIF 0x1F: the slave has more records to send.
+    pub more_records_follow: bool,
+    /// Manufacturer specific data following DIF 0x0F or 0x1F.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub manufacturer_data: Vec<u8>,
+}
+
+impl Telegram {
+    pub fn from_frame(frame: &Frame) -> Result<Self, TelegramError> {
+        Self::from_frame_ref(frame.as_frame_ref())
+    }
+
+    /// Like [`Telegram::from_frame`], for frames borrowing their user data.
+    pub fn from_frame_ref(frame: FrameRef<'_>) -> Result<Self, TelegramError> {
+        match frame {
+            FrameRef::Long {
+                control_information: CI_RESPONSE_VARIABLE,
+                data,
+                ..
+            } => Self::parse(data),
+            FrameRef::Long {
+                control_information: CI_RESPONSE_VARIABLE_MSB_FIRST,
+                data,
+                ..
+            } => Self::parse_msb_first(data),
+            FrameRef::Control {
+                control_information,
+                ..
+            }
+            | FrameRef::Long {
+                control_information,
+                ..
+            } => Err(TelegramError::UnsupportedControlInformation(
+                control_information,
+            )),
+            FrameRef::Single | FrameRef::Short { .. } => Err(TelegramError::NoUserData),
+        }
+    }
+
+    /// Parses the user data of a frame with CI 0x72.
+    pub fn parse(data: &[u8]) -> Result<Self, TelegramError> {
+        Self::parse_with(data, Endianness::Little)
+    }
+
+    /// Like [`Telegram::parse`], failing with [`TelegramError::LimitExceeded`] for telegrams
+    /// beyond `limits`.
+    pub fn parse_with_limits(data: &[u8], limits: ParseLimits) -> Result<Self, TelegramError> {
+        Self::parse_with_options(
+            data,
+            ParseOptions {
+                limits,
+                ..ParseOptions::default()
+            },
+        )
+    }
+
+    /// Like [`Telegram::parse`], with the records parsed according to `options`.
+    pub fn parse_with_options(data: &[u8], options: ParseOptions) -> Result<Self, TelegramError> {
+        let (header, records) = Self::parse_lazy(data)?;
+        Self::collect(header, records.with_options(options))
+    }
+
+    /// Parses the user data of a frame with CI 0x76, in which multi-byte values are transmitted
+    /// most significant byte first. The header fields and record data are converted to the
+    /// usual byte order.
+    pub fn parse_msb_first(data: &[u8]) -> Result<Self, TelegramError> {
+        Self::parse_with(data, Endianness::Big)
+    }
+
+    /// Encodes the telegram as the user data of a frame with CI 0x72.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.header.encode(&mut out);
+        for record in &self.records {
+            record.encode(&mut out);
+        }
+        if self.more_records_follow {
+            out.push(MORE_RECORDS_FOLLOW);
+        } else if !self.manufacturer_data.is_empty() {
+            out.push(MANUFACTURER_DATA);
+        }
+        out.extend_from_slice(&self.manufacturer_data);
+        out
+    }
+
+    /// Wraps the telegram in the RSP_UD a slave with `address` would send.
+    pub fn to_frame(&self, address: u8) -> Frame {
+        Frame::Long {
+            control: crate::control::RSP_UD,
+            address,
+            control_information: CI_RESPONSE_VARIABLE,
+            data: self.to_bytes(),
+        }
+    }
+
+    /// The window `record` was averaged over: the duration of the averaging duration record
+    /// with the same storage number, tariff and subunit.
+    pub fn averaging_duration(&self, record: &DataRecord) -> Option<Duration> {
+        self.duration_of(record, Quantity::AveragingDuration)
+    }
+
+    /// How long before the readout `record` was measured: the duration of the actuality
+    /// duration record with the same storage number, tariff and subunit.
+    pub fn actuality_duration(&self, record: &DataRecord) -> Option<Duration> {
+        self.duration_of(record, Quantity::ActualityDuration)
+    }
+
+    fn duration_of(&self, record: &DataRecord, quantity: Quantity) -> Option<Duration> {
+        let header = &record.header;
+        self.records
+            .iter()
+            .find(|other| {
+                other.value_information().quantity == quantity
+                    && other.header.storage_number == header.storage_number
+                    && other.header.tariff == header.tariff
+                    && other.header.subunit == header.subunit
+            })
+            .and_then(|other| other.duration().ok().flatten())
+    }
+
+    /// Parses only the header of the user data of a frame with CI 0x72, leaving the records to
+    /// be parsed one at a time.
+    pub fn parse_lazy(data: &[u8]) -> Result<(Header, Records<'_>), TelegramError> {
+        Self::parse_lazy_with(data, Endianness::Little)
+    }
+
+    fn parse_lazy_with(
+        data: &[u8],
+        endianness: Endianness,
+    ) -> Result<(Header, Records<'_>), TelegramError> {
+        let (i, header) = header::header(data, endianness)?;
+        Ok((header, Records::with_endianness(i, endianness)))
+    }
+
+    fn parse_with(data: &[u8], endianness: Endianness) -> Result<Self, TelegramError> {
+        let (header, records) = Self::parse_lazy_with(data, endianness)?;
+        Self::collect(header, records)
+    }
+
+    fn collect(header: Header, mut iter: Records<'_>) -> Result<Self, TelegramError> {
+        let records = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+        let end = iter.remaining();
+
+        Ok(Self {
+            header,
+            records,
+            more_records_follow: end.first() == Some(&MORE_RECORDS_FOLLOW),
+            manufacturer_data: end.get(1..).unwrap_or_default().to_vec(),
+        })
+    }
+}
+
+/// Parses data records up to the end of the data or a manufacturer data block. Also returns
+/// the remaining data, starting with DIF 0x0F or 0x1F if there is any.
+fn records(i: &[u8]) -> Result<(Vec<DataRecord>, &[u8]), TelegramError> {
+    let mut iter = Records::new(i);
+    let records = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+    Ok((records, iter.remaining()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TelegramError {
+    NoUserData,
+    UnsupportedControlInformation(u8),
+    UnexpectedEnd,
+    UnsupportedDataField(u8),
+    InvalidBcd,
+    TooManyExtensions,
+    InvalidLvar(u8),
+    MismatchedTelegram,
+    UnknownFormat(u16),
+    DataCrcMismatch,
+    /// The telegram exceeds one of the [`ParseLimits`].
+    LimitExceeded(Limit),
+}
+
+impl fmt::Display for TelegramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TelegramError::NoUserData => write!(f, "frame does not carry user data"),
+            TelegramError::UnsupportedControlInformation(ci) => {
+                write!(f, "unsupported control information 0x{:02X}", ci)
+            }
+            TelegramError::UnexpectedEnd => write!(f, "telegram ended unexpectedly"),
+            TelegramError::UnsupportedDataField(coding) => {
+                write!(f, "unsupported data field coding 0x{:X}", coding)
+            }
+            TelegramError::InvalidBcd => write!(f, "invalid BCD digits"),
+            TelegramError::TooManyExtensions => write!(f, "too many extension bytes"),
+            TelegramError::InvalidLvar(lvar) => write!(f, "reserved LVAR value 0x{:02X}", lvar),
+            TelegramError::MismatchedTelegram => {
+                write!(f, "telegram belongs to a different meter")
+            }
+            TelegramError::UnknownFormat(signature) => {
+                write!(f, "unknown format signature 0x{:04X}", signature)
+            }
+            TelegramError::DataCrcMismatch => write!(f, "CRC of the expanded data does not match"),
+            TelegramError::LimitExceeded(limit) => write!(f, "too many {} in telegram", limit),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TelegramError {}
+
+impl<'a> nom::error::ParseError<&'a [u8]> for TelegramError {
+    // the telegram parsers only fail on their own errors or when running out of input
+    fn from_error_kind(_: &'a [u8], _: nom::error::ErrorKind) -> Self {
+        Self::UnexpectedEnd
+    }
+
+    fn append(_: &'a [u8], _: nom::error::ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl From<nom::Err<TelegramError>> for TelegramError {
+    fn from(e: nom::Err<TelegramError>) -> Self {
+        match e {
+            nom::Err::Incomplete(_) => TelegramError::UnexpectedEnd,
+            nom::Err::Error(e) | nom::Err::Failure(e) => e,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_parse() {
+        // example from EN 13757-3 annex
+        let frame = Frame::from_bytes(&[
+            0x68, 0x1F, 0x1F, 0x68, 0x08, 0x02, 0x72, 0x78, 0x56, 0x34, 0x12, 0x24, 0x40, 0x01,
+            0x07, 0x55, 0x00, 0x00, 0x00, 0x03, 0x13, 0x15, 0x31, 0x00, 0xDA, 0x02, 0x3B, 0x13,
+            0x01, 0x8B, 0x60, 0x04, 0x37, 0x18, 0x02, 0x18, 0x16,
+        ])
+        .unwrap();
+
+        let telegram = Telegram::from_frame(&frame).unwrap();
+        assert_eq!(
+            telegram.header,
+            Header {
+                identification: 0x12345678,
+                manufacturer: ManufacturerId::from(0x4024),
+                version: 0x01,
+                medium: Medium::Water,
+                access_number: 0x55,
+                status: Status::default(),
+                signature: 0x0000,
+            }
+        );
+        assert_eq!(
+            telegram.records,
+            vec![
+                DataRecord {
+                    header: DataRecordHeader {
+                        data_field: DataField::Int24,
+                        function: FunctionField::Instantaneous,
+                        storage_number: 0,
+                        tariff: 0,
+                        subunit: 0,
+                    },
+                    vif: 0x13,
+                    vife: vec![],
+                    plaintext_unit: None,
+                    raw_header: vec![],
+                    data: vec![0x15, 0x31, 0x00],
+                },
+                DataRecord {
+                    header: DataRecordHeader {
+                        data_field: DataField::Bcd4,
+                        function: FunctionField::Maximum,
+                        storage_number: 5,
+                        tariff: 0,
+                        subunit: 0,
+                    },
+                    vif: 0x3B,
+                    vife: vec![],
+                    plaintext_unit: None,
+                    raw_header: vec![],
+                    data: vec![0x13, 0x01],
+                },
+                DataRecord {
+                    header: DataRecordHeader {
+                        data_field: DataField::Bcd6,
+                        function: FunctionField::Instantaneous,
+                        storage_number: 0,
+                        tariff: 2,
+                        subunit: 1,
+                    },
+                    vif: 0x04,
+                    vife: vec![],
+                    plaintext_unit: None,
+                    raw_header: vec![],
+                    data: vec![0x37, 0x18, 0x02],
+                },
+            ]
+        );
+
+        assert_eq!(
+            telegram.records[2].value_information(),
+            ValueInformation {
+                quantity: Quantity::Energy,
+                unit: Unit::WattHour,
+                exponent: 1,
+                offset: None,
+                per: None,
+                direction: None,
+            }
+        );
+
+        assert_eq!(telegram.records[0].value(), Ok(Value::I64(0x3115)));
+        assert_eq!(telegram.records[1].value(), Ok(Value::Bcd(113)));
+        assert_eq!(telegram.records[2].value(), Ok(Value::Bcd(21837)));
+
+        assert_eq!(
+            Telegram::parse(&[0x78, 0x56, 0x34, 0x12, 0x24, 0x40, 0x01, 0x07, 0x55, 0x00, 0x00]),
+            Err(TelegramError::UnexpectedEnd)
+        );
+        assert_eq!(telegram.to_frame(0x02), frame);
+
+        let msb_first = Telegram::parse_msb_first(&[
+            0x12, 0x34, 0x56, 0x78, 0x40, 0x24, 0x01, 0x07, 0x55, 0x00, 0x00, 0x00, 0x03, 0x13,
+            0x00, 0x31, 0x15, 0x0D, 0x13, 0xC2, 0x12, 0x34,
+        ])
+        .unwrap();
+        assert_eq!(msb_first.header, telegram.header);
+        assert_eq!(msb_first.records[0].data, telegram.records[0].data);
+        assert_eq!(msb_first.records[1].data, [0xC2, 0x34, 0x12]);
+        assert_eq!(msb_first.records[1].value(), Ok(Value::Bcd(1234)));
+
+        // the 48 and 64 byte binary LVARs are numbers too
+        for (lvar, length) in [(0xF5, 48), (0xF6, 64)] {
+            let mut data = vec![0x12, 0x34, 0x56, 0x78, 0x40, 0x24, 0x01, 0x07, 0x55, 0x00];
+            data.extend_from_slice(&[0x00, 0x00, 0x0D, 0x13, lvar]);
+            data.extend(1..=length);
+            let msb_first = Telegram::parse_msb_first(&data).unwrap();
+            assert_eq!(msb_first.records[0].data[0], lvar);
+            assert!(msb_first.records[0].data[1..]
+                .iter()
+                .copied()
+                .eq((1..=length).rev()));
+        }
+
+        let mut data = frame.addressed().unwrap().data().to_vec();
+        data.extend_from_slice(&[0x0F, 0xCA, 0xFE]);
+        let telegram = Telegram::parse(&data).unwrap();
+        assert_eq!(telegram.records.len(), 3);
+        assert!(!telegram.more_records_follow);
+        assert_eq!(telegram.manufacturer_data, [0xCA, 0xFE]);
+        assert_eq!(telegram.to_bytes(), data);
+
+        assert_eq!(
+            Telegram::from_frame(&Frame::Single),
+            Err(TelegramError::NoUserData)
+        );
+    }
+
+    #[test]
+    fn test_hand_built_meters() {
+        use alloc::string::{String, ToString};
+
+        // Hand-built after the annex example, not captures of real meters: the expected values
+        // are decoded by hand from EN 13757-3.
+        fn records(bytes: &[u8]) -> (String, Vec<(u64, u32, Value)>) {
+            let telegram = Telegram::from_frame(&Frame::from_bytes(bytes).unwrap()).unwrap();
+            let records = telegram
+                .records
+                .iter()
+                .map(|record| {
+                    let header = &record.header;
+                    (
+                        header.storage_number,
+                        header.tariff,
+                        record.value().unwrap(),
+                    )
+                })
+                .collect();
+            (telegram.header.manufacturer.to_string(), records)
+        }
+        fn date(year: u16, month: u8, day: u8) -> Date {
+            Date { year, month, day }
+        }
+
+        // heat meter: energy, volume, power, flow and return temperature, date of readout and
+        // the energy at the last due date
+        let heat_meter = [
+            0x68, 0x36, 0x36, 0x68, 0x08, 0x01, 0x72, 0x01, 0x23, 0x45, 0x67, 0x42, 0x04, 0x01,
+            0x04, 0x10, 0x00, 0x00, 0x00, 0x0C, 0x06, 0x78, 0x56, 0x34, 0x12, 0x0C, 0x14, 0x27,
+            0x04, 0x00, 0x00, 0x0B, 0x2D, 0x15, 0x00, 0x00, 0x0A, 0x5A, 0x54, 0x06, 0x0A, 0x5E,
+            0x22, 0x04, 0x02, 0x6C, 0x0F, 0x33, 0x4C, 0x06, 0x00, 0x50, 0x34, 0x12, 0x42, 0x6C,
+            0x01, 0x31, 0x28, 0x16,
+        ];
+        assert_eq!(
+            records(&heat_meter),
+            (
+                "ABB".into(),
+                vec![
+                    (0, 0, Value::Bcd(12345678)),
+                    (0, 0, Value::Bcd(427)),
+                    (0, 0, Value::Bcd(15)),
+                    (0, 0, Value::Bcd(654)),
+                    (0, 0, Value::Bcd(422)),
+                    (0, 0, Value::Date(date(2024, 3, 15))),
+                    (1, 0, Value::Bcd(12345000)),
+                    (1, 0, Value::Date(date(2024, 1, 1))),
+                ]
+            )
+        );
+
+        // water meter: volume with the date and time of readout, the volume at the last due
+        // date and error flags
+        let water_meter = [
+            0x68, 0x2A, 0x2A, 0x68, 0x08, 0x01, 0x72, 0x45, 0x23, 0x01, 0x00, 0x25, 0x4D, 0x15,
+            0x07, 0x2A, 0x00, 0x00, 0x00, 0x04, 0x13, 0x45, 0x2D, 0x01, 0x00, 0x04, 0x6D, 0x2D,
+            0x0C, 0x1E, 0x36, 0x44, 0x13, 0x10, 0x27, 0x01, 0x00, 0x42, 0x6C, 0x1F, 0x35, 0x02,
+            0xFD, 0x17, 0x00, 0x00, 0xCB, 0x16,
+        ];
+        let read_out = DateTime {
+            date: date(2024, 6, 30),
+            time: Time {
+                hour: 12,
+                minute: 45,
+                second: 0,
+            },
+            invalid: false,
+            summer_time: false,
+        };
+        assert_eq!(
+            records(&water_meter),
+            (
+                "SIE".into(),
+                vec![
+                    (0, 0, Value::I64(77125)),
+                    (0, 0, Value::DateTime(read_out)),
+                    (1, 0, Value::I64(75536)),
+                    (1, 0, Value::Date(date(2024, 5, 31))),
+                    (0, 0, Value::I64(0)),
+                ]
+            )
+        );
+
+        // electricity meter: total energy and per tariff, power as integer and as float
+        let electricity_meter = [
+            0x68, 0x35, 0x35, 0x68, 0x08, 0x01, 0x72, 0x01, 0x01, 0x24, 0x20, 0x42, 0x04, 0x02,
+            0x02, 0x03, 0x00, 0x00, 0x00, 0x0E, 0x04, 0x41, 0x03, 0x25, 0x00, 0x00, 0x00, 0x8E,
+            0x10, 0x04, 0x11, 0x02, 0x10, 0x00, 0x00, 0x00, 0x8E, 0x20, 0x04, 0x30, 0x01, 0x15,
+            0x00, 0x00, 0x00, 0x04, 0x2B, 0x20, 0x03, 0x00, 0x00, 0x05, 0x2E, 0x00, 0x00, 0x20,
+            0x40, 0x2B, 0x16,
+        ];
+        assert_eq!(
+            records(&electricity_meter),
+            (
+                "ABB".into(),
+                vec![
+                    (0, 0, Value::Bcd(250341)),
+                    (0, 1, Value::Bcd(100211)),
+                    (0, 2, Value::Bcd(150130)),
+                    (0, 0, Value::I64(800)),
+                    (0, 0, Value::F64(2.5)),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_averaging_duration() {
+        // power averaged over 15 minutes, and power at the due date without averaging duration
+        let telegram = Telegram::parse(&[
+            0x78, 0x56, 0x34, 0x12, 0x24, 0x40, 0x01, 0x07, 0x55, 0x00, 0x00, 0x00, 0x02, 0x2B,
+            0xE8, 0x03, 0x01, 0x71, 0x0F, 0x42, 0x2B, 0xD0, 0x07,
+        ])
+        .unwrap();
+        assert_eq!(
+            telegram.averaging_duration(&telegram.records[0]),
+            Some(Duration::from_secs(900))
+        );
+        assert_eq!(telegram.averaging_duration(&telegram.records[2]), None);
+        assert_eq!(telegram.actuality_duration(&telegram.records[0]), None);
+    }
+}
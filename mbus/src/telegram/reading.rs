@@ -0,0 +1,106 @@
+//! Numeric records reduced to what they measure, for feeding readings into other systems.
+
+use super::{DataRecord, FunctionField, Quantity, Unit, ValueError};
+
+/// The value of a record scaled to `unit`, with where it was stored. With the `serde`
+/// feature, an energy reading serializes as:
+///
+/// ```json
+/// {"quantity":"Energy","unit":"WattHour","value":218370.0,
+///  "function":"Instantaneous","storage_number":0,"tariff":2,"subunit":1}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reading {
+    pub quantity: Quantity,
+    pub unit: Unit,
+    pub value: f64,
+    pub function: FunctionField,
+    pub storage_number: u64,
+    pub tariff: u32,
+    pub subunit: u16,
+}
+
+impl DataRecord {
+    /// The record as a [`Reading`], `None` for values that are not numeric like dates and text.
+    pub fn reading(&self) -> Result<Option<Reading>, ValueError> {
+        let quantity = self.value_information().quantity;
+        Ok(self.normalized()?.map(|(value, unit)| Reading {
+            quantity,
+            unit,
+            value,
+            function: self.header.function,
+            storage_number: self.header.storage_number,
+            tariff: self.header.tariff,
+            subunit: self.header.subunit,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::record::data_record;
+
+    // 21837 * 10 Wh in tariff 2 of subunit 1
+    const ENERGY: [u8; 6] = [0x8B, 0x60, 0x04, 0x37, 0x18, 0x02];
+
+    #[test]
+    fn test_reading() {
+        let (_, record) = data_record(&ENERGY).unwrap();
+        assert_eq!(
+            record.reading(),
+            Ok(Some(Reading {
+                quantity: Quantity::Energy,
+                unit: Unit::WattHour,
+                value: 218370.0,
+                function: FunctionField::Instantaneous,
+                storage_number: 0,
+                tariff: 2,
+                subunit: 1,
+            }))
+        );
+
+        // a date has no numeric value
+        let (_, record) = data_record(&[0x02, 0x6C, 0x61, 0x2C]).unwrap();
+        assert_eq!(record.reading(), Ok(None));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use crate::telegram::{Date, Value};
+
+        let (_, record) = data_record(&ENERGY).unwrap();
+        let reading = record.reading().unwrap().unwrap();
+        let json = serde_json::to_string(&reading).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"quantity":"Energy","unit":"WattHour","value":218370.0,"#,
+                r#""function":"Instantaneous","storage_number":0,"tariff":2,"subunit":1}"#
+            )
+        );
+        assert_eq!(serde_json::from_str::<Reading>(&json).unwrap(), reading);
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(serde_json::from_str::<DataRecord>(&json).unwrap(), record);
+
+        for value in [
+            Value::Bcd(21837),
+            Value::Date(Date {
+                year: 2024,
+                month: 12,
+                day: 1,
+            }),
+            Value::Binary(alloc::vec![0xCA, 0xFE]),
+        ] {
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), value);
+        }
+        assert_eq!(
+            serde_json::to_string(&Value::Binary(alloc::vec![0xCA, 0xFE])).unwrap(),
+            r#"{"Binary":"CAFE"}"#
+        );
+    }
+}
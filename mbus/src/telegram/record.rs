@@ -0,0 +1,424 @@
+use super::{IResult, Limit, ParseLimits, TelegramError, ValueInformation};
+use alloc::string::String;
+use alloc::vec::Vec;
+use nom::bytes::complete::take;
+use nom::number::complete::u8;
+
+const EXTENSION: u8 = 0x80;
+const VIF_PLAINTEXT: u8 = 0x7C;
+const DIF_STORAGE: u8 = 0x40;
+const DIFE_SUBUNIT: u8 = 0x40;
+
+/// Data field coding from the lower nibble of the DIF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataField {
+    None,
+    Int8,
+    Int16,
+    Int24,
+    Int32,
+    Real32,
+    Int48,
+    Int64,
+    SelectionForReadout,
+    Bcd2,
+    Bcd4,
+    Bcd6,
+    Bcd8,
+    VariableLength,
+    Bcd12,
+    Special,
+}
+
+impl DataField {
+    fn from_dif(dif: u8) -> Self {
+        match dif & 0x0F {
+            0x0 => DataField::None,
+            0x1 => DataField::Int8,
+            0x2 => DataField::Int16,
+            0x3 => DataField::Int24,
+            0x4 => DataField::Int32,
+            0x5 => DataField::Real32,
+            0x6 => DataField::Int48,
+            0x7 => DataField::Int64,
+            0x8 => DataField::SelectionForReadout,
+            0x9 => DataField::Bcd2,
+            0xA => DataField::Bcd4,
+            0xB => DataField::Bcd6,
+            0xC => DataField::Bcd8,
+            0xD => DataField::VariableLength,
+            0xE => DataField::Bcd12,
+            _ => DataField::Special,
+        }
+    }
+
+    fn bits(self) -> u8 {
+        match self {
+            DataField::None => 0x0,
+            DataField::Int8 => 0x1,
+            DataField::Int16 => 0x2,
+            DataField::Int24 => 0x3,
+            DataField::Int32 => 0x4,
+            DataField::Real32 => 0x5,
+            DataField::Int48 => 0x6,
+            DataField::Int64 => 0x7,
+            DataField::SelectionForReadout => 0x8,
+            DataField::Bcd2 => 0x9,
+            DataField::Bcd4 => 0xA,
+            DataField::Bcd6 => 0xB,
+            DataField::Bcd8 => 0xC,
+            DataField::VariableLength => 0xD,
+            DataField::Bcd12 => 0xE,
+            DataField::Special => 0xF,
+        }
+    }
+
+    /// Number of data bytes, if fixed.
+    pub fn length(self) -> Option<usize> {
+        match self {
+            DataField::None | DataField::SelectionForReadout => Some(0),
+            DataField::Int8 | DataField::Bcd2 => Some(1),
+            DataField::Int16 | DataField::Bcd4 => Some(2),
+            DataField::Int24 | DataField::Bcd6 => Some(3),
+            DataField::Int32 | DataField::Real32 | DataField::Bcd8 => Some(4),
+            DataField::Int48 | DataField::Bcd12 => Some(6),
+            DataField::Int64 => Some(8),
+            DataField::VariableLength | DataField::Special => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FunctionField {
+    Instantaneous,
+    Maximum,
+    Minimum,
+    DuringError,
+}
+
+impl FunctionField {
+    fn from_dif(dif: u8) -> Self {
+        match (dif >> 4) & 0x03 {
+            0 => FunctionField::Instantaneous,
+            1 => FunctionField::Maximum,
+            2 => FunctionField::Minimum,
+            _ => FunctionField::DuringError,
+        }
+    }
+
+    fn bits(self) -> u8 {
+        match self {
+            FunctionField::Instantaneous => 0x00,
+            FunctionField::Maximum => 0x10,
+            FunctionField::Minimum => 0x20,
+            FunctionField::DuringError => 0x30,
+        }
+    }
+}
+
+/// Decoded data information block: the DIF and its DIFEs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataRecordHeader {
+    pub data_field: DataField,
+    pub function: FunctionField,
+    pub storage_number: u64,
+    pub tariff: u32,
+    pub subunit: u16,
+}
+
+impl DataRecordHeader {
+    /// Appends the DIF and as many DIFEs as needed for the storage number, tariff and subunit.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        let mut dif = self.data_field.bits() | self.function.bits();
+        if self.storage_number & 1 != 0 {
+            dif |= DIF_STORAGE;
+        }
+
+        let mut storage_number = self.storage_number >> 1;
+        let mut tariff = self.tariff;
+        let mut subunit = self.subunit;
+        let mut last = out.len();
+        out.push(dif);
+        while storage_number != 0 || tariff != 0 || subunit != 0 {
+            out[last] |= EXTENSION;
+            last = out.len();
+            out.push(
+                (storage_number & 0x0F) as u8
+                    | ((tariff & 0x03) as u8) << 4
+                    | ((subunit & 0x01) as u8) << 6,
+            );
+            storage_number >>= 4;
+            tariff >>= 2;
+            subunit >>= 1;
+        }
+    }
+}
+
+/// A single data record: its data information block, the value information block (VIF + VIFEs)
+/// and the raw data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataRecord {
+    pub header: DataRecordHeader,
+    pub vif: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub vife: Vec<u8>,
+    /// Unit given as text by a plain-text VIF (0x7C).
+    pub plaintext_unit: Option<String>,
+    /// The DIF, DIFEs, VIF, VIFEs and plain-text unit as transmitted, when parsed with
+    /// [`ParseOptions::keep_raw`](super::ParseOptions::keep_raw). Empty otherwise.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_hex"))]
+    pub raw_header: Vec<u8>,
+    /// Raw data, starting with the LVAR byte for variable length data.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub data: Vec<u8>,
+}
+
+impl DataRecord {
+    pub fn value_information(&self) -> ValueInformation {
+        ValueInformation::decode(self.vif, &self.vife)
+    }
+
+    /// Appends the record as transmitted. The extension bits of the VIF and VIFEs are taken as
+    /// is, so they have to match the number of VIFEs.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        self.header.encode(out);
+        out.push(self.vif);
+        out.extend_from_slice(&self.vife);
+        if let Some(unit) = &self.plaintext_unit {
+            out.push(unit.chars().count() as u8);
+            out.extend(unit.chars().rev().map(|c| c as u8));
+        }
+        out.extend_from_slice(&self.data);
+    }
+}
+
+/// Number of data bytes following the LVAR byte of a variable length record.
+pub(super) fn lvar_length(lvar: u8) -> Option<usize> {
+    match lvar {
+        // text, positive and negative BCD, binary
+        0x00..=0xBF => Some(lvar.into()),
+        0xC0..=0xC9 => Some((lvar - 0xC0).into()),
+        0xD0..=0xD9 => Some((lvar - 0xD0).into()),
+        0xE0..=0xEF => Some((lvar - 0xE0).into()),
+        0xF0..=0xF4 => Some(4 * usize::from(lvar - 0xEC)),
+        0xF5 => Some(48),
+        0xF6 => Some(64),
+        _ => None,
+    }
+}
+
+/// Reads the extension bytes following `first`, as long as the previous one has its extension
+/// bit set, failing with [`Limit::Vifes`] after `max` bytes.
+fn extensions(first: u8, max: usize) -> impl FnMut(&[u8]) -> IResult<'_, Vec<u8>> {
+    move |mut i| {
+        let mut extensions = Vec::new();
+        let mut last = first;
+        while last & EXTENSION != 0 {
+            if extensions.len() == max {
+                return Err(nom::Err::Failure(TelegramError::LimitExceeded(
+                    Limit::Vifes,
+                )));
+            }
+            let (rest, b) = u8(i)?;
+            extensions.push(b);
+            last = b;
+            i = rest;
+        }
+        Ok((i, extensions))
+    }
+}
+
+fn data_record_header(i: &[u8]) -> IResult<'_, DataRecordHeader> {
+    let (mut i, dif) = u8(i)?;
+
+    let mut header = DataRecordHeader {
+        data_field: DataField::from_dif(dif),
+        function: FunctionField::from_dif(dif),
+        storage_number: u64::from((dif & DIF_STORAGE) >> 6),
+        tariff: 0,
+        subunit: 0,
+    };
+
+    // every DIFE contributes the next more significant bits
+    let mut last = dif;
+    let mut n = 0;
+    while last & EXTENSION != 0 {
+        if n == 10 {
+            return Err(nom::Err::Failure(TelegramError::TooManyExtensions));
+        }
+        let (rest, dife) = u8(i)?;
+        header.storage_number |= u64::from(dife & 0x0F) << (1 + 4 * n);
+        header.tariff |= u32::from((dife >> 4) & 0x03) << (2 * n);
+        header.subunit |= u16::from((dife & DIFE_SUBUNIT) >> 6) << n;
+        last = dife;
+        n += 1;
+        i = rest;
+    }
+
+    Ok((i, header))
+}
+
+/// Everything of a record but its data: the part that compact frames leave out.
+pub(super) struct RecordFormat {
+    header: DataRecordHeader,
+    vif: u8,
+    vife: Vec<u8>,
+    plaintext_unit: Option<String>,
+}
+
+impl RecordFormat {
+    pub(super) fn data_field(&self) -> DataField {
+        self.header.data_field
+    }
+
+    pub(super) fn with_data(self, data: &[u8]) -> DataRecord {
+        DataRecord {
+            header: self.header,
+            vif: self.vif,
+            vife: self.vife,
+            plaintext_unit: self.plaintext_unit,
+            raw_header: Vec::new(),
+            data: data.to_vec(),
+        }
+    }
+}
+
+pub(super) fn record_format(limits: ParseLimits) -> impl FnMut(&[u8]) -> IResult<'_, RecordFormat> {
+    move |i| {
+        let (i, header) = data_record_header(i)?;
+        let (i, vif) = u8(i)?;
+        let (i, vife) = extensions(vif, limits.max_vifes)(i)?;
+        let (i, plaintext_unit) = if vif & !EXTENSION == VIF_PLAINTEXT {
+            let (i, length) = u8(i)?;
+            let (i, text) = take(length)(i)?;
+            // the text is transmitted last character first
+            (
+                i,
+                Some(text.iter().rev().copied().map(char::from).collect()),
+            )
+        } else {
+            (i, None)
+        };
+
+        Ok((
+            i,
+            RecordFormat {
+                header,
+                vif,
+                vife,
+                plaintext_unit,
+            },
+        ))
+    }
+}
+
+pub(super) fn record_data(
+    data_field: DataField,
+    limits: ParseLimits,
+) -> impl FnMut(&[u8]) -> IResult<'_, &[u8]> {
+    move |i| {
+        let length = match data_field {
+            DataField::VariableLength => {
+                let (_, lvar) = u8(i)?;
+                let length =
+                    lvar_length(lvar).ok_or(nom::Err::Failure(TelegramError::InvalidLvar(lvar)))?;
+                if length > limits.max_lvar_length {
+                    return Err(nom::Err::Failure(TelegramError::LimitExceeded(
+                        Limit::LvarLength,
+                    )));
+                }
+                1 + length
+            }
+            data_field => data_field
+                .length()
+                .ok_or(nom::Err::Failure(TelegramError::UnsupportedDataField(0xF)))?,
+        };
+        take(length)(i)
+    }
+}
+
+pub(super) fn data_record_with_limits(
+    limits: ParseLimits,
+) -> impl FnMut(&[u8]) -> IResult<'_, DataRecord> {
+    move |i| {
+        let (i, format) = record_format(limits)(i)?;
+        let (i, data) = record_data(format.data_field(), limits)(i)?;
+        Ok((i, format.with_data(data)))
+    }
+}
+
+#[cfg(test)]
+pub(super) fn data_record(i: &[u8]) -> IResult<'_, DataRecord> {
+    data_record_with_limits(ParseLimits::default())(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::Quantity;
+
+    #[test]
+    fn test_plaintext_unit() {
+        let (rest, record) =
+            data_record(&[0x02, 0xFC, 0x74, 0x03, b'l', b'm', b'p', 0x39, 0x30, 0x01]).unwrap();
+        assert_eq!(rest, &[0x01]);
+        assert_eq!(record.vif, 0xFC);
+        assert_eq!(record.vife, [0x74]);
+        assert_eq!(record.plaintext_unit.as_deref(), Some("pml"));
+        assert_eq!(record.data, [0x39, 0x30]);
+        assert_eq!(record.value_information().quantity, Quantity::PlainText);
+    }
+
+    #[test]
+    fn test_encode() {
+        for bytes in [
+            &[0xC4, 0xFF, 0x01, 0x13, 0x01, 0x02, 0x03, 0x04][..],
+            &[0x5B, 0x13, 0x01, 0x02, 0x03],
+            &[0x02, 0xFC, 0x74, 0x03, b'l', b'm', b'p', 0x39, 0x30],
+            &[0x0D, 0xFD, 0x11, 0x03, b'C', b'B', b'A'],
+        ] {
+            let (_, record) = data_record(bytes).unwrap();
+            let mut encoded = Vec::new();
+            record.encode(&mut encoded);
+            assert_eq!(encoded, bytes);
+        }
+    }
+
+    #[test]
+    fn test_data_record_header() {
+        assert_eq!(
+            data_record_header(&[0xC4, 0xFF, 0x01, 0x13]),
+            Ok((
+                &[0x13][..],
+                DataRecordHeader {
+                    data_field: DataField::Int32,
+                    function: FunctionField::Instantaneous,
+                    storage_number: 1 | 0xF << 1 | 0x1 << 5,
+                    tariff: 3,
+                    subunit: 1,
+                }
+            ))
+        );
+        assert_eq!(
+            data_record_header(&[0x5B]),
+            Ok((
+                &[][..],
+                DataRecordHeader {
+                    data_field: DataField::Bcd6,
+                    function: FunctionField::Maximum,
+                    storage_number: 1,
+                    tariff: 0,
+                    subunit: 0,
+                }
+            ))
+        );
+        assert_eq!(
+            data_record_header(&[0x84; 12]),
+            Err(nom::Err::Failure(TelegramError::TooManyExtensions))
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use super::{
+    record, DataRecord, Limit, ParseLimits, ParseOptions, TelegramError, IDLE_FILLER,
+    MANUFACTURER_DATA, MORE_RECORDS_FOLLOW,
+};
+use core::iter::FusedIterator;
+use nom::number::Endianness;
+
+/// Parses data records on demand from the data following the header. As the length of a
+/// malformed record is unknown, the iterator ends after yielding its error; the records before
+/// it are still usable.
+#[derive(Debug, Clone)]
+pub struct Records<'a> {
+    data: &'a [u8],
+    endianness: Endianness,
+    options: ParseOptions,
+    count: usize,
+    failed: bool,
+}
+
+impl<'a> Records<'a> {
+    /// Records transmitted least significant byte first, as with CI 0x72.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self::with_endianness(data, Endianness::Little)
+    }
+
+    pub(super) fn with_endianness(data: &'a [u8], endianness: Endianness) -> Self {
+        Self {
+            data,
+            endianness,
+            options: ParseOptions::default(),
+            count: 0,
+            failed: false,
+        }
+    }
+
+    /// Parses within `limits` instead of the default ones.
+    pub fn with_limits(mut self, limits: ParseLimits) -> Self {
+        self.options.limits = limits;
+        self
+    }
+
+    /// Parses according to `options`, including its limits.
+    pub fn with_options(self, options: ParseOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    /// The data that has not been parsed yet. Once the iterator is exhausted without error,
+    /// this is the manufacturer data block, starting with DIF 0x0F or 0x1F, if there is any.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl Iterator for Records<'_> {
+    type Item = Result<DataRecord, TelegramError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            match self.data.first() {
+                None | Some(&MANUFACTURER_DATA) | Some(&MORE_RECORDS_FOLLOW) => return None,
+                Some(&IDLE_FILLER) => self.data = &self.data[1..],
+                Some(_) => break,
+            }
+        }
+
+        if self.count == self.options.limits.max_records {
+            self.failed = true;
+            return Some(Err(TelegramError::LimitExceeded(Limit::Records)));
+        }
+        match record::data_record_with_limits(self.options.limits)(self.data) {
+            Ok((rest, mut record)) => {
+                if self.options.keep_raw {
+                    let length = self.data.len() - rest.len() - record.data.len();
+                    record.raw_header = self.data[..length].to_vec();
+                }
+                self.data = rest;
+                self.count += 1;
+                if self.endianness == Endianness::Big {
+                    reverse_numbers(&mut record);
+                }
+                Some(Ok(record))
+            }
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err.into()))
+            }
+        }
+    }
+}
+
+impl FusedIterator for Records<'_> {}
+
+/// Brings the numbers of a record transmitted most significant byte first into the order of
+/// CI 0x72. Of variable length data, only the BCD and binary payload after the LVAR is a number.
+fn reverse_numbers(record: &mut DataRecord) {
+    if record.header.data_field.length().is_some() {
+        record.data.reverse();
+    } else if let Some((0xC0..=0xC9 | 0xD0..=0xD9 | 0xE0..=0xEF | 0xF0..=0xF6, payload)) =
+        record.data.split_first_mut()
+    {
+        payload.reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records() {
+        // a good record, idle filler, a record with a reserved LVAR and one that is never reached
+        let data = [
+            0x03, 0x13, 0x15, 0x31, 0x00, 0x2F, 0x0D, 0x13, 0xFA, 0x01, 0xFD, 0x17, 0x00,
+        ];
+        let mut records = Records::new(&data);
+        assert_eq!(
+            records
+                .next()
+                .map(|record| record.map(|record| record.data)),
+            Some(Ok([0x15, 0x31, 0x00].into()))
+        );
+        assert_eq!(records.next(), Some(Err(TelegramError::InvalidLvar(0xFA))));
+        assert_eq!(records.next(), None);
+
+        let mut records = Records::new(&[0x01, 0xFD, 0x17, 0x00, 0x1F, 0xAB]);
+        assert!(matches!(records.next(), Some(Ok(_))));
+        assert_eq!(records.next(), None);
+        assert_eq!(records.remaining(), &[0x1F, 0xAB]);
+
+        let limits = ParseLimits {
+            max_records: 1,
+            max_lvar_length: 2,
+            max_vifes: 1,
+        };
+        let mut records =
+            Records::new(&[0x01, 0xFD, 0x17, 0x00, 0x01, 0x13, 0x00]).with_limits(limits);
+        assert!(matches!(records.next(), Some(Ok(_))));
+        assert_eq!(
+            records.next(),
+            Some(Err(TelegramError::LimitExceeded(Limit::Records)))
+        );
+        let mut records = Records::new(&[0x0D, 0x13, 0x03, 0x01, 0x02, 0x03]).with_limits(limits);
+        assert_eq!(
+            records.next(),
+            Some(Err(TelegramError::LimitExceeded(Limit::LvarLength)))
+        );
+        let mut records = Records::new(&[0x01, 0x93, 0xBB, 0x73, 0x00]).with_limits(limits);
+        assert_eq!(
+            records.next(),
+            Some(Err(TelegramError::LimitExceeded(Limit::Vifes)))
+        );
+    }
+
+    #[test]
+    fn test_keep_raw() {
+        use alloc::vec::Vec;
+
+        // a DIF with a DIFE for tariff 1 and a volume VIF with a VIFE
+        let data = [
+            0xC4, 0x10, 0x93, 0x3C, 0x01, 0x02, 0x03, 0x04, 0x01, 0xFD, 0x17, 0x00,
+        ];
+        let options = ParseOptions {
+            keep_raw: true,
+            ..ParseOptions::default()
+        };
+        let records = Records::new(&data)
+            .with_options(options)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records[0].raw_header, data[..4]);
+        assert_eq!(records[0].data, data[4..8]);
+        assert_eq!(records[1].raw_header, data[8..11]);
+
+        let record = Records::new(&data).next().unwrap().unwrap();
+        assert!(record.raw_header.is_empty());
+    }
+}
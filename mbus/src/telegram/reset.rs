@@ -0,0 +1,181 @@
+use super::{TelegramError, CI_APPLICATION_RESET};
+use crate::control::SND_UD;
+use crate::Frame;
+use alloc::vec;
+
+/// Telegrams a slave sends after an application reset with a subcode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TelegramSelection {
+    All,
+    UserData,
+    SimpleBilling,
+    EnhancedBilling,
+    MultiTariffBilling,
+    InstantaneousValues,
+    LoadManagement,
+    InstallationAndStartup,
+    Testing,
+    Calibration,
+    Manufacturing,
+    Development,
+    SelfTest,
+    /// A reserved selection, carrying the upper nibble of the subcode.
+    Reserved(u8),
+}
+
+impl From<u8> for TelegramSelection {
+    fn from(nibble: u8) -> Self {
+        match nibble {
+            0x0 => TelegramSelection::All,
+            0x1 => TelegramSelection::UserData,
+            0x2 => TelegramSelection::SimpleBilling,
+            0x3 => TelegramSelection::EnhancedBilling,
+            0x4 => TelegramSelection::MultiTariffBilling,
+            0x5 => TelegramSelection::InstantaneousValues,
+            0x6 => TelegramSelection::LoadManagement,
+            0x8 => TelegramSelection::InstallationAndStartup,
+            0x9 => TelegramSelection::Testing,
+            0xA => TelegramSelection::Calibration,
+            0xB => TelegramSelection::Manufacturing,
+            0xC => TelegramSelection::Development,
+            0xD => TelegramSelection::SelfTest,
+            nibble => TelegramSelection::Reserved(nibble),
+        }
+    }
+}
+
+impl From<TelegramSelection> for u8 {
+    fn from(selection: TelegramSelection) -> Self {
+        match selection {
+            TelegramSelection::All => 0x0,
+            TelegramSelection::UserData => 0x1,
+            TelegramSelection::SimpleBilling => 0x2,
+            TelegramSelection::EnhancedBilling => 0x3,
+            TelegramSelection::MultiTariffBilling => 0x4,
+            TelegramSelection::InstantaneousValues => 0x5,
+            TelegramSelection::LoadManagement => 0x6,
+            TelegramSelection::InstallationAndStartup => 0x8,
+            TelegramSelection::Testing => 0x9,
+            TelegramSelection::Calibration => 0xA,
+            TelegramSelection::Manufacturing => 0xB,
+            TelegramSelection::Development => 0xC,
+            TelegramSelection::SelfTest => 0xD,
+            TelegramSelection::Reserved(nibble) => nibble & 0x0F,
+        }
+    }
+}
+
+/// The byte following CI 0x50: which telegrams to send from now on and, in the lower nibble,
+/// the number of the telegram to start with, 0 for all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResetSubcode {
+    pub selection: TelegramSelection,
+    pub telegram: u8,
+}
+
+impl From<u8> for ResetSubcode {
+    fn from(bits: u8) -> Self {
+        Self {
+            selection: (bits >> 4).into(),
+            telegram: bits & 0x0F,
+        }
+    }
+}
+
+impl From<ResetSubcode> for u8 {
+    fn from(subcode: ResetSubcode) -> Self {
+        u8::from(subcode.selection) << 4 | subcode.telegram & 0x0F
+    }
+}
+
+/// Application reset (CI 0x50) sent by the master with SND_UD, restarting the application of
+/// the slave and optionally selecting the telegrams it answers with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ApplicationReset {
+    pub subcode: Option<ResetSubcode>,
+}
+
+impl ApplicationReset {
+    pub fn from_frame(frame: &Frame) -> Result<Self, TelegramError> {
+        match frame {
+            Frame::Control {
+                control_information: CI_APPLICATION_RESET,
+                ..
+            } => Ok(Self { subcode: None }),
+            Frame::Long {
+                control_information: CI_APPLICATION_RESET,
+                data,
+                ..
+            } => Ok(Self {
+                subcode: data.first().map(|&subcode| subcode.into()),
+            }),
+            Frame::Control {
+                control_information,
+                ..
+            }
+            | Frame::Long {
+                control_information,
+                ..
+            } => Err(TelegramError::UnsupportedControlInformation(
+                *control_information,
+            )),
+            Frame::Single | Frame::Short { .. } => Err(TelegramError::NoUserData),
+        }
+    }
+
+    /// The SND_UD sending the reset to the slave at `address`, as a control frame when there is
+    /// no subcode.
+    pub fn to_frame(&self, address: u8) -> Frame {
+        match self.subcode {
+            None => Frame::Control {
+                control: SND_UD,
+                address,
+                control_information: CI_APPLICATION_RESET,
+            },
+            Some(subcode) => Frame::Long {
+                control: SND_UD,
+                address,
+                control_information: CI_APPLICATION_RESET,
+                data: vec![subcode.into()],
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let reset = ApplicationReset {
+            subcode: Some(ResetSubcode {
+                selection: TelegramSelection::InstallationAndStartup,
+                telegram: 0,
+            }),
+        };
+        let frame = reset.to_frame(0x01);
+        assert_eq!(frame.to_hex_string(), "68 04 04 68 53 01 50 80 24 16");
+        assert_eq!(ApplicationReset::from_frame(&frame), Ok(reset));
+
+        let frame = ApplicationReset::default().to_frame(0x01);
+        assert_eq!(frame.to_hex_string(), "68 03 03 68 53 01 50 A4 16");
+        assert_eq!(
+            ApplicationReset::from_frame(&frame),
+            Ok(ApplicationReset { subcode: None })
+        );
+
+        assert_eq!(
+            ResetSubcode::from(0x72),
+            ResetSubcode {
+                selection: TelegramSelection::Reserved(0x7),
+                telegram: 2,
+            }
+        );
+        assert_eq!(u8::from(ResetSubcode::from(0x72)), 0x72);
+        assert_eq!(
+            ApplicationReset::from_frame(&Frame::req_ud2(0x01, true)),
+            Err(TelegramError::NoUserData)
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use super::{Header, ManufacturerId, Medium, TelegramError, CI_SLAVE_SELECT};
+use crate::Frame;
+use alloc::vec::Vec;
+use nom::number::complete::{le_u16, le_u32, u8};
+use nom::sequence::tuple;
+
+const WILDCARD_NIBBLE: u32 = 0xF;
+const WILDCARD_MANUFACTURER: u16 = 0xFFFF;
+const WILDCARD_BYTE: u8 = 0xFF;
+
+/// Secondary address as sent with CI 0x52 to select a slave. Each nibble of the identification
+/// number can be 0xF to match any digit, the other fields match anything when `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecondaryAddress {
+    /// Identification number as 8 BCD digits, print it with `{:08X}`.
+    pub identification: u32,
+    pub manufacturer: Option<ManufacturerId>,
+    pub version: Option<u8>,
+    pub medium: Option<Medium>,
+}
+
+impl SecondaryAddress {
+    /// Matches every slave.
+    pub const WILDCARD: Self = Self {
+        identification: u32::MAX,
+        manufacturer: None,
+        version: None,
+        medium: None,
+    };
+
+    /// Selects the slave with exactly this header.
+    pub fn from_header(header: &Header) -> Self {
+        Self {
+            identification: header.identification,
+            manufacturer: Some(header.manufacturer),
+            version: Some(header.version),
+            medium: Some(header.medium),
+        }
+    }
+
+    pub fn matches(&self, header: &Header) -> bool {
+        let identification = (0..32).step_by(4).all(|shift| {
+            let nibble = (self.identification >> shift) & 0xF;
+            nibble == WILDCARD_NIBBLE || nibble == (header.identification >> shift) & 0xF
+        });
+        identification
+            && self.manufacturer.is_none_or(|m| m == header.manufacturer)
+            && self.version.is_none_or(|v| v == header.version)
+            && self.medium.is_none_or(|m| m == header.medium)
+    }
+
+    /// Parses the user data of a frame with CI 0x52.
+    pub fn parse(data: &[u8]) -> Result<Self, TelegramError> {
+        let (_, (identification, manufacturer, version, medium)) =
+            tuple((le_u32, le_u16, u8, u8))(data)?;
+        Ok(Self {
+            identification,
+            manufacturer: (manufacturer != WILDCARD_MANUFACTURER).then(|| manufacturer.into()),
+            version: (version != WILDCARD_BYTE).then_some(version),
+            medium: (medium != WILDCARD_BYTE).then(|| medium.into()),
+        })
+    }
+
+    pub fn from_frame(frame: &Frame) -> Result<Self, TelegramError> {
+        match frame {
+            Frame::Long {
+                control_information: CI_SLAVE_SELECT,
+                data,
+                ..
+            } => Self::parse(data),
+            Frame::Control {
+                control_information,
+                ..
+            }
+            | Frame::Long {
+                control_information,
+                ..
+            } => Err(TelegramError::UnsupportedControlInformation(
+                *control_information,
+            )),
+            Frame::Single | Frame::Short { .. } => Err(TelegramError::NoUserData),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(8);
+        out.extend_from_slice(&self.identification.to_le_bytes());
+        out.extend_from_slice(
+            &self
+                .manufacturer
+                .map_or(WILDCARD_MANUFACTURER, u16::from)
+                .to_le_bytes(),
+        );
+        out.push(self.version.unwrap_or(WILDCARD_BYTE));
+        out.push(self.medium.map_or(WILDCARD_BYTE, u8::from));
+        out
+    }
+
+    /// Builds the SND_UD the master sends to select the matching slave.
+    pub fn to_frame(&self) -> Frame {
+        Frame::Long {
+            control: crate::control::SND_UD,
+            address: crate::address::NETWORK_LAYER,
+            control_information: CI_SLAVE_SELECT,
+            data: self.to_bytes(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::Status;
+
+    #[test]
+    fn test_select() {
+        let address = SecondaryAddress {
+            identification: 0x1234FFFF,
+            manufacturer: Some(ManufacturerId::from(0x4024)),
+            version: None,
+            medium: Some(Medium::Water),
+        };
+        let frame = address.to_frame();
+        assert_eq!(
+            frame.to_hex_string(),
+            "68 0B 0B 68 53 FD 52 FF FF 34 12 24 40 FF 07 50 16"
+        );
+        assert_eq!(SecondaryAddress::from_frame(&frame), Ok(address));
+
+        let mut header = Header {
+            identification: 0x12345678,
+            manufacturer: ManufacturerId::from(0x4024),
+            version: 0x01,
+            medium: Medium::Water,
+            access_number: 0x55,
+            status: Status::default(),
+            signature: 0x0000,
+        };
+        assert!(address.matches(&header));
+        assert!(SecondaryAddress::WILDCARD.matches(&header));
+        assert!(SecondaryAddress::from_header(&header).matches(&header));
+        header.identification = 0x12355678;
+        assert!(!address.matches(&header));
+    }
+}
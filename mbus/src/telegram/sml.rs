@@ -0,0 +1,82 @@
+use super::{TelegramError, CI_RESPONSE_SHORT_HEADER, CI_RESPONSE_VARIABLE};
+use crate::oms::TransportHeader;
+use crate::Frame;
+use alloc::vec::Vec;
+
+pub(super) const SML_LONG_HEADER: u8 = 0x7E;
+pub(super) const SML_SHORT_HEADER: u8 = 0x7F;
+
+/// An SML file sent by a meter over M-Bus, following CI 0x7E or 0x7F and a transport header.
+/// The file is left as is, to be handed to an SML parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmlData {
+    pub control_information: u8,
+    pub header: TransportHeader,
+    pub file: Vec<u8>,
+}
+
+impl SmlData {
+    /// Whether `control_information` introduces an SML file.
+    pub fn is_sml(control_information: u8) -> bool {
+        matches!(control_information, SML_LONG_HEADER | SML_SHORT_HEADER)
+    }
+
+    pub fn from_frame(frame: &Frame) -> Result<Self, TelegramError> {
+        match frame {
+            Frame::Control {
+                control_information,
+                ..
+            } => Err(TelegramError::UnsupportedControlInformation(
+                *control_information,
+            )),
+            Frame::Long {
+                control_information,
+                data,
+                ..
+            } => Self::parse(*control_information, data),
+            Frame::Single | Frame::Short { .. } => Err(TelegramError::NoUserData),
+        }
+    }
+
+    /// Splits the data following `control_information` into the transport header and the file.
+    pub fn parse(control_information: u8, data: &[u8]) -> Result<Self, TelegramError> {
+        let header_ci = match control_information {
+            SML_LONG_HEADER => CI_RESPONSE_VARIABLE,
+            SML_SHORT_HEADER => CI_RESPONSE_SHORT_HEADER,
+            ci => return Err(TelegramError::UnsupportedControlInformation(ci)),
+        };
+        let (header, file) = TransportHeader::parse(header_ci, data)?;
+        Ok(Self {
+            control_information,
+            header,
+            file: file.to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::ControlInformation;
+
+    #[test]
+    fn test_parse() {
+        // long header followed by the escape sequence starting an SML file
+        let mut data = [
+            0x78, 0x56, 0x34, 0x12, 0x93, 0x15, 0x01, 0x02, 0x2A, 0x00, 0x00, 0x00,
+        ]
+        .to_vec();
+        data.extend_from_slice(&[0x1B, 0x1B, 0x1B, 0x1B, 0x01, 0x01, 0x01, 0x01]);
+        let sml = SmlData::parse(SML_LONG_HEADER, &data).unwrap();
+        match sml.header {
+            TransportHeader::Long(header) => assert_eq!(header.identification, 0x12345678),
+            header => panic!("unexpected header {:?}", header),
+        }
+        assert_eq!(sml.file, &data[12..]);
+        assert!(SmlData::is_sml(0x7F));
+        assert_eq!(
+            ControlInformation::from(SML_SHORT_HEADER),
+            ControlInformation::Sml(SML_SHORT_HEADER)
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use core::fmt;
+
+/// Status byte of the variable data header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Status(u8);
+
+impl Status {
+    pub const APPLICATION_BUSY: Status = Status(0x01);
+    pub const APPLICATION_ERROR: Status = Status(0x02);
+    /// Both application bits set signal an abnormal condition or alarm.
+    pub const ABNORMAL_CONDITION: Status = Status(0x03);
+    pub const POWER_LOW: Status = Status(0x04);
+    pub const PERMANENT_ERROR: Status = Status(0x08);
+    pub const TEMPORARY_ERROR: Status = Status(0x10);
+    pub const MANUFACTURER_SPECIFIC: Status = Status(0xE0);
+
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn contains(self, other: Status) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The three manufacturer specific bits, shifted down.
+    pub const fn manufacturer_bits(self) -> u8 {
+        self.0 >> 5
+    }
+
+    /// Whether the meter reports an application error, abnormal condition or any other error.
+    pub const fn has_error(self) -> bool {
+        // the error bit is also set for abnormal conditions
+        self.0 & (Self::APPLICATION_ERROR.0 | Self::PERMANENT_ERROR.0 | Self::TEMPORARY_ERROR.0)
+            != 0
+    }
+}
+
+impl From<u8> for Status {
+    fn from(bits: u8) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<Status> for u8 {
+    fn from(status: Status) -> Self {
+        status.0
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let application = match self.0 & Self::ABNORMAL_CONDITION.0 {
+            0x01 => Some("application busy"),
+            0x02 => Some("application error"),
+            0x03 => Some("abnormal condition"),
+            _ => None,
+        };
+        let flags = [
+            application,
+            self.contains(Self::POWER_LOW).then_some("power low"),
+            self.contains(Self::PERMANENT_ERROR)
+                .then_some("permanent error"),
+            self.contains(Self::TEMPORARY_ERROR)
+                .then_some("temporary error"),
+        ];
+
+        let mut empty = true;
+        for flag in flags.into_iter().flatten() {
+            if !empty {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", flag)?;
+            empty = false;
+        }
+        if self.manufacturer_bits() != 0 {
+            if !empty {
+                write!(f, ", ")?;
+            }
+            write!(f, "manufacturer bits 0x{:X}", self.manufacturer_bits())?;
+            empty = false;
+        }
+        if empty {
+            write!(f, "ok")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_flags() {
+        let status = Status::from(0x24);
+        assert!(status.contains(Status::POWER_LOW));
+        assert!(!status.contains(Status::APPLICATION_BUSY));
+        assert_eq!(status.manufacturer_bits(), 1);
+        assert!(!status.has_error());
+        assert_eq!(status.to_string(), "power low, manufacturer bits 0x1");
+
+        assert!(!Status::from(0x01).has_error());
+        assert!(Status::from(0x02).has_error());
+        assert!(Status::from(0x03).contains(Status::ABNORMAL_CONDITION));
+        assert!(Status::from(0x10).has_error());
+        assert_eq!(
+            Status::from(0x0B).to_string(),
+            "abnormal condition, permanent error"
+        );
+        assert_eq!(Status::default().to_string(), "ok");
+    }
+}
@@ -0,0 +1,143 @@
+use super::{DataRecord, Quantity, Unit, ValueError};
+use uom::si::f64::{
+    ElectricCurrent, ElectricPotential, Energy, Frequency, Mass, MassRate, Power, Pressure,
+    TemperatureInterval, ThermodynamicTemperature, Time, Volume, VolumeRate,
+};
+use uom::si::{
+    electric_current, electric_potential, energy, frequency, mass, mass_rate, power, pressure,
+    temperature_interval, thermodynamic_temperature, time, volume, volume_rate,
+};
+
+/// Value of a record as a [uom] quantity, enabled with the `uom` feature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypedValue {
+    Energy(Energy),
+    Volume(Volume),
+    Mass(Mass),
+    Time(Time),
+    Power(Power),
+    VolumeRate(VolumeRate),
+    MassRate(MassRate),
+    Temperature(ThermodynamicTemperature),
+    TemperatureInterval(TemperatureInterval),
+    Pressure(Pressure),
+    ElectricPotential(ElectricPotential),
+    ElectricCurrent(ElectricCurrent),
+    Frequency(Frequency),
+}
+
+impl DataRecord {
+    /// The normalized value as a quantity. Gives `Ok(None)` where [`DataRecord::normalized`]
+    /// does, and for units without a physical dimension (currency, baud) or with a calendar
+    /// length (months, years), for reactive energy and power and for values per another unit.
+    pub fn typed_value(&self) -> Result<Option<TypedValue>, ValueError> {
+        let value_information = self.value_information();
+        if value_information.per.is_some() {
+            return Ok(None);
+        }
+        Ok(self
+            .normalized()?
+            .and_then(|(value, unit)| typed_value(value_information.quantity, value, unit)))
+    }
+}
+
+fn typed_value(quantity: Quantity, value: f64, unit: Unit) -> Option<TypedValue> {
+    let interval = quantity == Quantity::TemperatureDifference;
+    let typed = match unit {
+        Unit::WattHour => TypedValue::Energy(Energy::new::<energy::watt_hour>(value)),
+        Unit::Joule => TypedValue::Energy(Energy::new::<energy::joule>(value)),
+        Unit::CubicMetre => TypedValue::Volume(Volume::new::<volume::cubic_meter>(value)),
+        Unit::CubicFoot => TypedValue::Volume(Volume::new::<volume::cubic_foot>(value)),
+        Unit::UsGallon => TypedValue::Volume(Volume::new::<volume::gallon>(value)),
+        Unit::Kilogram => TypedValue::Mass(Mass::new::<mass::kilogram>(value)),
+        Unit::Second => TypedValue::Time(Time::new::<time::second>(value)),
+        Unit::Minute => TypedValue::Time(Time::new::<time::minute>(value)),
+        Unit::Hour => TypedValue::Time(Time::new::<time::hour>(value)),
+        Unit::Day => TypedValue::Time(Time::new::<time::day>(value)),
+        Unit::Watt => TypedValue::Power(Power::new::<power::watt>(value)),
+        Unit::JoulePerHour => TypedValue::Power(Power::new::<power::watt>(value / 3600.0)),
+        Unit::CubicMetrePerHour => {
+            TypedValue::VolumeRate(VolumeRate::new::<volume_rate::cubic_meter_per_hour>(value))
+        }
+        Unit::CubicMetrePerMinute => TypedValue::VolumeRate(VolumeRate::new::<
+            volume_rate::cubic_meter_per_minute,
+        >(value)),
+        Unit::CubicMetrePerSecond => TypedValue::VolumeRate(VolumeRate::new::<
+            volume_rate::cubic_meter_per_second,
+        >(value)),
+        Unit::UsGallonPerMinute => {
+            TypedValue::VolumeRate(VolumeRate::new::<volume_rate::gallon_per_minute>(value))
+        }
+        Unit::UsGallonPerHour => TypedValue::VolumeRate(VolumeRate::new::<
+            volume_rate::gallon_per_minute,
+        >(value / 60.0)),
+        Unit::KilogramPerHour => {
+            TypedValue::MassRate(MassRate::new::<mass_rate::kilogram_per_hour>(value))
+        }
+        Unit::DegreeCelsius if interval => {
+            TypedValue::TemperatureInterval(TemperatureInterval::new::<
+                temperature_interval::degree_celsius,
+            >(value))
+        }
+        Unit::DegreeFahrenheit if interval => {
+            TypedValue::TemperatureInterval(TemperatureInterval::new::<
+                temperature_interval::degree_fahrenheit,
+            >(value))
+        }
+        Unit::Kelvin if interval => TypedValue::TemperatureInterval(TemperatureInterval::new::<
+            temperature_interval::kelvin,
+        >(value)),
+        Unit::DegreeCelsius => TypedValue::Temperature(ThermodynamicTemperature::new::<
+            thermodynamic_temperature::degree_celsius,
+        >(value)),
+        Unit::DegreeFahrenheit => TypedValue::Temperature(ThermodynamicTemperature::new::<
+            thermodynamic_temperature::degree_fahrenheit,
+        >(value)),
+        Unit::Kelvin => TypedValue::Temperature(ThermodynamicTemperature::new::<
+            thermodynamic_temperature::kelvin,
+        >(value)),
+        Unit::Bar => TypedValue::Pressure(Pressure::new::<pressure::bar>(value)),
+        Unit::Volt => {
+            TypedValue::ElectricPotential(ElectricPotential::new::<electric_potential::volt>(value))
+        }
+        Unit::Ampere => {
+            TypedValue::ElectricCurrent(ElectricCurrent::new::<electric_current::ampere>(value))
+        }
+        Unit::Hertz => TypedValue::Frequency(Frequency::new::<frequency::hertz>(value)),
+        Unit::None
+        | Unit::Month
+        | Unit::Year
+        | Unit::Currency
+        | Unit::Baud
+        | Unit::BitTimes
+        | Unit::VarHour
+        | Unit::Var => return None,
+    };
+    Some(typed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::record::data_record;
+
+    #[test]
+    fn test_typed_value() {
+        // 12345 * 10^-3 m³
+        let (_, record) = data_record(&[0x0C, 0x13, 0x45, 0x23, 0x01, 0x00]).unwrap();
+        let Ok(Some(TypedValue::Volume(volume))) = record.typed_value() else {
+            panic!("expected a volume");
+        };
+        assert!((volume.get::<volume::liter>() - 12345.0).abs() < 1e-6);
+
+        // 0x0123 * 10^-2 K
+        let (_, record) = data_record(&[0x02, 0x61, 0x23, 0x01]).unwrap();
+        let Ok(Some(TypedValue::TemperatureInterval(interval))) = record.typed_value() else {
+            panic!("expected a temperature interval");
+        };
+        assert!((interval.get::<temperature_interval::kelvin>() - 2.91).abs() < 1e-9);
+
+        let (_, record) = data_record(&[0x02, 0x6C, 0x13, 0x1B]).unwrap();
+        assert_eq!(record.typed_value(), Ok(None));
+    }
+}
@@ -0,0 +1,100 @@
+use super::{
+    AlarmStatus, ApplicationError, ControlInformation, DlmsData, FixedDataStructure, SmlData,
+    Telegram, TelegramError,
+};
+use crate::Frame;
+use alloc::vec::Vec;
+
+/// The user data of a frame, decoded according to its CI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserData {
+    /// CI 0x72 or 0x76.
+    VariableData(Telegram),
+    /// CI 0x73 or 0x77.
+    FixedData(FixedDataStructure),
+    /// CI 0x70.
+    ApplicationError(ApplicationError),
+    /// CI 0x71.
+    AlarmStatus(AlarmStatus),
+    /// CI 0x60, 0x61, 0x64 or 0x65.
+    Dlms(DlmsData),
+    /// CI 0x7E or 0x7F.
+    Sml(SmlData),
+    /// User data of any other CI, left undecoded.
+    Raw(Vec<u8>),
+}
+
+impl UserData {
+    /// Decodes the user data of a control or long frame, dispatching on its CI.
+    pub fn from_frame(frame: &Frame) -> Result<Self, TelegramError> {
+        let control_information = frame
+            .control_information()
+            .ok_or(TelegramError::NoUserData)?;
+        let user_data = match ControlInformation::from(control_information) {
+            ControlInformation::ResponseVariable | ControlInformation::ResponseVariableMsbFirst => {
+                UserData::VariableData(Telegram::from_frame(frame)?)
+            }
+            ControlInformation::ResponseFixed | ControlInformation::ResponseFixedMsbFirst => {
+                UserData::FixedData(FixedDataStructure::from_frame(frame)?)
+            }
+            ControlInformation::ApplicationError => {
+                UserData::ApplicationError(ApplicationError::from_frame(frame)?)
+            }
+            ControlInformation::AlarmStatus => {
+                UserData::AlarmStatus(AlarmStatus::from_frame(frame)?)
+            }
+            ControlInformation::Dlms(_) => UserData::Dlms(DlmsData::from_frame(frame)?),
+            ControlInformation::Sml(_) => UserData::Sml(SmlData::from_frame(frame)?),
+            _ => UserData::Raw(frame.payload().unwrap_or_default().to_vec()),
+        };
+        Ok(user_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::RSP_UD;
+    use alloc::vec;
+
+    #[test]
+    fn test_from_frame() {
+        let frame = Frame::rsp_ud(0x01, 0x70, [0x08]);
+        assert_eq!(
+            frame.user_data(),
+            Ok(UserData::ApplicationError(
+                ApplicationError::ApplicationBusy
+            ))
+        );
+
+        let frame = Frame::rsp_ud(
+            0x01,
+            0x72,
+            [
+                0x78, 0x56, 0x34, 0x12, 0x24, 0x40, 0x01, 0x07, 0x55, 0x00, 0x00, 0x00, 0x01, 0x13,
+                0x05,
+            ],
+        );
+        let Ok(UserData::VariableData(telegram)) = frame.user_data() else {
+            panic!("no variable data");
+        };
+        assert_eq!(telegram.records.len(), 1);
+
+        let frame = Frame::rsp_ud(0x01, 0x72, [0x78, 0x56]);
+        assert_eq!(frame.user_data(), Err(TelegramError::UnexpectedEnd));
+
+        let frame = Frame::rsp_ud(0x01, 0xA0, [0x01, 0x02]);
+        assert_eq!(frame.user_data(), Ok(UserData::Raw(vec![0x01, 0x02])));
+
+        let frame = Frame::Control {
+            control: RSP_UD,
+            address: 0x01,
+            control_information: 0xA0,
+        };
+        assert_eq!(frame.user_data(), Ok(UserData::Raw(vec![])));
+        assert_eq!(
+            Frame::snd_nke(0x01).user_data(),
+            Err(TelegramError::NoUserData)
+        );
+    }
+}
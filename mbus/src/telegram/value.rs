@@ -0,0 +1,328 @@
+use super::date::{date_g, date_time_f, date_time_i, time_j};
+use super::record::lvar_length;
+use super::{DataField, DataRecord, Date, DateTime, Quantity, Time, Unit};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+/// Decoded data of a record.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Value {
+    None,
+    I64(i64),
+    F64(f64),
+    Bcd(i64),
+    Date(Date),
+    Time(Time),
+    DateTime(DateTime),
+    Text(String),
+    Binary(#[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))] Vec<u8>),
+}
+
+impl Value {
+    /// The value as a number, `None` for values that are not numeric like dates and text.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::I64(value) | Value::Bcd(value) => Some(*value as f64),
+            Value::F64(value) => Some(*value),
+            Value::None
+            | Value::Date(_)
+            | Value::Time(_)
+            | Value::DateTime(_)
+            | Value::Text(_)
+            | Value::Binary(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueError {
+    UnsupportedDataField(DataField),
+    InvalidLength,
+    InvalidBcd,
+    /// The meter marked the value as erroneous by filling it with 0xE digits.
+    ErrorMarker,
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueError::UnsupportedDataField(data_field) => {
+                write!(f, "unsupported data field {:?}", data_field)
+            }
+            ValueError::InvalidLength => write!(f, "data length does not match its data field"),
+            ValueError::InvalidBcd => write!(f, "invalid BCD digits"),
+            ValueError::ErrorMarker => write!(f, "value is marked as erroneous"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValueError {}
+
+impl DataRecord {
+    pub fn value(&self) -> Result<Value, ValueError> {
+        let data_field = self.header.data_field;
+        if data_field == DataField::VariableLength {
+            return lvar_value(&self.data);
+        }
+        if data_field.length() != Some(self.data.len()) {
+            return match data_field.length() {
+                Some(_) => Err(ValueError::InvalidLength),
+                None => Err(ValueError::UnsupportedDataField(data_field)),
+            };
+        }
+
+        let is_date = matches!(
+            self.value_information().quantity,
+            Quantity::Date
+                | Quantity::DateTime
+                | Quantity::TariffStart
+                | Quantity::BatteryChangeDateTime
+        );
+        if is_date {
+            if let Some(value) = date_value(&self.data) {
+                return Ok(value);
+            }
+        }
+
+        match data_field {
+            DataField::None | DataField::SelectionForReadout => Ok(Value::None),
+            DataField::Int8
+            | DataField::Int16
+            | DataField::Int24
+            | DataField::Int32
+            | DataField::Int48
+            | DataField::Int64 => Ok(Value::I64(int(&self.data))),
+            DataField::Real32 => {
+                let bytes = self.data[..]
+                    .try_into()
+                    .map_err(|_| ValueError::InvalidLength)?;
+                Ok(Value::F64(f32::from_le_bytes(bytes).into()))
+            }
+            DataField::Bcd2
+            | DataField::Bcd4
+            | DataField::Bcd6
+            | DataField::Bcd8
+            | DataField::Bcd12 => bcd(&self.data).map(Value::Bcd),
+            _ => Err(ValueError::UnsupportedDataField(data_field)),
+        }
+    }
+}
+
+impl DataRecord {
+    /// The value in `unit`, with the decimal exponent and correction constant of the VIF
+    /// applied. Values that are not numeric, like dates and text, give `Ok(None)`; use
+    /// [`DataRecord::value`] for those.
+    pub fn normalized(&self) -> Result<Option<(f64, Unit)>, ValueError> {
+        let value_information = self.value_information();
+        Ok(self.value()?.as_f64().map(|value| {
+            let offset = value_information
+                .offset
+                .map_or(0.0, |offset| scale(1.0, offset));
+            (
+                scale(value, value_information.exponent) + offset,
+                value_information.unit,
+            )
+        }))
+    }
+}
+
+impl DataRecord {
+    /// The value as a duration, for records in seconds, minutes, hours or days like the
+    /// averaging and actuality durations. Gives `Ok(None)` for other units, for months and
+    /// years, which have no fixed length, and for negative values.
+    pub fn duration(&self) -> Result<Option<Duration>, ValueError> {
+        let Some((value, unit)) = self.normalized()? else {
+            return Ok(None);
+        };
+        let seconds = match unit {
+            Unit::Second => value,
+            Unit::Minute => value * 60.0,
+            Unit::Hour => value * 3600.0,
+            Unit::Day => value * 86400.0,
+            _ => return Ok(None),
+        };
+        Ok(Duration::try_from_secs_f64(seconds).ok())
+    }
+}
+
+/// Multiplies by `10^exponent`, dividing for negative exponents so that e.g. 0.001 is not
+/// rounded into the result.
+fn scale(value: f64, exponent: i8) -> f64 {
+    let factor = (0..exponent.unsigned_abs()).fold(1.0, |factor, _| factor * 10.0);
+    if exponent < 0 {
+        value / factor
+    } else {
+        value * factor
+    }
+}
+
+fn lvar_value(data: &[u8]) -> Result<Value, ValueError> {
+    let (&lvar, data) = data.split_first().ok_or(ValueError::InvalidLength)?;
+    if lvar_length(lvar) != Some(data.len()) {
+        return Err(ValueError::InvalidLength);
+    }
+
+    match lvar {
+        // like plain-text units, the text is transmitted last character first
+        0x00..=0xBF => Ok(Value::Text(
+            data.iter().rev().copied().map(char::from).collect(),
+        )),
+        0xC0..=0xC9 => bcd(data).map(Value::Bcd),
+        0xD0..=0xD9 => bcd(data).map(|value| Value::Bcd(-value)),
+        _ => Ok(Value::Binary(data.to_vec())),
+    }
+}
+
+/// Picks the date/time type from the size of the data.
+fn date_value(data: &[u8]) -> Option<Value> {
+    if let Ok(data) = data.try_into() {
+        Some(Value::Date(date_g(data)))
+    } else if let Ok(data) = data.try_into() {
+        Some(Value::Time(time_j(data)))
+    } else if let Ok(data) = data.try_into() {
+        Some(Value::DateTime(date_time_f(data)))
+    } else if let Ok(data) = data.try_into() {
+        Some(Value::DateTime(date_time_i(data)))
+    } else {
+        None
+    }
+}
+
+/// Decodes a little-endian two's complement integer.
+fn int(data: &[u8]) -> i64 {
+    let value = data
+        .iter()
+        .rev()
+        .fold(0i64, |value, b| value << 8 | i64::from(*b));
+    // sign extend
+    let unused = 64 - 8 * data.len() as u32;
+    value << unused >> unused
+}
+
+/// Decodes little-endian BCD digits. An 0xF in the most significant digit makes the value
+/// negative.
+fn bcd(data: &[u8]) -> Result<i64, ValueError> {
+    if !data.is_empty() && data.iter().all(|b| *b == 0xEE) {
+        return Err(ValueError::ErrorMarker);
+    }
+
+    let mut value = 0i64;
+    let mut negative = false;
+    for (index, b) in data.iter().rev().enumerate() {
+        for (position, digit) in [b >> 4, b & 0x0F].into_iter().enumerate() {
+            match digit {
+                0..=9 => value = value * 10 + i64::from(digit),
+                0xF if index == 0 && position == 0 => negative = true,
+                _ => return Err(ValueError::InvalidBcd),
+            }
+        }
+    }
+
+    Ok(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::record::data_record;
+    use alloc::vec;
+
+    #[test]
+    fn test_bcd() {
+        assert_eq!(bcd(&[0x15, 0x31, 0x00]), Ok(3115));
+        assert_eq!(bcd(&[0x78, 0x56, 0x34, 0x12, 0x00, 0x99]), Ok(990012345678));
+        assert_eq!(bcd(&[0x21, 0xF3]), Ok(-321));
+        assert_eq!(bcd(&[0xEE, 0xEE]), Err(ValueError::ErrorMarker));
+        assert_eq!(bcd(&[0x1A]), Err(ValueError::InvalidBcd));
+        assert_eq!(bcd(&[0xF1, 0x00]), Err(ValueError::InvalidBcd));
+    }
+
+    #[test]
+    fn test_date_record() {
+        let (_, record) = data_record(&[0x04, 0x6D, 0x2D, 0x8D, 0xBB, 0x23]).unwrap();
+        assert!(
+            matches!(record.value(), Ok(Value::DateTime(date_time)) if date_time.date.year == 2021)
+        );
+
+        let (_, record) = data_record(&[0x02, 0x6C, 0x13, 0x1B]).unwrap();
+        assert!(matches!(record.value(), Ok(Value::Date(date)) if date.month == 11));
+    }
+
+    #[test]
+    fn test_lvar_record() {
+        let (rest, record) =
+            data_record(&[0x0D, 0xFD, 0x11, 0x03, b'C', b'B', b'A', 0x01]).unwrap();
+        assert_eq!(rest, &[0x01]);
+        assert_eq!(record.value(), Ok(Value::Text("ABC".into())));
+
+        let (_, record) = data_record(&[0x0D, 0x13, 0xD2, 0x45, 0x23]).unwrap();
+        assert_eq!(record.value(), Ok(Value::Bcd(-2345)));
+
+        let (_, record) = data_record(&[0x0D, 0x13, 0xE2, 0xCA, 0xFE]).unwrap();
+        assert_eq!(record.value(), Ok(Value::Binary(vec![0xCA, 0xFE])));
+
+        assert_eq!(
+            data_record(&[0x0D, 0x13, 0xFA]),
+            Err(nom::Err::Failure(
+                crate::telegram::TelegramError::InvalidLvar(0xFA)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_int() {
+        assert_eq!(int(&[0x15, 0x31, 0x00]), 0x3115);
+        assert_eq!(int(&[0xFF]), -1);
+        assert_eq!(int(&[0x00, 0x80]), -32768);
+        assert_eq!(int(&[0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]), -2);
+        assert_eq!(int(&[0x01, 0, 0, 0, 0, 0, 0, 0x01]), 0x0100000000000001);
+    }
+
+    #[test]
+    fn test_normalized() {
+        // 12345 * 10^-3 m³
+        let (_, record) = data_record(&[0x0C, 0x13, 0x45, 0x23, 0x01, 0x00]).unwrap();
+        assert_eq!(record.normalized(), Ok(Some((12.345, Unit::CubicMetre))));
+
+        // 3115 * 10^1 Wh
+        let (_, record) = data_record(&[0x0B, 0x04, 0x15, 0x31, 0x00]).unwrap();
+        assert_eq!(record.normalized(), Ok(Some((31150.0, Unit::WattHour))));
+
+        // 3115 * 10^1 * 10^-1 Wh, plus 10^0 * 10^1 Wh
+        let (_, record) = data_record(&[0x0B, 0x84, 0xF5, 0x7B, 0x15, 0x31, 0x00]).unwrap();
+        assert_eq!(record.normalized(), Ok(Some((3125.0, Unit::WattHour))));
+
+        let (_, record) = data_record(&[0x02, 0x6C, 0x13, 0x1B]).unwrap();
+        assert_eq!(record.normalized(), Ok(None));
+
+        let (_, record) = data_record(&[0x0C, 0x13, 0xEE, 0xEE, 0xEE, 0xEE]).unwrap();
+        assert_eq!(record.normalized(), Err(ValueError::ErrorMarker));
+    }
+
+    #[test]
+    fn test_duration() {
+        // averaging duration of 15 minutes
+        let (_, record) = data_record(&[0x01, 0x71, 0x0F]).unwrap();
+        assert_eq!(record.duration(), Ok(Some(Duration::from_secs(900))));
+
+        // actuality duration of 3 days
+        let (_, record) = data_record(&[0x01, 0x77, 0x03]).unwrap();
+        assert_eq!(record.duration(), Ok(Some(Duration::from_secs(259200))));
+
+        // volume, and storage interval in months
+        let (_, record) = data_record(&[0x01, 0x13, 0x03]).unwrap();
+        assert_eq!(record.duration(), Ok(None));
+        let (_, record) = data_record(&[0x01, 0xFD, 0x28, 0x03]).unwrap();
+        assert_eq!(record.duration(), Ok(None));
+    }
+
+    #[test]
+    fn test_real_record() {
+        let (_, record) = data_record(&[0x05, 0x2B, 0x00, 0x00, 0x28, 0x42]).unwrap();
+        assert_eq!(record.value(), Ok(Value::F64(42.0)));
+    }
+}
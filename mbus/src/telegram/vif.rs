@@ -0,0 +1,612 @@
+const EXTENSION: u8 = 0x80;
+const VIF_EXTENSION_FB: u8 = 0xFB;
+const VIF_EXTENSION_FD: u8 = 0xFD;
+const VIF_MANUFACTURER_SPECIFIC: u8 = 0x7F;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Quantity {
+    Energy,
+    Volume,
+    Mass,
+    OnTime,
+    OperatingTime,
+    Power,
+    VolumeFlow,
+    MassFlow,
+    FlowTemperature,
+    ReturnTemperature,
+    TemperatureDifference,
+    ExternalTemperature,
+    Pressure,
+    Date,
+    DateTime,
+    HeatCostAllocation,
+    AveragingDuration,
+    ActualityDuration,
+    FabricationNumber,
+    EnhancedIdentification,
+    BusAddress,
+    PlainText,
+    AnyVif,
+    ManufacturerSpecific,
+    Credit,
+    Debit,
+    AccessNumber,
+    Medium,
+    Manufacturer,
+    ParameterSetIdentification,
+    ModelVersion,
+    HardwareVersion,
+    FirmwareVersion,
+    SoftwareVersion,
+    CustomerLocation,
+    Customer,
+    AccessCodeUser,
+    AccessCodeOperator,
+    AccessCodeSystemOperator,
+    AccessCodeDeveloper,
+    Password,
+    ErrorFlags,
+    ErrorMask,
+    DigitalOutput,
+    DigitalInput,
+    Baudrate,
+    ResponseDelayTime,
+    Retry,
+    FirstStorageNumber,
+    LastStorageNumber,
+    StorageBlockSize,
+    StorageInterval,
+    DurationSinceLastReadout,
+    TariffStart,
+    TariffDuration,
+    TariffPeriod,
+    Dimensionless,
+    Voltage,
+    Current,
+    ResetCounter,
+    CumulationCounter,
+    ControlSignal,
+    DayOfWeek,
+    WeekNumber,
+    DayChangeTimePoint,
+    ParameterActivationState,
+    SpecialSupplierInformation,
+    DurationSinceLastCumulation,
+    BatteryOperatingTime,
+    BatteryChangeDateTime,
+    ReactiveEnergy,
+    ReactivePower,
+    Frequency,
+    TemperatureLimit,
+    CumulativeMaximumPower,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unit {
+    None,
+    WattHour,
+    Joule,
+    CubicMetre,
+    Kilogram,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+    Watt,
+    JoulePerHour,
+    CubicMetrePerHour,
+    CubicMetrePerMinute,
+    CubicMetrePerSecond,
+    KilogramPerHour,
+    DegreeCelsius,
+    DegreeFahrenheit,
+    Kelvin,
+    Bar,
+    /// Local legal currency units.
+    Currency,
+    Volt,
+    Ampere,
+    Baud,
+    BitTimes,
+    VarHour,
+    Var,
+    Hertz,
+    CubicFoot,
+    UsGallon,
+    UsGallonPerMinute,
+    UsGallonPerHour,
+}
+
+impl Unit {
+    /// Symbol of the unit, empty for [`Unit::None`].
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Unit::None => "",
+            Unit::WattHour => "Wh",
+            Unit::Joule => "J",
+            Unit::CubicMetre => "m³",
+            Unit::Kilogram => "kg",
+            Unit::Second => "s",
+            Unit::Minute => "min",
+            Unit::Hour => "h",
+            Unit::Day => "d",
+            Unit::Month => "months",
+            Unit::Year => "years",
+            Unit::Watt => "W",
+            Unit::JoulePerHour => "J/h",
+            Unit::CubicMetrePerHour => "m³/h",
+            Unit::CubicMetrePerMinute => "m³/min",
+            Unit::CubicMetrePerSecond => "m³/s",
+            Unit::KilogramPerHour => "kg/h",
+            Unit::DegreeCelsius => "°C",
+            Unit::DegreeFahrenheit => "°F",
+            Unit::Kelvin => "K",
+            Unit::Bar => "bar",
+            Unit::Currency => "currency units",
+            Unit::Volt => "V",
+            Unit::Ampere => "A",
+            Unit::Baud => "Bd",
+            Unit::BitTimes => "bit times",
+            Unit::VarHour => "varh",
+            Unit::Var => "var",
+            Unit::Hertz => "Hz",
+            Unit::CubicFoot => "ft³",
+            Unit::UsGallon => "US gal",
+            Unit::UsGallonPerMinute => "US gal/min",
+            Unit::UsGallonPerHour => "US gal/h",
+        }
+    }
+}
+
+/// Unit a value is given per, from a combinable VIFE: energy per hour, volume per pulse...
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+    Revolution,
+    /// Increment per pulse on input channel 0 or 1.
+    InputPulse(u8),
+    /// Increment per pulse on output channel 0 or 1.
+    OutputPulse(u8),
+    Litre,
+    CubicMetre,
+    Kilogram,
+    Kelvin,
+    KilowattHour,
+    Gigajoule,
+    Kilowatt,
+    KelvinLitre,
+    Volt,
+    Ampere,
+}
+
+impl PerUnit {
+    const fn from_code(code: u8) -> Option<Self> {
+        let per = match code {
+            0x20 => PerUnit::Second,
+            0x21 => PerUnit::Minute,
+            0x22 => PerUnit::Hour,
+            0x23 => PerUnit::Day,
+            0x24 => PerUnit::Week,
+            0x25 => PerUnit::Month,
+            0x26 => PerUnit::Year,
+            0x27 => PerUnit::Revolution,
+            0x28..=0x29 => PerUnit::InputPulse(code & 0x01),
+            0x2A..=0x2B => PerUnit::OutputPulse(code & 0x01),
+            0x2C => PerUnit::Litre,
+            0x2D => PerUnit::CubicMetre,
+            0x2E => PerUnit::Kilogram,
+            0x2F => PerUnit::Kelvin,
+            0x30 => PerUnit::KilowattHour,
+            0x31 => PerUnit::Gigajoule,
+            0x32 => PerUnit::Kilowatt,
+            0x33 => PerUnit::KelvinLitre,
+            0x34 => PerUnit::Volt,
+            0x35 => PerUnit::Ampere,
+            _ => return None,
+        };
+        Some(per)
+    }
+
+    /// Symbol of the unit, e.g. `h` for [`PerUnit::Hour`].
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            PerUnit::Second => "s",
+            PerUnit::Minute => "min",
+            PerUnit::Hour => "h",
+            PerUnit::Day => "d",
+            PerUnit::Week => "week",
+            PerUnit::Month => "month",
+            PerUnit::Year => "year",
+            PerUnit::Revolution => "revolution",
+            PerUnit::InputPulse(_) => "input pulse",
+            PerUnit::OutputPulse(_) => "output pulse",
+            PerUnit::Litre => "l",
+            PerUnit::CubicMetre => "m³",
+            PerUnit::Kilogram => "kg",
+            PerUnit::Kelvin => "K",
+            PerUnit::KilowattHour => "kWh",
+            PerUnit::Gigajoule => "GJ",
+            PerUnit::Kilowatt => "kW",
+            PerUnit::KelvinLitre => "K·l",
+            PerUnit::Volt => "V",
+            PerUnit::Ampere => "A",
+        }
+    }
+}
+
+/// Contributions accumulated by a record, from a combinable VIFE. Meters use it to tell the
+/// volume or energy that flowed forward from what flowed backward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowDirection {
+    /// Only positive contributions are accumulated.
+    Forward,
+    /// Only negative contributions are accumulated, as an absolute value.
+    Backward,
+}
+
+/// What a record measures, decoded from its VIF and combinable VIFEs: the value is to be
+/// multiplied by `10^exponent`, and `10^offset` added to it, to get an amount of `unit`, or
+/// of `unit` per `per`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueInformation {
+    pub quantity: Quantity,
+    pub unit: Unit,
+    /// Exponent of the VIF, including any multiplicative correction factor.
+    pub exponent: i8,
+    /// Additive correction constant, as a power of ten in `unit`.
+    pub offset: Option<i8>,
+    pub per: Option<PerUnit>,
+    pub direction: Option<FlowDirection>,
+}
+
+impl ValueInformation {
+    const fn new(quantity: Quantity, unit: Unit, exponent: i8) -> Self {
+        Self {
+            quantity,
+            unit,
+            exponent,
+            offset: None,
+            per: None,
+            direction: None,
+        }
+    }
+
+    const fn without_unit(quantity: Quantity) -> Self {
+        Self::new(quantity, Unit::None, 0)
+    }
+
+    /// Decodes the VIF according to the tables of EN 13757-3. The VIFs 0xFB and 0xFD select an
+    /// extension table, in which case the first VIFE holds the actual code. The VIFEs after
+    /// that are combinable (orthogonal) VIFEs modifying the meaning of the VIF.
+    pub fn decode(vif: u8, vife: &[u8]) -> Self {
+        let (information, combinable) = match (vif, vife.split_first()) {
+            (VIF_EXTENSION_FD, Some((code, rest))) => (Self::decode_fd(*code), rest),
+            (VIF_EXTENSION_FB, Some((code, rest))) => (Self::decode_fb(*code), rest),
+            (VIF_EXTENSION_FD, None) | (VIF_EXTENSION_FB, None) => {
+                return Self::without_unit(Quantity::Unknown)
+            }
+            // the VIFEs of manufacturer specific VIFs are manufacturer specific too
+            _ if vif & !EXTENSION == VIF_MANUFACTURER_SPECIFIC => return Self::decode_primary(vif),
+            _ => (Self::decode_primary(vif), vife),
+        };
+
+        let base = information.exponent;
+        let mut information = information;
+        for code in combinable {
+            match code & !EXTENSION {
+                per @ 0x20..=0x35 => information.per = PerUnit::from_code(per),
+                0x3B => information.direction = Some(FlowDirection::Forward),
+                0x3C => information.direction = Some(FlowDirection::Backward),
+                // multiplicative correction factor 10^(nnn-6)
+                0x70..=0x77 => {
+                    let nnn = (code & 0x07) as i8;
+                    information.exponent = information.exponent.saturating_add(nnn - 6);
+                }
+                // additive correction constant 10^(nn-3) in the unit of the VIF
+                0x78..=0x7B => {
+                    let nn = (code & 0x03) as i8;
+                    information.offset = Some(base.saturating_add(nn - 3));
+                }
+                // multiplicative correction factor 10^3 for the value
+                0x7D => information.exponent = information.exponent.saturating_add(3),
+                // the remaining VIFEs are manufacturer specific
+                VIF_MANUFACTURER_SPECIFIC => break,
+                _ => {}
+            }
+        }
+        information
+    }
+
+    fn decode_primary(vif: u8) -> Self {
+        let n = (vif & 0x07) as i8;
+        let nn = (vif & 0x03) as i8;
+        match vif & !EXTENSION {
+            0x00..=0x07 => Self::new(Quantity::Energy, Unit::WattHour, n - 3),
+            0x08..=0x0F => Self::new(Quantity::Energy, Unit::Joule, n),
+            0x10..=0x17 => Self::new(Quantity::Volume, Unit::CubicMetre, n - 6),
+            0x18..=0x1F => Self::new(Quantity::Mass, Unit::Kilogram, n - 3),
+            0x20..=0x23 => Self::new(Quantity::OnTime, time_unit(vif), 0),
+            0x24..=0x27 => Self::new(Quantity::OperatingTime, time_unit(vif), 0),
+            0x28..=0x2F => Self::new(Quantity::Power, Unit::Watt, n - 3),
+            0x30..=0x37 => Self::new(Quantity::Power, Unit::JoulePerHour, n),
+            0x38..=0x3F => Self::new(Quantity::VolumeFlow, Unit::CubicMetrePerHour, n - 6),
+            0x40..=0x47 => Self::new(Quantity::VolumeFlow, Unit::CubicMetrePerMinute, n - 7),
+            0x48..=0x4F => Self::new(Quantity::VolumeFlow, Unit::CubicMetrePerSecond, n - 9),
+            0x50..=0x57 => Self::new(Quantity::MassFlow, Unit::KilogramPerHour, n - 3),
+            0x58..=0x5B => Self::new(Quantity::FlowTemperature, Unit::DegreeCelsius, nn - 3),
+            0x5C..=0x5F => Self::new(Quantity::ReturnTemperature, Unit::DegreeCelsius, nn - 3),
+            0x60..=0x63 => Self::new(Quantity::TemperatureDifference, Unit::Kelvin, nn - 3),
+            0x64..=0x67 => Self::new(Quantity::ExternalTemperature, Unit::DegreeCelsius, nn - 3),
+            0x68..=0x6B => Self::new(Quantity::Pressure, Unit::Bar, nn - 3),
+            0x6C => Self::without_unit(Quantity::Date),
+            0x6D => Self::without_unit(Quantity::DateTime),
+            0x6E => Self::without_unit(Quantity::HeatCostAllocation),
+            0x70..=0x73 => Self::new(Quantity::AveragingDuration, time_unit(vif), 0),
+            0x74..=0x77 => Self::new(Quantity::ActualityDuration, time_unit(vif), 0),
+            0x78 => Self::without_unit(Quantity::FabricationNumber),
+            0x79 => Self::without_unit(Quantity::EnhancedIdentification),
+            0x7A => Self::without_unit(Quantity::BusAddress),
+            0x7C => Self::without_unit(Quantity::PlainText),
+            0x7E => Self::without_unit(Quantity::AnyVif),
+            0x7F => Self::without_unit(Quantity::ManufacturerSpecific),
+            _ => Self::without_unit(Quantity::Unknown),
+        }
+    }
+
+    /// Alternate units are scaled to the units of the primary table, e.g. MWh to Wh.
+    fn decode_fb(code: u8) -> Self {
+        let n = (code & 0x01) as i8;
+        let nn = (code & 0x03) as i8;
+        let nnn = (code & 0x07) as i8;
+        match code & !EXTENSION {
+            0x00..=0x01 => Self::new(Quantity::Energy, Unit::WattHour, n - 1 + 6),
+            0x02..=0x03 => Self::new(Quantity::ReactiveEnergy, Unit::VarHour, n + 3),
+            0x08..=0x09 => Self::new(Quantity::Energy, Unit::Joule, n - 1 + 9),
+            0x10..=0x11 => Self::new(Quantity::Volume, Unit::CubicMetre, n + 2),
+            0x14..=0x17 => Self::new(Quantity::ReactivePower, Unit::Var, nn - 3 + 3),
+            0x18..=0x19 => Self::new(Quantity::Mass, Unit::Kilogram, n + 2 + 3),
+            0x21 => Self::new(Quantity::Volume, Unit::CubicFoot, -1),
+            0x22 => Self::new(Quantity::Volume, Unit::UsGallon, -1),
+            0x23 => Self::new(Quantity::Volume, Unit::UsGallon, 0),
+            0x24 => Self::new(Quantity::VolumeFlow, Unit::UsGallonPerMinute, -3),
+            0x25 => Self::new(Quantity::VolumeFlow, Unit::UsGallonPerMinute, 0),
+            0x26 => Self::new(Quantity::VolumeFlow, Unit::UsGallonPerHour, 0),
+            0x28..=0x29 => Self::new(Quantity::Power, Unit::Watt, n - 1 + 6),
+            0x2C..=0x2F => Self::new(Quantity::Frequency, Unit::Hertz, nn - 3),
+            0x30..=0x31 => Self::new(Quantity::Power, Unit::JoulePerHour, n - 1 + 9),
+            0x58..=0x5B => Self::new(Quantity::FlowTemperature, Unit::DegreeFahrenheit, nn - 3),
+            0x5C..=0x5F => Self::new(Quantity::ReturnTemperature, Unit::DegreeFahrenheit, nn - 3),
+            0x60..=0x63 => Self::new(
+                Quantity::TemperatureDifference,
+                Unit::DegreeFahrenheit,
+                nn - 3,
+            ),
+            0x64..=0x67 => Self::new(
+                Quantity::ExternalTemperature,
+                Unit::DegreeFahrenheit,
+                nn - 3,
+            ),
+            0x70..=0x73 => Self::new(Quantity::TemperatureLimit, Unit::DegreeFahrenheit, nn - 3),
+            0x74..=0x77 => Self::new(Quantity::TemperatureLimit, Unit::DegreeCelsius, nn - 3),
+            0x78..=0x7F => Self::new(Quantity::CumulativeMaximumPower, Unit::Watt, nnn - 3),
+            _ => Self::without_unit(Quantity::Unknown),
+        }
+    }
+
+    fn decode_fd(code: u8) -> Self {
+        let nn = (code & 0x03) as i8;
+        let nnnn = (code & 0x0F) as i8;
+        match code & !EXTENSION {
+            0x00..=0x03 => Self::new(Quantity::Credit, Unit::Currency, nn - 3),
+            0x04..=0x07 => Self::new(Quantity::Debit, Unit::Currency, nn - 3),
+            0x08 => Self::without_unit(Quantity::AccessNumber),
+            0x09 => Self::without_unit(Quantity::Medium),
+            0x0A => Self::without_unit(Quantity::Manufacturer),
+            0x0B => Self::without_unit(Quantity::ParameterSetIdentification),
+            0x0C => Self::without_unit(Quantity::ModelVersion),
+            0x0D => Self::without_unit(Quantity::HardwareVersion),
+            0x0E => Self::without_unit(Quantity::FirmwareVersion),
+            0x0F => Self::without_unit(Quantity::SoftwareVersion),
+            0x10 => Self::without_unit(Quantity::CustomerLocation),
+            0x11 => Self::without_unit(Quantity::Customer),
+            0x12 => Self::without_unit(Quantity::AccessCodeUser),
+            0x13 => Self::without_unit(Quantity::AccessCodeOperator),
+            0x14 => Self::without_unit(Quantity::AccessCodeSystemOperator),
+            0x15 => Self::without_unit(Quantity::AccessCodeDeveloper),
+            0x16 => Self::without_unit(Quantity::Password),
+            0x17 => Self::without_unit(Quantity::ErrorFlags),
+            0x18 => Self::without_unit(Quantity::ErrorMask),
+            0x1A => Self::without_unit(Quantity::DigitalOutput),
+            0x1B => Self::without_unit(Quantity::DigitalInput),
+            0x1C => Self::new(Quantity::Baudrate, Unit::Baud, 0),
+            0x1D => Self::new(Quantity::ResponseDelayTime, Unit::BitTimes, 0),
+            0x1E => Self::without_unit(Quantity::Retry),
+            0x20 => Self::without_unit(Quantity::FirstStorageNumber),
+            0x21 => Self::without_unit(Quantity::LastStorageNumber),
+            0x22 => Self::without_unit(Quantity::StorageBlockSize),
+            0x24..=0x27 => Self::new(Quantity::StorageInterval, time_unit(code), 0),
+            0x28 => Self::new(Quantity::StorageInterval, Unit::Month, 0),
+            0x29 => Self::new(Quantity::StorageInterval, Unit::Year, 0),
+            0x2C..=0x2F => Self::new(Quantity::DurationSinceLastReadout, time_unit(code), 0),
+            0x30 => Self::without_unit(Quantity::TariffStart),
+            0x31..=0x33 => Self::new(Quantity::TariffDuration, time_unit(code), 0),
+            0x34..=0x37 => Self::new(Quantity::TariffPeriod, time_unit(code), 0),
+            0x38 => Self::new(Quantity::TariffPeriod, Unit::Month, 0),
+            0x39 => Self::new(Quantity::TariffPeriod, Unit::Year, 0),
+            0x3A => Self::without_unit(Quantity::Dimensionless),
+            0x40..=0x4F => Self::new(Quantity::Voltage, Unit::Volt, nnnn - 9),
+            0x50..=0x5F => Self::new(Quantity::Current, Unit::Ampere, nnnn - 12),
+            0x60 => Self::without_unit(Quantity::ResetCounter),
+            0x61 => Self::without_unit(Quantity::CumulationCounter),
+            0x62 => Self::without_unit(Quantity::ControlSignal),
+            0x63 => Self::without_unit(Quantity::DayOfWeek),
+            0x64 => Self::without_unit(Quantity::WeekNumber),
+            0x65 => Self::without_unit(Quantity::DayChangeTimePoint),
+            0x66 => Self::without_unit(Quantity::ParameterActivationState),
+            0x67 => Self::without_unit(Quantity::SpecialSupplierInformation),
+            0x68..=0x6B => Self::new(
+                Quantity::DurationSinceLastCumulation,
+                long_time_unit(code),
+                0,
+            ),
+            0x6C..=0x6F => Self::new(Quantity::BatteryOperatingTime, long_time_unit(code), 0),
+            0x70 => Self::without_unit(Quantity::BatteryChangeDateTime),
+            _ => Self::without_unit(Quantity::Unknown),
+        }
+    }
+}
+
+fn long_time_unit(code: u8) -> Unit {
+    match code & 0x03 {
+        0 => Unit::Hour,
+        1 => Unit::Day,
+        2 => Unit::Month,
+        _ => Unit::Year,
+    }
+}
+
+fn time_unit(vif: u8) -> Unit {
+    match vif & 0x03 {
+        0 => Unit::Second,
+        1 => Unit::Minute,
+        2 => Unit::Hour,
+        _ => Unit::Day,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            ValueInformation::decode(0x04, &[]),
+            ValueInformation::new(Quantity::Energy, Unit::WattHour, 1)
+        );
+        assert_eq!(
+            ValueInformation::decode(0x13, &[]),
+            ValueInformation::new(Quantity::Volume, Unit::CubicMetre, -3)
+        );
+        assert_eq!(
+            ValueInformation::decode(0x3B, &[]),
+            ValueInformation::new(Quantity::VolumeFlow, Unit::CubicMetrePerHour, -3)
+        );
+        assert_eq!(
+            ValueInformation::decode(0x5A, &[]),
+            ValueInformation::new(Quantity::FlowTemperature, Unit::DegreeCelsius, -1)
+        );
+        assert_eq!(
+            ValueInformation::decode(0x22, &[]),
+            ValueInformation::new(Quantity::OnTime, Unit::Hour, 0)
+        );
+        assert_eq!(
+            ValueInformation::decode(0x6D, &[]),
+            ValueInformation::without_unit(Quantity::DateTime)
+        );
+        assert_eq!(
+            ValueInformation::decode(0x6F, &[]).quantity,
+            Quantity::Unknown
+        );
+    }
+
+    #[test]
+    fn test_decode_fb() {
+        assert_eq!(
+            ValueInformation::decode(0xFB, &[0x01]),
+            ValueInformation::new(Quantity::Energy, Unit::WattHour, 6)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFB, &[0x08]),
+            ValueInformation::new(Quantity::Energy, Unit::Joule, 8)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFB, &[0x18]),
+            ValueInformation::new(Quantity::Mass, Unit::Kilogram, 5)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFB, &[0x17]),
+            ValueInformation::new(Quantity::ReactivePower, Unit::Var, 3)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFB, &[0x5A]),
+            ValueInformation::new(Quantity::FlowTemperature, Unit::DegreeFahrenheit, -1)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFB, &[0x40]).quantity,
+            Quantity::Unknown
+        );
+    }
+
+    #[test]
+    fn test_decode_fd() {
+        assert_eq!(
+            ValueInformation::decode(0xFD, &[0x17]),
+            ValueInformation::without_unit(Quantity::ErrorFlags)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFD, &[0x0E]),
+            ValueInformation::without_unit(Quantity::FirmwareVersion)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFD, &[0x02]),
+            ValueInformation::new(Quantity::Credit, Unit::Currency, -1)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFD, &[0x48]),
+            ValueInformation::new(Quantity::Voltage, Unit::Volt, -1)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFD, &[0xD9, 0x3D]),
+            ValueInformation::new(Quantity::Current, Unit::Ampere, -3)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFD, &[0x6D]),
+            ValueInformation::new(Quantity::BatteryOperatingTime, Unit::Day, 0)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFD, &[]).quantity,
+            Quantity::Unknown
+        );
+    }
+
+    #[test]
+    fn test_decode_combinable() {
+        // energy in 10 Wh, corrected by 10^-3, in forward direction
+        let information = ValueInformation::decode(0x84, &[0xBB, 0x73]);
+        assert_eq!(information.exponent, -2);
+        assert_eq!(information.direction, Some(FlowDirection::Forward));
+
+        // volume in litres per hour, with an offset of 1 litre
+        let information = ValueInformation::decode(0x93, &[0xA2, 0x7B]);
+        assert_eq!(information.per, Some(PerUnit::Hour));
+        assert_eq!(information.offset, Some(-3));
+
+        // alternate unit MWh, times 1000
+        let information = ValueInformation::decode(0xFB, &[0x81, 0x7D]);
+        assert_eq!(information.unit, Unit::WattHour);
+        assert_eq!(information.exponent, 9);
+
+        // manufacturer specific VIFEs are not interpreted
+        assert_eq!(
+            ValueInformation::decode(0x93, &[0xFF, 0x73]),
+            ValueInformation::new(Quantity::Volume, Unit::CubicMetre, -3)
+        );
+        assert_eq!(
+            ValueInformation::decode(0xFF, &[0x73]),
+            ValueInformation::without_unit(Quantity::ManufacturerSpecific)
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use super::WirelessError;
+use alloc::vec::Vec;
+
+const CI_AFL: u8 = 0x90;
+
+/// The FCL-field of the authentication and fragmentation layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FragmentControl(u16);
+
+impl FragmentControl {
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Number of the fragment, counting up within a message.
+    pub const fn fragment_id(self) -> u8 {
+        (self.0 & 0xFF) as u8
+    }
+
+    /// More fragments of the message follow this one.
+    pub const fn more_fragments(self) -> bool {
+        self.0 & 0x4000 != 0
+    }
+
+    const fn message_control_present(self) -> bool {
+        self.0 & 0x2000 != 0
+    }
+
+    const fn message_length_present(self) -> bool {
+        self.0 & 0x1000 != 0
+    }
+
+    const fn message_counter_present(self) -> bool {
+        self.0 & 0x0800 != 0
+    }
+
+    const fn mac_present(self) -> bool {
+        self.0 & 0x0400 != 0
+    }
+
+    const fn key_information_present(self) -> bool {
+        self.0 & 0x0200 != 0
+    }
+}
+
+impl From<u16> for FragmentControl {
+    fn from(bits: u16) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<FragmentControl> for u16 {
+    fn from(fcl: FragmentControl) -> Self {
+        fcl.0
+    }
+}
+
+/// The MCL-field of the authentication and fragmentation layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageControl(u8);
+
+impl MessageControl {
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// The message length is covered by the MAC.
+    pub const fn message_length_authenticated(self) -> bool {
+        self.0 & 0x40 != 0
+    }
+
+    /// The message counter is covered by the MAC.
+    pub const fn message_counter_authenticated(self) -> bool {
+        self.0 & 0x20 != 0
+    }
+
+    /// The key information is covered by the MAC.
+    pub const fn key_information_authenticated(self) -> bool {
+        self.0 & 0x10 != 0
+    }
+
+    pub const fn authentication_type(self) -> u8 {
+        self.0 & 0x0F
+    }
+
+    /// Length of the MAC for the authentication type, `None` for reserved types.
+    pub const fn mac_length(self) -> Option<usize> {
+        match self.authentication_type() {
+            // AES-CMAC-128 truncated to 2, 4, 8, 12 and 16 bytes
+            3 => Some(2),
+            4 => Some(4),
+            5 => Some(8),
+            6 => Some(12),
+            7 => Some(16),
+            // AES-GMAC-128
+            8 => Some(12),
+            _ => None,
+        }
+    }
+}
+
+impl From<u8> for MessageControl {
+    fn from(bits: u8) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<MessageControl> for u8 {
+    fn from(mcl: MessageControl) -> Self {
+        mcl.0
+    }
+}
+
+/// Authentication and fragmentation layer (EN 13757-7) introduced by CI 0x90, carrying the
+/// message counter and MAC of messages in security mode 7 and splitting long messages into
+/// fragments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticationLayer {
+    pub fragment_control: FragmentControl,
+    pub message_control: Option<MessageControl>,
+    pub key_information: Option<u16>,
+    pub message_counter: Option<u32>,
+    pub mac: Option<Vec<u8>>,
+    /// Length of the whole message following the layer, over all fragments.
+    pub message_length: Option<u16>,
+}
+
+impl AuthenticationLayer {
+    /// Whether `control_information` introduces an authentication and fragmentation layer.
+    pub fn is_authentication_layer(control_information: u8) -> bool {
+        control_information == CI_AFL
+    }
+
+    /// Parses the layer introduced by `control_information` at the start of `data`, returning
+    /// it together with the fragment of the message that follows.
+    pub fn parse(control_information: u8, data: &[u8]) -> Result<(Self, &[u8]), WirelessError> {
+        if control_information != CI_AFL {
+            return Err(WirelessError::UnsupportedControlInformation(
+                control_information,
+            ));
+        }
+        let (&length, data) = data.split_first().ok_or(WirelessError::Truncated)?;
+        if data.len() < usize::from(length) {
+            return Err(WirelessError::Truncated);
+        }
+        let (mut header, payload) = data.split_at(usize::from(length));
+
+        let mut take = |n: usize| -> Result<&[u8], WirelessError> {
+            if header.len() < n {
+                return Err(WirelessError::Truncated);
+            }
+            let (field, rest) = header.split_at(n);
+            header = rest;
+            Ok(field)
+        };
+        let fcl = take(2)?;
+        let fragment_control = FragmentControl(u16::from_le_bytes([fcl[0], fcl[1]]));
+        let message_control = if fragment_control.message_control_present() {
+            Some(MessageControl(take(1)?[0]))
+        } else {
+            None
+        };
+        let key_information = if fragment_control.key_information_present() {
+            let ki = take(2)?;
+            Some(u16::from_le_bytes([ki[0], ki[1]]))
+        } else {
+            None
+        };
+        let message_counter = if fragment_control.message_counter_present() {
+            let mcr = take(4)?;
+            Some(u32::from_le_bytes([mcr[0], mcr[1], mcr[2], mcr[3]]))
+        } else {
+            None
+        };
+        let mac = if fragment_control.mac_present() {
+            let mcl = message_control.unwrap_or(MessageControl(0));
+            let length = mcl
+                .mac_length()
+                .ok_or(WirelessError::UnsupportedAuthenticationType(
+                    mcl.authentication_type(),
+                ))?;
+            Some(take(length)?.to_vec())
+        } else {
+            None
+        };
+        let message_length = if fragment_control.message_length_present() {
+            let ml = take(2)?;
+            Some(u16::from_le_bytes([ml[0], ml[1]]))
+        } else {
+            None
+        };
+
+        let afl = Self {
+            fragment_control,
+            message_control,
+            key_information,
+            message_counter,
+            mac,
+            message_length,
+        };
+        Ok((afl, payload))
+    }
+
+    /// Fields of the layer covered by the MAC, which also covers the message following it.
+    pub fn authenticated_fields(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let Some(mcl) = self.message_control else {
+            return out;
+        };
+        out.push(mcl.bits());
+        if let Some(ki) = self
+            .key_information
+            .filter(|_| mcl.key_information_authenticated())
+        {
+            out.extend_from_slice(&ki.to_le_bytes());
+        }
+        if let Some(mcr) = self
+            .message_counter
+            .filter(|_| mcl.message_counter_authenticated())
+        {
+            out.extend_from_slice(&mcr.to_le_bytes());
+        }
+        if let Some(ml) = self
+            .message_length
+            .filter(|_| mcl.message_length_authenticated())
+        {
+            out.extend_from_slice(&ml.to_le_bytes());
+        }
+        out
+    }
+}
+
+/// Reassembles a message split over several fragments, each with an authentication and
+/// fragmentation layer of its own.
+#[derive(Debug, Clone, Default)]
+pub struct FragmentAssembler {
+    layer: Option<AuthenticationLayer>,
+    message: Vec<u8>,
+}
+
+impl FragmentAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the next fragment, given as the data following CI 0x90. Returns the layer, with the
+    /// fields carried by any of the fragments, and the whole message once the last fragment is
+    /// in. Fragments have to arrive in order, a fragment out of order resets the assembler.
+    pub fn push(
+        &mut self,
+        data: &[u8],
+    ) -> Result<Option<(AuthenticationLayer, Vec<u8>)>, WirelessError> {
+        let (afl, payload) = AuthenticationLayer::parse(CI_AFL, data)?;
+        let fragment_id = afl.fragment_control.fragment_id();
+        let layer = match self.layer.take() {
+            None => afl,
+            Some(layer) if layer.fragment_control.fragment_id().wrapping_add(1) == fragment_id => {
+                AuthenticationLayer {
+                    fragment_control: afl.fragment_control,
+                    message_control: afl.message_control.or(layer.message_control),
+                    key_information: afl.key_information.or(layer.key_information),
+                    message_counter: afl.message_counter.or(layer.message_counter),
+                    mac: afl.mac.or(layer.mac),
+                    message_length: afl.message_length.or(layer.message_length),
+                }
+            }
+            Some(_) => {
+                self.message.clear();
+                return Err(WirelessError::FragmentOutOfOrder(fragment_id));
+            }
+        };
+        self.message.extend_from_slice(payload);
+
+        if layer.fragment_control.more_fragments() {
+            self.layer = Some(layer);
+            return Ok(None);
+        }
+        let message = core::mem::take(&mut self.message);
+        if let Some(length) = layer.message_length {
+            if usize::from(length) != message.len() {
+                return Err(WirelessError::MessageLengthMismatch(message.len()));
+            }
+        }
+        Ok(Some((layer, message)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_parse() {
+        // MF, MCL, MCR, MAC and ML present, CMAC truncated to 8 bytes
+        let data = [
+            0x11, 0x01, 0x7C, 0x25, 0x02, 0x01, 0x00, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66,
+            0x77, 0x88, 0x03, 0x00, 0x7A, 0x2A,
+        ];
+        let (afl, payload) = AuthenticationLayer::parse(0x90, &data).unwrap();
+        assert_eq!(payload, &[0x7A, 0x2A]);
+        assert_eq!(afl.fragment_control.fragment_id(), 1);
+        assert!(afl.fragment_control.more_fragments());
+        assert_eq!(afl.message_counter, Some(0x0102));
+        assert_eq!(afl.mac.as_deref(), Some(&data[8..16]));
+        assert_eq!(afl.message_length, Some(3));
+        assert_eq!(afl.key_information, None);
+        assert_eq!(afl.authenticated_fields(), [0x25, 0x02, 0x01, 0x00, 0x00]);
+
+        assert_eq!(
+            AuthenticationLayer::parse(0x90, &data[..10]),
+            Err(WirelessError::Truncated)
+        );
+        let mut data = data;
+        data[3] = 0x20;
+        assert_eq!(
+            AuthenticationLayer::parse(0x90, &data),
+            Err(WirelessError::UnsupportedAuthenticationType(0))
+        );
+    }
+
+    #[test]
+    fn test_reassemble() {
+        let first = [
+            0x0F, 0x01, 0x6C, 0x25, 0x02, 0x01, 0x00, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66,
+            0x77, 0x88, 0x7A, 0x2A,
+        ];
+        let second = [0x02, 0x02, 0x00, 0x00];
+        let mut assembler = FragmentAssembler::new();
+        assert_eq!(assembler.push(&first), Ok(None));
+        let (afl, message) = assembler.push(&second).unwrap().unwrap();
+        assert_eq!(message, vec![0x7A, 0x2A, 0x00]);
+        assert_eq!(afl.fragment_control.fragment_id(), 2);
+        assert_eq!(afl.message_counter, Some(0x0102));
+
+        assert_eq!(assembler.push(&first), Ok(None));
+        assert_eq!(
+            assembler.push(&first),
+            Err(WirelessError::FragmentOutOfOrder(1))
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use super::WirelessError;
+use crate::crc::calculate_crc;
+use crate::telegram::{ManufacturerId, Medium};
+
+const CI_ELL_SHORT: u8 = 0x8C;
+const CI_ELL_SESSION: u8 = 0x8D;
+const CI_ELL_ADDRESS: u8 = 0x8E;
+const CI_ELL_ADDRESS_SESSION: u8 = 0x8F;
+
+/// Extended link layer following the wireless link layer with CI 0x8C to 0x8F.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtendedLinkLayer {
+    pub communication_control: CommunicationControl,
+    pub access_number: u8,
+    /// Address of the meter when the link layer carries the address of a repeater or gateway
+    /// (CI 0x8E and 0x8F).
+    pub address: Option<DeviceAddress>,
+    /// Session number and the CRC of the payload following it (CI 0x8D and 0x8F). When the
+    /// session number announces encryption, the CRC is encrypted along with the payload.
+    pub session: Option<(SessionNumber, u16)>,
+}
+
+/// The CC-field of the extended link layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommunicationControl(u8);
+
+impl CommunicationControl {
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// The meter accepts commands after this frame.
+    pub const fn bidirectional(self) -> bool {
+        self.0 & 0x80 != 0
+    }
+
+    /// The meter wants a fast response.
+    pub const fn response_delay(self) -> bool {
+        self.0 & 0x40 != 0
+    }
+
+    pub const fn synchronized(self) -> bool {
+        self.0 & 0x20 != 0
+    }
+
+    /// The frame was repeated by a hop.
+    pub const fn hop(self) -> bool {
+        self.0 & 0x10 != 0
+    }
+
+    pub const fn priority(self) -> bool {
+        self.0 & 0x08 != 0
+    }
+
+    pub const fn accessibility(self) -> bool {
+        self.0 & 0x04 != 0
+    }
+
+    /// The frame was repeated by a repeater.
+    pub const fn repeated(self) -> bool {
+        self.0 & 0x02 != 0
+    }
+}
+
+impl From<u8> for CommunicationControl {
+    fn from(bits: u8) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<CommunicationControl> for u8 {
+    fn from(cc: CommunicationControl) -> Self {
+        cc.0
+    }
+}
+
+/// The SN-field of the extended link layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionNumber(u32);
+
+impl SessionNumber {
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Encryption mode of the payload, 0 when it is not encrypted.
+    pub const fn encryption(self) -> u8 {
+        (self.0 >> 29) as u8
+    }
+
+    /// Minutes since the start of the session.
+    pub const fn time(self) -> u32 {
+        (self.0 >> 4) & 0x01FF_FFFF
+    }
+
+    pub const fn session(self) -> u8 {
+        (self.0 & 0x0F) as u8
+    }
+}
+
+impl From<u32> for SessionNumber {
+    fn from(bits: u32) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<SessionNumber> for u32 {
+    fn from(sn: SessionNumber) -> Self {
+        sn.0
+    }
+}
+
+/// Manufacturer and address of a meter, as in the header of a wireless link layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceAddress {
+    pub manufacturer: ManufacturerId,
+    /// Identification number as 8 BCD digits, print it with `{:08X}`.
+    pub identification: u32,
+    pub version: u8,
+    pub medium: Medium,
+}
+
+impl ExtendedLinkLayer {
+    /// Whether `control_information` introduces an extended link layer.
+    pub fn is_extended_link_layer(control_information: u8) -> bool {
+        (CI_ELL_SHORT..=CI_ELL_ADDRESS_SESSION).contains(&control_information)
+    }
+
+    /// Parses the extended link layer introduced by `control_information` at the start of
+    /// `data`, returning it together with the payload that follows, which starts with the CI
+    /// field of the next layer.
+    pub fn parse(control_information: u8, data: &[u8]) -> Result<(Self, &[u8]), WirelessError> {
+        let (has_address, has_session) = match control_information {
+            CI_ELL_SHORT => (false, false),
+            CI_ELL_SESSION => (false, true),
+            CI_ELL_ADDRESS => (true, false),
+            CI_ELL_ADDRESS_SESSION => (true, true),
+            ci => return Err(WirelessError::UnsupportedControlInformation(ci)),
+        };
+        let length = 2 + if has_address { 8 } else { 0 } + if has_session { 6 } else { 0 };
+        if data.len() < length {
+            return Err(WirelessError::Truncated);
+        }
+        let (header, payload) = data.split_at(length);
+
+        let address = has_address.then(|| DeviceAddress {
+            manufacturer: u16::from_le_bytes([header[2], header[3]]).into(),
+            identification: u32::from_le_bytes([header[4], header[5], header[6], header[7]]),
+            version: header[8],
+            medium: header[9].into(),
+        });
+        let session = has_session.then(|| {
+            let sn = &header[length - 6..];
+            (
+                SessionNumber(u32::from_le_bytes([sn[0], sn[1], sn[2], sn[3]])),
+                u16::from_le_bytes([sn[4], sn[5]]),
+            )
+        });
+        let ell = Self {
+            communication_control: header[0].into(),
+            access_number: header[1],
+            address,
+            session,
+        };
+        Ok((ell, payload))
+    }
+
+    /// Checks the payload CRC against the (decrypted) payload following the extended link
+    /// layer. Variants without session number carry no CRC and always pass.
+    pub fn check_payload_crc(&self, payload: &[u8]) -> bool {
+        self.session
+            .is_none_or(|(_, crc)| calculate_crc(payload) == crc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let (ell, payload) = ExtendedLinkLayer::parse(0x8C, &[0x20, 0x55, 0x7A, 0x01]).unwrap();
+        assert!(ell.communication_control.synchronized());
+        assert_eq!(ell.access_number, 0x55);
+        assert_eq!((ell.address, ell.session), (None, None));
+        assert_eq!(payload, &[0x7A, 0x01]);
+
+        let payload = [0x7A, 0x01];
+        let [crc0, crc1] = calculate_crc(&payload).to_le_bytes();
+        let data = [
+            0x00, 0x55, 0x2C, 0x2D, 0x78, 0x56, 0x34, 0x12, 0x1B, 0x07, 0x13, 0x00, 0x00, 0x20,
+            crc0, crc1, 0x7A, 0x01,
+        ];
+        let (ell, rest) = ExtendedLinkLayer::parse(0x8F, &data).unwrap();
+        assert_eq!(rest, &payload);
+        let address = ell.address.unwrap();
+        assert_eq!(address.identification, 0x12345678);
+        assert_eq!(address.medium, Medium::Water);
+        let (session_number, _) = ell.session.unwrap();
+        assert_eq!(session_number.encryption(), 1);
+        assert_eq!(session_number.time(), 1);
+        assert_eq!(session_number.session(), 3);
+        assert!(ell.check_payload_crc(rest));
+        assert!(!ell.check_payload_crc(&[0x7A]));
+
+        assert_eq!(
+            ExtendedLinkLayer::parse(0x8D, &[0x00, 0x55]),
+            Err(WirelessError::Truncated)
+        );
+        assert_eq!(
+            ExtendedLinkLayer::parse(0x7A, &[]),
+            Err(WirelessError::UnsupportedControlInformation(0x7A))
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use super::{WirelessError, WirelessFrame, HEADER_LENGTH};
+use crate::crc::{append_block, verify_block};
+use alloc::vec::Vec;
+
+/// Data bytes in each block after the first one, which holds the L-field and the header.
+const BLOCK_LENGTH: usize = 16;
+const CRC_LENGTH: usize = 2;
+
+/// Length of the frame on air for the value of its L-field.
+fn frame_length(length_field: u8) -> usize {
+    let remaining = usize::from(length_field) - HEADER_LENGTH;
+    1 + HEADER_LENGTH + CRC_LENGTH + remaining + CRC_LENGTH * remaining.div_ceil(BLOCK_LENGTH)
+}
+
+pub(super) fn parse(bytes: &[u8]) -> Result<(usize, WirelessFrame), WirelessError> {
+    let length_field = *bytes
+        .first()
+        .ok_or(WirelessError::Incomplete { needed: 1 })?;
+    if usize::from(length_field) <= HEADER_LENGTH {
+        return Err(WirelessError::InvalidLength(length_field));
+    }
+    let length = frame_length(length_field);
+    if bytes.len() < length {
+        return Err(WirelessError::Incomplete {
+            needed: length - bytes.len(),
+        });
+    }
+
+    let first_block = 1 + HEADER_LENGTH;
+    let blocks = core::iter::once(&bytes[..first_block + CRC_LENGTH])
+        .chain(bytes[first_block + CRC_LENGTH..length].chunks(BLOCK_LENGTH + CRC_LENGTH));
+    let mut data = Vec::with_capacity(usize::from(length_field) + 1);
+    for (index, block) in blocks.enumerate() {
+        let block = verify_block(block).ok_or(WirelessError::CrcMismatch { block: index })?;
+        data.extend_from_slice(block);
+    }
+
+    let frame = WirelessFrame::from_parts(&data[1..first_block], &data[first_block..])?;
+    Ok((length, frame))
+}
+
+pub(super) fn encode(frame: &WirelessFrame) -> Result<Vec<u8>, WirelessError> {
+    let length_field = frame.length_field()?;
+    let mut out = Vec::with_capacity(frame_length(length_field));
+
+    let mut first_block = [0u8; 1 + HEADER_LENGTH];
+    first_block[0] = length_field;
+    first_block[1..].copy_from_slice(&frame.header());
+    append_block(&mut out, &first_block);
+
+    let mut remaining = Vec::with_capacity(1 + frame.data.len());
+    remaining.push(frame.control_information);
+    remaining.extend_from_slice(&frame.data);
+    for block in remaining.chunks(BLOCK_LENGTH) {
+        append_block(&mut out, block);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::{ManufacturerId, Medium};
+
+    fn frame(data_length: usize) -> WirelessFrame {
+        WirelessFrame {
+            control: 0x44,
+            manufacturer: ManufacturerId::from(0x2D2C),
+            identification: 0x12345678,
+            version: 0x1B,
+            medium: Medium::Water,
+            control_information: 0x7A,
+            data: (0..data_length as u8).collect(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for data_length in [0, 15, 16, 40] {
+            let frame = frame(data_length);
+            let bytes = frame.to_format_a().unwrap();
+            assert_eq!(usize::from(bytes[0]), 10 + data_length);
+            assert_eq!(
+                WirelessFrame::parse_format_a(&bytes),
+                Ok((bytes.len(), frame))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let mut bytes = frame(20).to_format_a().unwrap();
+        // L C M M A A A A A A CRC CRC, then 16 bytes and their CRC
+        assert_eq!(bytes.len(), 12 + 16 + 2 + 5 + 2);
+        assert_eq!(
+            WirelessFrame::parse_format_a(&bytes[..30]),
+            Err(WirelessError::Incomplete { needed: 7 })
+        );
+
+        bytes[20] ^= 0x01;
+        assert_eq!(
+            WirelessFrame::parse_format_a(&bytes),
+            Err(WirelessError::CrcMismatch { block: 1 })
+        );
+
+        assert_eq!(
+            WirelessFrame::parse_format_a(&[0x09]),
+            Err(WirelessError::InvalidLength(0x09))
+        );
+    }
+}
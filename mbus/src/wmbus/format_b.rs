@@ -0,0 +1,123 @@
+use super::{WirelessError, WirelessFrame, HEADER_LENGTH};
+use crate::crc::{calculate_crc, verify_block};
+use alloc::vec::Vec;
+
+/// Bytes covered by the first CRC: the L-field, the header and the second block.
+const FIRST_CRC_OFFSET: usize = 126;
+const CRC_LENGTH: usize = 2;
+
+pub(super) fn parse(bytes: &[u8]) -> Result<(usize, WirelessFrame), WirelessError> {
+    let length_field = *bytes
+        .first()
+        .ok_or(WirelessError::Incomplete { needed: 1 })?;
+    // unlike Format A, the L-field counts the CRCs too
+    if usize::from(length_field) < HEADER_LENGTH + 1 + CRC_LENGTH {
+        return Err(WirelessError::InvalidLength(length_field));
+    }
+    let length = 1 + usize::from(length_field);
+    if bytes.len() < length {
+        return Err(WirelessError::Incomplete {
+            needed: length - bytes.len(),
+        });
+    }
+
+    let blocks = if length <= FIRST_CRC_OFFSET + CRC_LENGTH {
+        [&bytes[..length], &[][..]]
+    } else {
+        bytes[..length]
+            .split_at(FIRST_CRC_OFFSET + CRC_LENGTH)
+            .into()
+    };
+    let mut data = Vec::with_capacity(length);
+    for (index, block) in blocks.into_iter().enumerate() {
+        if block.is_empty() {
+            continue;
+        }
+        if block.len() <= CRC_LENGTH {
+            return Err(WirelessError::InvalidLength(length_field));
+        }
+        let block = verify_block(block).ok_or(WirelessError::CrcMismatch { block: index })?;
+        data.extend_from_slice(block);
+    }
+
+    let frame = WirelessFrame::from_parts(&data[1..=HEADER_LENGTH], &data[HEADER_LENGTH + 1..])?;
+    Ok((length, frame))
+}
+
+pub(super) fn encode(frame: &WirelessFrame) -> Result<Vec<u8>, WirelessError> {
+    let content_length = 1 + usize::from(frame.length_field()?);
+    let crcs = if content_length <= FIRST_CRC_OFFSET {
+        1
+    } else {
+        2
+    };
+    let length_field = u8::try_from(content_length - 1 + crcs * CRC_LENGTH)
+        .map_err(|_| WirelessError::DataTooLong(frame.data.len()))?;
+
+    let mut out = Vec::with_capacity(content_length + crcs * CRC_LENGTH);
+    out.push(length_field);
+    out.extend_from_slice(&frame.header());
+    out.push(frame.control_information);
+    out.extend_from_slice(&frame.data);
+    if crcs == 1 {
+        let crc = calculate_crc(&out);
+        out.extend_from_slice(&crc.to_be_bytes());
+    } else {
+        let crc = calculate_crc(&out[..FIRST_CRC_OFFSET]);
+        let rest = out.split_off(FIRST_CRC_OFFSET);
+        out.extend_from_slice(&crc.to_be_bytes());
+        out.extend_from_slice(&rest);
+        out.extend_from_slice(&calculate_crc(&rest).to_be_bytes());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::{ManufacturerId, Medium};
+    use crate::wmbus::FrameFormat;
+
+    fn frame(data_length: usize) -> WirelessFrame {
+        WirelessFrame {
+            control: 0x44,
+            manufacturer: ManufacturerId::from(0x2D2C),
+            identification: 0x12345678,
+            version: 0x1B,
+            medium: Medium::Water,
+            control_information: 0x7A,
+            data: (0..data_length).map(|i| i as u8).collect(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for data_length in [0, 20, 115, 116, 200] {
+            let frame = frame(data_length);
+            let bytes = frame.to_format_b().unwrap();
+            assert_eq!(usize::from(bytes[0]) + 1, bytes.len());
+            assert_eq!(
+                WirelessFrame::parse_format_b(&bytes),
+                Ok((bytes.len(), frame.clone()))
+            );
+            assert_eq!(
+                WirelessFrame::parse(&bytes),
+                Ok((bytes.len(), frame, FrameFormat::B))
+            );
+        }
+        assert_eq!(
+            frame(250).to_format_b(),
+            Err(WirelessError::DataTooLong(250))
+        );
+    }
+
+    #[test]
+    fn test_crc_mismatch() {
+        let mut bytes = frame(200).to_format_b().unwrap();
+        bytes[150] ^= 0x01;
+        assert_eq!(
+            WirelessFrame::parse_format_b(&bytes),
+            Err(WirelessError::CrcMismatch { block: 1 })
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Link layer of wireless M-Bus (EN 13757-4): the frames received from and sent to radio
+//! transceivers, with their data blocks checked and stripped of their CRCs.
+
+use crate::telegram::{ManufacturerId, Medium};
+use alloc::vec::Vec;
+use core::fmt;
+
+mod afl;
+mod ell;
+mod format_a;
+mod format_b;
+
+pub use afl::{AuthenticationLayer, FragmentAssembler, FragmentControl, MessageControl};
+pub use ell::{CommunicationControl, DeviceAddress, ExtendedLinkLayer, SessionNumber};
+
+/// Bytes covered by the L-field before the CI field: C, M (2 bytes) and A (6 bytes).
+const HEADER_LENGTH: usize = 9;
+
+/// How the data of a frame is split into CRC protected blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// A CRC after the header and after every 16 bytes of data.
+    A,
+    /// A single CRC over the whole frame, or two for frames longer than 128 bytes.
+    B,
+}
+
+/// A wireless M-Bus frame with its CRCs removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WirelessFrame {
+    pub control: u8,
+    pub manufacturer: ManufacturerId,
+    /// Identification number as 8 BCD digits, print it with `{:08X}`.
+    pub identification: u32,
+    pub version: u8,
+    pub medium: Medium,
+    pub control_information: u8,
+    /// The data following the CI field.
+    pub data: Vec<u8>,
+}
+
+impl WirelessFrame {
+    /// Parses a Format A frame, in which the first block holds the header and every following
+    /// block of at most 16 bytes carries its own CRC. Returns the number of bytes read along with
+    /// the frame.
+    pub fn parse_format_a(bytes: &[u8]) -> Result<(usize, Self), WirelessError> {
+        format_a::parse(bytes)
+    }
+
+    /// Encodes the frame in Format A, see [`WirelessFrame::parse_format_a`].
+    pub fn to_format_a(&self) -> Result<Vec<u8>, WirelessError> {
+        format_a::encode(self)
+    }
+
+    /// Parses a Format B frame, in which the L-field includes the CRCs and a single CRC covers
+    /// the first 126 bytes, followed by a second CRC for the rest of longer frames.
+    pub fn parse_format_b(bytes: &[u8]) -> Result<(usize, Self), WirelessError> {
+        format_b::parse(bytes)
+    }
+
+    /// Encodes the frame in Format B, see [`WirelessFrame::parse_format_b`].
+    pub fn to_format_b(&self) -> Result<Vec<u8>, WirelessError> {
+        format_b::encode(self)
+    }
+
+    /// Parses a frame of either format, telling them apart by their CRCs. Errors are those of
+    /// Format A when neither matches.
+    pub fn parse(bytes: &[u8]) -> Result<(usize, Self, FrameFormat), WirelessError> {
+        match format_a::parse(bytes) {
+            Ok((length, frame)) => Ok((length, frame, FrameFormat::A)),
+            Err(err) => format_b::parse(bytes)
+                .map(|(length, frame)| (length, frame, FrameFormat::B))
+                .map_err(|_| err),
+        }
+    }
+
+    pub fn encode(&self, format: FrameFormat) -> Result<Vec<u8>, WirelessError> {
+        match format {
+            FrameFormat::A => self.to_format_a(),
+            FrameFormat::B => self.to_format_b(),
+        }
+    }
+
+    /// Value of the L-field in Format A: the length of the frame without the L-field and the
+    /// CRCs.
+    pub fn length_field(&self) -> Result<u8, WirelessError> {
+        let length = HEADER_LENGTH + 1 + self.data.len();
+        u8::try_from(length).map_err(|_| WirelessError::DataTooLong(self.data.len()))
+    }
+
+    fn header(&self) -> [u8; HEADER_LENGTH] {
+        let [m0, m1] = u16::from(self.manufacturer).to_le_bytes();
+        let [a0, a1, a2, a3] = self.identification.to_le_bytes();
+        [
+            self.control,
+            m0,
+            m1,
+            a0,
+            a1,
+            a2,
+            a3,
+            self.version,
+            self.medium.into(),
+        ]
+    }
+
+    /// Builds a frame from the header fields and the data from the CI field onwards.
+    fn from_parts(header: &[u8], data: &[u8]) -> Result<Self, WirelessError> {
+        let (&control_information, data) =
+            data.split_first().ok_or(WirelessError::InvalidLength(0))?;
+        Ok(Self {
+            control: header[0],
+            manufacturer: u16::from_le_bytes([header[1], header[2]]).into(),
+            identification: u32::from_le_bytes([header[3], header[4], header[5], header[6]]),
+            version: header[7],
+            medium: header[8].into(),
+            control_information,
+            data: data.to_vec(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WirelessError {
+    /// At least `needed` more bytes are required to parse the frame.
+    Incomplete { needed: usize },
+    /// The L-field is too small to hold the header and the CI field.
+    InvalidLength(u8),
+    /// The CRC of a block, counted from zero, does not match its contents.
+    CrcMismatch { block: usize },
+    /// The data does not fit in a frame.
+    DataTooLong(usize),
+    /// The CI field does not introduce the layer that was asked for.
+    UnsupportedControlInformation(u8),
+    /// The data ends in the middle of a layer header.
+    Truncated,
+    /// The authentication type of a MAC that is present is reserved.
+    UnsupportedAuthenticationType(u8),
+    /// A fragment with this ID does not follow the previous one.
+    FragmentOutOfOrder(u8),
+    /// The reassembled message, of this length, does not have the announced length.
+    MessageLengthMismatch(usize),
+}
+
+impl fmt::Display for WirelessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WirelessError::Incomplete { needed } => {
+                write!(f, "incomplete frame, {} more bytes needed", needed)
+            }
+            WirelessError::InvalidLength(length) => write!(f, "invalid length field {}", length),
+            WirelessError::CrcMismatch { block } => write!(f, "CRC mismatch in block {}", block),
+            WirelessError::DataTooLong(length) => {
+                write!(f, "{} data bytes do not fit in a frame", length)
+            }
+            WirelessError::UnsupportedControlInformation(ci) => {
+                write!(f, "unsupported control information 0x{:02X}", ci)
+            }
+            WirelessError::Truncated => write!(f, "data ends within a layer header"),
+            WirelessError::UnsupportedAuthenticationType(at) => {
+                write!(f, "unsupported authentication type {}", at)
+            }
+            WirelessError::FragmentOutOfOrder(id) => write!(f, "fragment {} is out of order", id),
+            WirelessError::MessageLengthMismatch(length) => {
+                write!(
+                    f,
+                    "message length {} does not match the announced length",
+                    length
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WirelessError {}
@@ -0,0 +1,208 @@
+//! Decodes the test frames of libmbus and compares the records with the XML libmbus produces
+//! for them. The frames in `tests/libmbus` are checked by default; point `LIBMBUS_TEST_FRAMES`
+//! at the `test/test-frames` directory of a libmbus checkout to run the whole corpus:
+//!
+//! ```sh
+//! LIBMBUS_TEST_FRAMES=../libmbus/test/test-frames cargo test -p mbus --features conformance
+//! ```
+
+use mbus::telegram::{DataRecord, Date, FunctionField, Telegram, TelegramError, Time, Value};
+use mbus::Frame;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A record as described by the XML of libmbus.
+#[derive(Debug, Default)]
+struct ExpectedRecord {
+    function: String,
+    storage_number: u64,
+    tariff: u32,
+    device: u16,
+    value: String,
+}
+
+/// The content of each `<tag>` in `xml`, which libmbus writes without attributes but for the
+/// id of data records.
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut out = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        // skip `<DataRecords>` when looking for `<DataRecord`
+        if !rest.starts_with(['>', ' ']) {
+            continue;
+        }
+        let Some(content) = rest.find('>').map(|end| &rest[end + 1..]) else {
+            break;
+        };
+        let Some(end) = content.find(&close) else {
+            break;
+        };
+        out.push(content[..end].trim());
+        rest = &content[end + close.len()..];
+    }
+    out
+}
+
+fn element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    elements(xml, tag).into_iter().next()
+}
+
+fn expected_records(xml: &str) -> Vec<ExpectedRecord> {
+    elements(xml, "DataRecord")
+        .into_iter()
+        .map(|record| {
+            let number = |tag| element(record, tag).map_or(Ok(0), str::parse);
+            ExpectedRecord {
+                function: element(record, "Function").unwrap_or_default().into(),
+                storage_number: number("StorageNumber").unwrap_or(u64::MAX),
+                tariff: number("Tariff").map_or(u32::MAX, |n| n as u32),
+                device: number("Device").map_or(u16::MAX, |n| n as u16),
+                value: element(record, "Value").unwrap_or_default().into(),
+            }
+        })
+        .collect()
+}
+
+/// Name of the function field as libmbus prints it.
+fn function_name(function: FunctionField) -> &'static str {
+    match function {
+        FunctionField::Instantaneous => "Instantaneous value",
+        FunctionField::Maximum => "Maximum value",
+        FunctionField::Minimum => "Minimum value",
+        FunctionField::DuringError => "Value during error state",
+    }
+}
+
+/// Formats like libmbus does, e.g. `2024-06-30` and `12:45:00`.
+fn date_string(date: Date) -> String {
+    format!("{:04}-{:02}-{:02}", date.year, date.month, date.day)
+}
+
+fn time_string(time: Time) -> String {
+    format!("{:02}:{:02}:{:02}", time.hour, time.minute, time.second)
+}
+
+fn values_match(record: &DataRecord, expected: &str) -> bool {
+    match record.value() {
+        Ok(Value::Text(text)) => text == expected,
+        Ok(Value::Date(date)) => date_string(date) == expected,
+        Ok(Value::Time(time)) => time_string(time) == expected,
+        Ok(Value::DateTime(date_time)) => {
+            format!(
+                "{}T{}",
+                date_string(date_time.date),
+                time_string(date_time.time)
+            ) == expected
+        }
+        // libmbus formats binary data differently from version to version
+        Ok(Value::Binary(_)) => true,
+        Ok(value) => match (value.as_f64(), expected.parse::<f64>()) {
+            (Some(actual), Ok(expected)) => {
+                (actual - expected).abs() <= 1e-6 * expected.abs().max(1.0)
+            }
+            _ => false,
+        },
+        Err(_) => false,
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    hex.split_whitespace()
+        .map(|b| u8::from_str_radix(b, 16).map_err(|err| format!("{:?}: {}", b, err)))
+        .collect()
+}
+
+/// Compares the decoded frame in `hex` with `xml`, returning the differences.
+fn compare(hex: &str, xml: &str) -> Result<Vec<String>, String> {
+    let frame = Frame::from_bytes(&parse_hex(hex)?).map_err(|err| err.to_string())?;
+    let telegram = match Telegram::from_frame(&frame) {
+        Ok(telegram) => telegram,
+        // fixed data structures and the like have no records to compare
+        Err(TelegramError::UnsupportedControlInformation(_)) => return Ok(Vec::new()),
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut differences = Vec::new();
+    let header = &telegram.header;
+    let id = format!("{:08X}", header.identification);
+    if element(xml, "Id").is_some_and(|expected| expected != id) {
+        differences.push(format!("id {}", id));
+    }
+    let manufacturer = header.manufacturer.to_string();
+    if element(xml, "Manufacturer").is_some_and(|expected| expected != manufacturer) {
+        differences.push(format!("manufacturer {}", manufacturer));
+    }
+
+    let expected = expected_records(xml);
+    if expected.len() != telegram.records.len() {
+        differences.push(format!(
+            "{} records instead of {}",
+            telegram.records.len(),
+            expected.len()
+        ));
+    }
+    for (n, (record, expected)) in telegram.records.iter().zip(&expected).enumerate() {
+        let header = &record.header;
+        let function = function_name(header.function);
+        if expected.function != function
+            || expected.storage_number != header.storage_number
+            || expected.tariff != header.tariff
+            || expected.device != header.subunit
+        {
+            differences.push(format!(
+                "record {}: {} storage {} tariff {} subunit {}, expected {:?}",
+                n, function, header.storage_number, header.tariff, header.subunit, expected
+            ));
+        }
+        if !values_match(record, &expected.value) {
+            differences.push(format!(
+                "record {}: value {:?}, expected {:?}",
+                n,
+                record.value(),
+                expected.value
+            ));
+        }
+    }
+    Ok(differences)
+}
+
+fn test_frames_dir() -> PathBuf {
+    std::env::var_os("LIBMBUS_TEST_FRAMES").map_or_else(
+        || Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/libmbus"),
+        PathBuf::from,
+    )
+}
+
+#[test]
+fn test_libmbus_frames() {
+    let dir = test_frames_dir();
+    let mut paths = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("{}: {}", dir.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "hex"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert!(!paths.is_empty(), "no test frames in {}", dir.display());
+
+    let mut failures = Vec::new();
+    for path in &paths {
+        // frames libmbus fails to decode come without XML
+        let Ok(xml) = fs::read_to_string(path.with_extension("xml")) else {
+            continue;
+        };
+        let hex = fs::read_to_string(path).unwrap();
+        let name = path.file_name().unwrap().to_string_lossy();
+        match compare(&hex, &xml) {
+            Ok(differences) => failures.extend(
+                differences
+                    .into_iter()
+                    .map(|difference| format!("{}: {}", name, difference)),
+            ),
+            Err(err) => failures.push(format!("{}: {}", name, err)),
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}